@@ -0,0 +1,3 @@
+ALTER TABLE guild_configs
+ ADD COLUMN appeal_channel BIGINT,
+ ADD COLUMN appeal_enabled BOOLEAN DEFAULT TRUE NOT NULL;
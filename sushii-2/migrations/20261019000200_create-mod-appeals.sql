@@ -0,0 +1,12 @@
+CREATE TABLE mod_appeals (
+    guild_id    BIGINT    NOT NULL,
+    case_id     BIGINT    NOT NULL,
+    user_id     BIGINT    NOT NULL,
+    reason      TEXT      NOT NULL,
+    status      TEXT      NOT NULL,
+    created_at  TIMESTAMP NOT NULL,
+    msg_id      BIGINT,
+    decided_by  BIGINT,
+    PRIMARY KEY (guild_id, case_id),
+    FOREIGN KEY (guild_id, case_id) REFERENCES mod_logs (guild_id, case_id) ON DELETE CASCADE
+);
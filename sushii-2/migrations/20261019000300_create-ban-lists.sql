@@ -0,0 +1,27 @@
+-- Guilds that are allowed to subscribe to a guild's bans
+CREATE TABLE ban_list_trusts (
+    guild_id         BIGINT    NOT NULL,
+    trusted_guild_id BIGINT    NOT NULL,
+    created_at       TIMESTAMP NOT NULL,
+    PRIMARY KEY (guild_id, trusted_guild_id)
+);
+
+CREATE TABLE ban_list_subscriptions (
+    guild_id        BIGINT  NOT NULL,
+    source_guild_id BIGINT  NOT NULL,
+    -- Ban automatically, otherwise only send an alert
+    auto_ban        BOOLEAN NOT NULL,
+    channel_id      BIGINT  NOT NULL,
+    PRIMARY KEY (guild_id, source_guild_id),
+    FOREIGN KEY (source_guild_id, guild_id) REFERENCES ban_list_trusts (guild_id, trusted_guild_id) ON DELETE CASCADE
+);
+
+-- Bans that were added from another guild's ban list
+CREATE TABLE shared_bans (
+    guild_id        BIGINT NOT NULL,
+    user_id         BIGINT NOT NULL,
+    source_guild_id BIGINT NOT NULL,
+    source_case_id  BIGINT NOT NULL,
+    case_id         BIGINT,
+    PRIMARY KEY (guild_id, user_id)
+);
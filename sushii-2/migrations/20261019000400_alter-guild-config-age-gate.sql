@@ -0,0 +1,6 @@
+ALTER TABLE guild_configs
+ ADD COLUMN age_gate_duration   BIGINT,
+ ADD COLUMN age_gate_quarantine BOOLEAN DEFAULT FALSE NOT NULL,
+ ADD COLUMN age_gate_dm_text    TEXT,
+ ADD COLUMN age_gate_dm_enabled BOOLEAN DEFAULT TRUE NOT NULL,
+ ADD COLUMN age_gate_allowlist  BIGINT [];
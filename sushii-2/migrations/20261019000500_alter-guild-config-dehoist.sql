@@ -0,0 +1,3 @@
+ALTER TABLE guild_configs
+ ADD COLUMN dehoist_enabled  BOOLEAN DEFAULT FALSE NOT NULL,
+ ADD COLUMN dehoist_fallback TEXT;
@@ -0,0 +1,7 @@
+ALTER TABLE guild_configs
+ ADD COLUMN log_msg_retention_count INTEGER,
+ ADD COLUMN log_msg_retention_secs  BIGINT;
+
+-- Pruning is done by retention age and count per channel
+CREATE INDEX messages_channel_id_created_idx
+    ON messages (channel_id, created DESC);
@@ -0,0 +1,3 @@
+ALTER TABLE guild_configs
+ ADD COLUMN log_server         BIGINT,
+ ADD COLUMN log_server_enabled BOOLEAN DEFAULT TRUE NOT NULL;
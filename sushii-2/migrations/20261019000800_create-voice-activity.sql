@@ -0,0 +1,13 @@
+ALTER TABLE guild_configs
+ ADD COLUMN log_voice         BIGINT,
+ ADD COLUMN log_voice_enabled BOOLEAN DEFAULT TRUE NOT NULL;
+
+CREATE TABLE voice_activity (
+    guild_id      BIGINT    NOT NULL,
+    user_id       BIGINT    NOT NULL,
+    -- Total seconds of finished voice sessions
+    total_secs    BIGINT    NOT NULL DEFAULT 0,
+    -- Start of the current session, null if not in a voice channel
+    session_start TIMESTAMP,
+    PRIMARY KEY (guild_id, user_id)
+);
@@ -0,0 +1,3 @@
+ALTER TABLE guild_configs
+ ADD COLUMN log_msg_ignore_channels BIGINT[],
+ ADD COLUMN log_msg_ignore_roles    BIGINT[];
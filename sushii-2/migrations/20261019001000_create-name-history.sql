@@ -0,0 +1,10 @@
+CREATE TABLE name_history (
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    BIGINT    NOT NULL,
+    -- Guild for nicknames, null for usernames
+    guild_id   BIGINT,
+    name       TEXT      NOT NULL,
+    changed_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX name_history_user_id_idx ON name_history (user_id, changed_at DESC);
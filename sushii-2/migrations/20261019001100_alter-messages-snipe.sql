@@ -0,0 +1,7 @@
+ALTER TABLE guild_configs
+ ADD COLUMN snipe_enabled BOOLEAN DEFAULT FALSE NOT NULL;
+
+ALTER TABLE messages
+ ADD COLUMN deleted_at   TIMESTAMP,
+ ADD COLUMN edited_at    TIMESTAMP,
+ ADD COLUMN prev_content TEXT;
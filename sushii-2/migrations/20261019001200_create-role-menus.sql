@@ -0,0 +1,9 @@
+CREATE TABLE role_menus (
+    message_id BIGINT PRIMARY KEY,
+    guild_id   BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL,
+    -- Name of the group in the guild's role config
+    group_name TEXT   NOT NULL
+);
+
+CREATE INDEX role_menus_guild_id_idx ON role_menus (guild_id);
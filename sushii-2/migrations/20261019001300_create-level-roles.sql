@@ -0,0 +1,10 @@
+ALTER TABLE guild_configs
+    -- Keep lower level role rewards, otherwise replaced by the highest
+    ADD COLUMN level_role_stack BOOLEAN DEFAULT TRUE NOT NULL;
+
+CREATE TABLE level_roles (
+    guild_id BIGINT NOT NULL,
+    role_id  BIGINT NOT NULL,
+    level    BIGINT NOT NULL,
+    PRIMARY KEY (guild_id, role_id)
+);
@@ -0,0 +1,10 @@
+ALTER TABLE guild_configs
+    ADD COLUMN level_up_msg     TEXT,
+    ADD COLUMN level_up_enabled BOOLEAN DEFAULT FALSE NOT NULL,
+    -- Channel to send level up messages in, same channel if not set
+    ADD COLUMN level_up_channel BIGINT,
+    -- DM members instead of sending in a channel
+    ADD COLUMN level_up_dm      BOOLEAN DEFAULT FALSE NOT NULL;
+
+ALTER TABLE users
+    ADD COLUMN level_up_opt_out BOOLEAN DEFAULT FALSE NOT NULL;
@@ -0,0 +1,11 @@
+ALTER TABLE guild_configs
+    -- XP given per message, defaults to 5 if not set
+    ADD COLUMN xp_min                 INTEGER,
+    ADD COLUMN xp_max                 INTEGER,
+    -- Seconds between messages that earn XP, defaults to 60 if not set
+    ADD COLUMN xp_cooldown_secs       BIGINT,
+    ADD COLUMN xp_ignore_channels     BIGINT[],
+    ADD COLUMN xp_ignore_roles        BIGINT[],
+    -- Maps of channel or role IDs to XP multipliers
+    ADD COLUMN xp_channel_multipliers JSONB,
+    ADD COLUMN xp_role_multipliers    JSONB;
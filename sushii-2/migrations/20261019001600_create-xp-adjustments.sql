@@ -0,0 +1,15 @@
+-- Audit log of XP changed by moderators
+CREATE TABLE xp_adjustments (
+    id           BIGSERIAL PRIMARY KEY,
+    guild_id     BIGINT    NOT NULL,
+    -- User whose XP was changed, null if all users in the guild were reset
+    user_id      BIGINT,
+    moderator_id BIGINT    NOT NULL,
+    -- add, remove, set or reset
+    action       TEXT      NOT NULL,
+    old_xp       BIGINT,
+    new_xp       BIGINT,
+    created_at   TIMESTAMP NOT NULL
+);
+
+CREATE INDEX xp_adjustments_guild_id_idx ON xp_adjustments (guild_id, created_at DESC);
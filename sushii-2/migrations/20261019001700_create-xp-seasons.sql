@@ -0,0 +1,20 @@
+-- Ended XP seasons of a guild
+CREATE TABLE xp_seasons (
+    id       BIGSERIAL PRIMARY KEY,
+    guild_id BIGINT    NOT NULL,
+    name     TEXT      NOT NULL,
+    ended_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX xp_seasons_guild_id_idx ON xp_seasons (guild_id, ended_at DESC);
+
+-- Leaderboard snapshot when a season ended
+CREATE TABLE xp_season_standings (
+    season_id BIGINT NOT NULL REFERENCES xp_seasons (id) ON DELETE CASCADE,
+    user_id   BIGINT NOT NULL,
+    rank      BIGINT NOT NULL,
+    xp        BIGINT NOT NULL,
+    PRIMARY KEY (season_id, user_id)
+);
+
+CREATE INDEX xp_season_standings_xp_idx ON xp_season_standings (season_id, xp DESC, user_id DESC);
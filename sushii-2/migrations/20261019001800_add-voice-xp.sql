@@ -0,0 +1,8 @@
+ALTER TABLE guild_configs
+    ADD COLUMN voice_xp_enabled    BOOLEAN DEFAULT FALSE NOT NULL,
+    -- XP given per minute in voice channels, defaults to 2 if not set
+    ADD COLUMN voice_xp_per_minute INTEGER;
+
+-- Part of msg_all_time earned in voice channels, text XP is the difference
+ALTER TABLE user_levels
+    ADD COLUMN voice_xp BIGINT DEFAULT 0 NOT NULL;
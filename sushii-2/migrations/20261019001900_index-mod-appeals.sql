@@ -0,0 +1,5 @@
+-- Staff reactions look up appeals by their message
+CREATE INDEX mod_appeals_msg_id_idx ON mod_appeals (msg_id);
+
+-- Pending appeals are expired by age
+CREATE INDEX mod_appeals_pending_idx ON mod_appeals (created_at) WHERE status = 'pending';
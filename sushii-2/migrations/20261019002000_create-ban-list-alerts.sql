@@ -0,0 +1,12 @@
+-- Ban alerts sent to subscribed guilds that are waiting for a staff reaction
+CREATE TABLE ban_list_alerts (
+    msg_id          BIGINT    PRIMARY KEY,
+    guild_id        BIGINT    NOT NULL,
+    channel_id      BIGINT    NOT NULL,
+    user_id         BIGINT    NOT NULL,
+    source_guild_id BIGINT    NOT NULL,
+    source_case_id  BIGINT    NOT NULL,
+    created_at      TIMESTAMP NOT NULL
+);
+
+CREATE INDEX ban_list_alerts_created_at_idx ON ban_list_alerts (created_at);
@@ -0,0 +1,4 @@
+-- Mutes that already notified the user some other way, e.g. age gate
+-- quarantines, don't send the regular mute DM
+ALTER TABLE mutes
+    ADD COLUMN skip_dm BOOLEAN DEFAULT FALSE NOT NULL;
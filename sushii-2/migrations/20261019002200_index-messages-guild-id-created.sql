@@ -0,0 +1,3 @@
+-- Saved messages are pruned per guild by age
+CREATE INDEX messages_guild_id_created_idx
+    ON messages (guild_id, created);
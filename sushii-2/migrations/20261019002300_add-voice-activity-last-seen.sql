@@ -0,0 +1,4 @@
+-- Last time an ongoing session was confirmed, sessions that ended while
+-- sushii was offline only count up to this time
+ALTER TABLE voice_activity
+    ADD COLUMN last_seen TIMESTAMP;
@@ -0,0 +1,10 @@
+-- When day, week and month XP were last reset, separate from last_msg so that
+-- XP changes outside of messages don't start a message cooldown
+ALTER TABLE user_levels
+    ADD COLUMN interval_reset TIMESTAMP;
+
+UPDATE user_levels
+   SET interval_reset = last_msg;
+
+ALTER TABLE user_levels
+    ALTER COLUMN interval_reset SET NOT NULL;
@@ -0,0 +1,145 @@
+use chrono::Duration;
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::model::moderation::appeal::{appeal_channel, submit, APPEAL_TIMEOUT};
+use crate::model::sql::{GuildConfig, ModAppeal, ModLogEntry, Mute};
+
+#[command]
+#[only_in("dms")]
+#[description("Appeal a ban or mute, instructions are included in the ban or mute DM")]
+#[usage("[server ID] [case ID] [appeal]")]
+async fn appeal(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = match args.single::<u64>() {
+        Ok(id) => GuildId(id),
+        Err(_) => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a valid server ID")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let case_id = match args.single::<u64>() {
+        Ok(id) => id,
+        Err(_) => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a valid case ID")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let reason = args.rest().trim();
+
+    if reason.is_empty() {
+        msg.channel_id
+            .say(&ctx.http, "Error: Please include your appeal")
+            .await?;
+
+        return Ok(());
+    }
+
+    if reason.chars().count() > 1000 {
+        msg.channel_id
+            .say(
+                &ctx.http,
+                "Error: Appeals can be a maximum of 1000 characters",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    // Cases for other users are treated as not found to not expose them
+    let entry = match ModLogEntry::from_case_id(&ctx, guild_id.0, case_id).await? {
+        Some(e) if e.user_id as u64 == msg.author.id.0 => e,
+        _ => {
+            msg.channel_id
+                .say(&ctx.http, "Error: No case was found with this ID")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    if entry.action != "ban" && entry.action != "mute" {
+        msg.channel_id
+            .say(&ctx.http, "Error: Only bans and mutes can be appealed")
+            .await?;
+
+        return Ok(());
+    }
+
+    let channel_id = match GuildConfig::from_id(&ctx, &guild_id)
+        .await?
+        .as_ref()
+        .and_then(appeal_channel)
+    {
+        Some(id) => id,
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: This server does not accept appeals")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    if entry.action == "mute"
+        && Mute::from_id(&ctx, guild_id.0, msg.author.id.0)
+            .await?
+            .is_none()
+    {
+        msg.channel_id
+            .say(&ctx.http, "Error: You are no longer muted in this server")
+            .await?;
+
+        return Ok(());
+    }
+
+    let timeout = Duration::from_std(APPEAL_TIMEOUT).unwrap();
+
+    if let Some(existing) = ModAppeal::from_case_id(&ctx, guild_id.0, case_id).await? {
+        if existing.is_active(timeout) {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    format!(
+                        "Error: You have already appealed this case, it is currently {}",
+                        existing.status
+                    ),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    }
+
+    let new_appeal = ModAppeal::new(guild_id.0, case_id, msg.author.id.0, reason);
+
+    if let Err(e) = submit(&ctx, new_appeal, &msg.author, entry, channel_id).await {
+        tracing::warn!(?msg, "Failed to submit appeal: {}", e);
+
+        msg.channel_id
+            .say(
+                &ctx.http,
+                "Error: Failed to submit your appeal, please try again later",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            "Your appeal has been submitted, you will receive a message here once it has been reviewed.",
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use serenity::framework::standard::macros::group;
+
+mod revert;
+mod subscribe;
+mod trust;
+
+use self::{revert::*, subscribe::*, trust::*};
+
+#[group]
+#[commands(trust, untrust, trusted, subscribe, unsubscribe, subscriptions, revert)]
+#[description("Share bans between trusted guilds")]
+#[prefix("banlist")]
+#[only_in("guild")]
+#[required_permissions("BAN_MEMBERS")]
+pub struct BanList;
@@ -0,0 +1,92 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::model::moderation::revert_shared_ban;
+use crate::model::sql::*;
+use crate::model::Confirmation;
+
+#[command]
+#[required_permissions("BAN_MEMBERS")]
+#[description("Unbans all users that were banned from another guild's ban list")]
+#[usage("[guild ID]")]
+async fn revert(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => {
+            msg.channel_id.say(&ctx.http, "No guild found").await?;
+
+            return Ok(());
+        }
+    };
+
+    let source_guild_id = match args.single::<u64>() {
+        Ok(id) => GuildId(id),
+        Err(_) => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a valid guild ID")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let shared_bans = SharedBan::from_source(&ctx, guild_id, source_guild_id).await?;
+
+    if shared_bans.is_empty() {
+        msg.channel_id
+            .say(&ctx.http, "There are no bans from this guild")
+            .await?;
+
+        return Ok(());
+    }
+
+    let count = shared_bans.len();
+
+    let mut conf = Confirmation::new(msg.author.id, move |e| {
+        e.title("Revert shared bans?");
+        e.description(format!(
+            "This will unban {} users that were banned from guild `{}`'s ban list.",
+            count, source_guild_id.0
+        ));
+        e.color(0xe74c3c);
+
+        e
+    })
+    .options(vec![
+        (ReactionType::Unicode("✅".into()), "confirm"),
+        (ReactionType::Unicode("❌".into()), "cancel"),
+    ]);
+
+    if conf.await_confirmation(&ctx, msg.channel_id).await? != Some("confirm") {
+        msg.channel_id.say(&ctx.http, "Cancelled").await?;
+
+        return Ok(());
+    }
+
+    let mut reverted = 0;
+
+    for shared_ban in &shared_bans {
+        let user = match UserId(shared_ban.user_id as u64).to_user(&ctx).await {
+            Ok(u) => u,
+            Err(e) => {
+                tracing::warn!(?shared_ban, "Failed to fetch shared ban user: {}", e);
+                continue;
+            }
+        };
+
+        match revert_shared_ban(&ctx, &shared_ban, &user).await {
+            Ok(()) => reverted += 1,
+            Err(e) => tracing::warn!(?shared_ban, "Failed to revert shared ban: {}", e),
+        }
+    }
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!("Unbanned {}/{} users", reverted, shared_bans.len()),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use serenity::utils::parse_channel;
+use std::fmt::Write;
+
+use crate::model::sql::*;
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description(
+    "Subscribes to another guild's bans, the other guild must trust this guild first. \
+    `auto` bans users automatically and `alert` sends an alert to ban with a reaction."
+)]
+#[usage("[guild ID] [auto|alert] [channel]")]
+#[example("167058919611564043 alert #mod-alerts")]
+async fn subscribe(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => {
+            msg.channel_id.say(&ctx.http, "No guild found").await?;
+
+            return Ok(());
+        }
+    };
+
+    let source_guild_id = match args.single::<u64>() {
+        Ok(id) => GuildId(id),
+        Err(_) => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a valid guild ID")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let auto_ban = match args.single::<String>().as_deref() {
+        Ok("auto") => true,
+        Ok("alert") => false,
+        _ => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Mode must be either `auto` or `alert`")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let channel_id = match args.single::<String>().ok().and_then(parse_channel) {
+        Some(id) => ChannelId(id),
+        None => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    "Error: Please give a channel for shared ban messages",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    // Alerts can only be sent to channels in this guild
+    let channel_in_guild = ctx
+        .cache
+        .guild_channel(channel_id)
+        .await
+        .map_or(false, |c| c.guild_id == guild_id);
+
+    if !channel_in_guild {
+        msg.channel_id
+            .say(&ctx.http, "Error: Channel must be in this server")
+            .await?;
+
+        return Ok(());
+    }
+
+    if BanListTrust::from_id(&ctx, source_guild_id, guild_id)
+        .await?
+        .is_none()
+    {
+        msg.channel_id
+            .say(
+                &ctx.http,
+                format!(
+                    "Error: Guild `{}` does not trust this guild yet. \
+                    Ask them to run `banlist trust {}` first.",
+                    source_guild_id.0, guild_id.0
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    BanListSubscription::new(guild_id, source_guild_id, auto_ban, channel_id)
+        .save(&ctx)
+        .await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "Subscribed to bans from guild `{}`, {} in {}",
+                source_guild_id.0,
+                if auto_ban {
+                    "users will be banned automatically and logged"
+                } else {
+                    "ban alerts will be sent"
+                },
+                channel_id.mention()
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Unsubscribes from another guild's bans, existing bans are kept")]
+#[usage("[guild ID]")]
+async fn unsubscribe(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => {
+            msg.channel_id.say(&ctx.http, "No guild found").await?;
+
+            return Ok(());
+        }
+    };
+
+    let source_guild_id = match args.single::<u64>() {
+        Ok(id) => GuildId(id),
+        Err(_) => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a valid guild ID")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let sub = match BanListSubscription::from_id(&ctx, guild_id, source_guild_id).await? {
+        Some(s) => s,
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Not subscribed to this guild")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    sub.delete(&ctx).await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "Unsubscribed from bans from guild `{}`. Existing shared bans \
+                can be removed with `banlist revert {}`",
+                source_guild_id.0, source_guild_id.0
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[description("Lists the guilds this guild is subscribed to")]
+async fn subscriptions(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => {
+            msg.channel_id.say(&ctx.http, "No guild found").await?;
+
+            return Ok(());
+        }
+    };
+
+    let subs = BanListSubscription::from_guild_id(&ctx, guild_id).await?;
+
+    if subs.is_empty() {
+        msg.channel_id
+            .say(
+                &ctx.http,
+                "Not subscribed to any ban lists, you can add one with `banlist subscribe`",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let mut s = String::new();
+
+    writeln!(s, "Guild ID - Mode - Channel - Guild Name")?;
+
+    for sub in subs {
+        let source_guild_id = GuildId(sub.source_guild_id as u64);
+        let name = source_guild_id
+            .to_guild_cached(&ctx)
+            .await
+            .map(|g| g.name)
+            .unwrap_or_else(|| "Unknown Guild".into());
+
+        writeln!(
+            s,
+            "`{}` - {} - <#{}> - {}",
+            source_guild_id.0,
+            if sub.auto_ban { "auto" } else { "alert" },
+            sub.channel_id as u64,
+            name
+        )?;
+    }
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Ban List Subscriptions");
+                e.description(s);
+                e.color(0xe67e22);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
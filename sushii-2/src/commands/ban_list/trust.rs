@@ -0,0 +1,171 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::fmt::Write;
+
+use crate::model::sql::*;
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Allows another guild to subscribe to this guild's bans")]
+#[usage("[guild ID]")]
+async fn trust(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => {
+            msg.channel_id.say(&ctx.http, "No guild found").await?;
+
+            return Ok(());
+        }
+    };
+
+    let trusted_guild_id = match args.single::<u64>() {
+        Ok(id) => GuildId(id),
+        Err(_) => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a valid guild ID")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    if trusted_guild_id == guild_id {
+        msg.channel_id
+            .say(&ctx.http, "Error: You can't trust this guild")
+            .await?;
+
+        return Ok(());
+    }
+
+    BanListTrust::new(guild_id, trusted_guild_id)
+        .save(&ctx)
+        .await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "Guild `{}` can now subscribe to this guild's bans with `banlist subscribe {}`",
+                trusted_guild_id.0, guild_id.0
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Removes a trusted guild, this also removes their subscription")]
+#[usage("[guild ID]")]
+async fn untrust(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => {
+            msg.channel_id.say(&ctx.http, "No guild found").await?;
+
+            return Ok(());
+        }
+    };
+
+    let trusted_guild_id = match args.single::<u64>() {
+        Ok(id) => GuildId(id),
+        Err(_) => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a valid guild ID")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let trust = match BanListTrust::from_id(&ctx, guild_id, trusted_guild_id).await? {
+        Some(t) => t,
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: This guild is not trusted")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    trust.delete(&ctx).await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "Guild `{}` is no longer trusted and will not receive this guild's bans",
+                trusted_guild_id.0
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[description("Lists guilds that are trusted to subscribe to this guild's bans")]
+async fn trusted(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => {
+            msg.channel_id.say(&ctx.http, "No guild found").await?;
+
+            return Ok(());
+        }
+    };
+
+    let trusts = BanListTrust::from_guild_id(&ctx, guild_id).await?;
+
+    if trusts.is_empty() {
+        msg.channel_id
+            .say(
+                &ctx.http,
+                "There are no trusted guilds, you can add one with `banlist trust [guild ID]`",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let subs = BanListSubscription::from_source_guild_id(&ctx, guild_id).await?;
+
+    let mut s = String::new();
+
+    for trust in trusts {
+        let trusted_guild_id = GuildId(trust.trusted_guild_id as u64);
+        let name = trusted_guild_id
+            .to_guild_cached(&ctx)
+            .await
+            .map(|g| g.name)
+            .unwrap_or_else(|| "Unknown Guild".into());
+
+        write!(s, "`{}` - {}", trusted_guild_id.0, name)?;
+
+        if subs
+            .iter()
+            .any(|sub| sub.guild_id == trust.trusted_guild_id)
+        {
+            write!(s, " (subscribed)")?;
+        }
+
+        writeln!(s)?;
+    }
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Trusted Guilds");
+                e.description(s);
+                e.color(0xe67e22);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
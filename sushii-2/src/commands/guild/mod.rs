@@ -3,9 +3,10 @@ use serenity::framework::standard::macros::group;
 mod first;
 mod leaderboard;
 mod serverinfo;
+mod snipe;
 
-use self::{first::*, leaderboard::*, serverinfo::*};
+use self::{first::*, leaderboard::*, serverinfo::*, snipe::*};
 
 #[group]
-#[commands(serverinfo, leaderboard, first)]
+#[commands(serverinfo, leaderboard, first, snipe, editsnipe)]
 pub struct Guild;
@@ -0,0 +1,186 @@
+use chrono::{Duration, Utc};
+use serenity::framework::standard::{macros::command, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::model::sql::*;
+
+/// How long deleted and edited messages can be sniped for
+const SNIPE_WINDOW_MINUTES: i64 = 10;
+
+/// Max recent messages checked for one that isn't from an ignored role
+const SNIPE_CANDIDATES: i64 = 25;
+
+/// Checks if snipe is enabled and the channel isn't ignored in the message log
+async fn can_snipe(ctx: &Context, msg: &Message, guild_conf: &GuildConfig) -> CommandResult<bool> {
+    if !guild_conf.snipe_enabled {
+        msg.channel_id
+            .say(&ctx.http, "Error: Snipe is not enabled in this server")
+            .await?;
+
+        return Ok(false);
+    }
+
+    let category_id = ctx
+        .cache
+        .guild_channel(msg.channel_id)
+        .await
+        .and_then(|c| c.category_id);
+
+    if guild_conf.msg_log_ignored(msg.channel_id, category_id, &[]) {
+        msg.channel_id
+            .say(&ctx.http, "Error: Snipe is disabled in this channel")
+            .await?;
+
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// If the author of a saved message had a role ignored in the message log
+fn has_ignored_role(guild_conf: &GuildConfig, saved_msg: &SavedMessage) -> bool {
+    let roles = saved_msg
+        .msg
+        .member
+        .as_ref()
+        .map_or(&[][..], |m| &m.roles[..]);
+
+    // Channel is already checked so only the roles can be ignored here
+    guild_conf.msg_log_ignored(ChannelId(saved_msg.channel_id as u64), None, roles)
+}
+
+/// Truncates content to fit in an embed field
+fn field_value(s: &str) -> String {
+    if s.is_empty() {
+        return "(empty)".into();
+    }
+
+    if s.chars().count() > 1024 {
+        return format!("{}...", s.chars().take(1021).collect::<String>());
+    }
+
+    s.into()
+}
+
+#[command]
+#[only_in("guild")]
+#[description("Shows the most recently deleted message in this channel")]
+async fn snipe(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    if !can_snipe(ctx, msg, &guild_conf).await? {
+        return Ok(());
+    }
+
+    let since = (Utc::now() - Duration::minutes(SNIPE_WINDOW_MINUTES)).naive_utc();
+
+    let candidates =
+        SavedMessage::recently_deleted(ctx, msg.channel_id, since, SNIPE_CANDIDATES).await?;
+
+    let saved_msg = match candidates
+        .into_iter()
+        .find(|m| !has_ignored_role(&guild_conf, m))
+    {
+        Some(m) => m,
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "There are no recently deleted messages")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let author = &saved_msg.msg.author;
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.author(|a| {
+                    a.icon_url(author.face());
+                    a.name(author.tag());
+
+                    a
+                });
+
+                e.description(&saved_msg.content);
+                e.color(0xe74c3c);
+
+                if let Some(attachment) = saved_msg.msg.attachments.first() {
+                    e.field("Attachment", &attachment.filename, false);
+                }
+
+                e.footer(|f| f.text("Deleted"));
+
+                if let Some(deleted_at) = saved_msg.deleted_at {
+                    e.timestamp(deleted_at.format("%Y-%m-%dT%H:%M:%S").to_string());
+                }
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in("guild")]
+#[description("Shows the most recently edited message in this channel")]
+async fn editsnipe(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    if !can_snipe(ctx, msg, &guild_conf).await? {
+        return Ok(());
+    }
+
+    let since = (Utc::now() - Duration::minutes(SNIPE_WINDOW_MINUTES)).naive_utc();
+
+    let candidates =
+        SavedMessage::recently_edited(ctx, msg.channel_id, since, SNIPE_CANDIDATES).await?;
+
+    let saved_msg = match candidates
+        .into_iter()
+        .find(|m| !has_ignored_role(&guild_conf, m))
+    {
+        Some(m) => m,
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "There are no recently edited messages")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let author = &saved_msg.msg.author;
+    let before = saved_msg.prev_content.as_deref().unwrap_or_default();
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.author(|a| {
+                    a.icon_url(author.face());
+                    a.name(author.tag());
+
+                    a
+                });
+
+                e.field("Before", field_value(before), false);
+                e.field("After", field_value(&saved_msg.content), false);
+                e.color(0x9b59b6);
+
+                e.footer(|f| f.text("Edited"));
+
+                if let Some(edited_at) = saved_msg.edited_at {
+                    e.timestamp(edited_at.format("%Y-%m-%dT%H:%M:%S").to_string());
+                }
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
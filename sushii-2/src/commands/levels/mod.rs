@@ -0,0 +1,14 @@
+use serenity::framework::standard::macros::group;
+
+mod rewards;
+mod sync;
+
+use self::{rewards::*, sync::*};
+
+#[group]
+#[commands(rewards, sync)]
+#[description("Level settings, requires MANAGE_GUILD permissions")]
+#[prefix("levels")]
+#[only_in("guild")]
+#[required_permissions("MANAGE_GUILD")]
+pub struct Levels;
@@ -0,0 +1,207 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use serenity::utils::parse_role;
+use std::fmt::Write;
+
+use crate::model::sql::*;
+
+/// Parses a role mention or ID that exists in the guild
+async fn parse_guild_role(ctx: &Context, msg: &Message, s: &str) -> Option<Role> {
+    let role_id = parse_role(s).or_else(|| s.parse::<u64>().ok())?;
+
+    msg.guild_field(ctx, |g| g.roles.get(&RoleId(role_id)).cloned())
+        .await
+        .flatten()
+}
+
+#[command]
+#[sub_commands(add, remove, stack)]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Lists roles given to members when they reach a level")]
+async fn rewards(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let guild_conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+    let level_roles = LevelRole::from_guild(&ctx, guild_id).await?;
+
+    if level_roles.is_empty() {
+        msg.channel_id
+            .say(
+                &ctx.http,
+                "There are no level role rewards, add one with `levels rewards add [level] [role]`",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let mut s = String::new();
+
+    for level_role in &level_roles {
+        writeln!(
+            s,
+            "Level `{}`: <@&{}>",
+            level_role.level, level_role.role_id as u64
+        )?;
+    }
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Level Role Rewards");
+                e.description(s);
+                e.footer(|f| {
+                    f.text(if guild_conf.level_role_stack {
+                        "Lower level roles are kept"
+                    } else {
+                        "Lower level roles are replaced"
+                    })
+                });
+                e.color(0xe67e22);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Adds a role given to members when they reach a level")]
+#[usage("[level] [role]")]
+#[example("10 @Regular")]
+async fn add(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let level = match args.single::<i64>() {
+        Ok(l) if l > 0 => l,
+        _ => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a level greater than 0")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let role = match parse_guild_role(ctx, msg, args.rest().trim()).await {
+        Some(r) => r,
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a valid role")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    if role.managed || role.id.0 == guild_id.0 {
+        msg.channel_id
+            .say(&ctx.http, "Error: This role can't be given to members")
+            .await?;
+
+        return Ok(());
+    }
+
+    LevelRole::new(guild_id, role.id, level).save(&ctx).await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "Members will now get the `{}` role at level {}, use `levels sync` to give it to members already at this level",
+                role.name, level
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Removes a level role reward, members keep the role if they already have it")]
+#[usage("[role]")]
+async fn remove(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    // Allow removing deleted roles by ID
+    let role_id = match parse_role(args.rest().trim()).or_else(|| args.rest().trim().parse().ok()) {
+        Some(id) => RoleId(id),
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a role")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    match LevelRole::delete(&ctx, guild_id, role_id).await? {
+        Some(level_role) => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    format!(
+                        "Removed the level {} role reward <@&{}>",
+                        level_role.level, role_id.0
+                    ),
+                )
+                .await?;
+        }
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: This role isn't a level role reward")
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Sets if members keep lower level roles or if they are replaced by higher ones")]
+#[usage("[on|off]")]
+async fn stack(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    conf.level_role_stack = match args.rest().trim() {
+        "on" | "true" | "yes" => true,
+        "off" | "false" | "no" => false,
+        _ => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give `on` or `off`")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    conf.save(&ctx).await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            if conf.level_role_stack {
+                "Members will now keep lower level roles"
+            } else {
+                "Lower level roles will now be replaced by higher ones"
+            },
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use serenity::framework::standard::{macros::command, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::handlers::user_levels::apply_level_roles;
+use crate::model::sql::*;
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Gives level role rewards to members that already reached the level")]
+async fn sync(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let guild_conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+    let level_roles = LevelRole::from_guild(&ctx, guild_id).await?;
+
+    // Sorted by level so the first is the lowest
+    let min_level = match level_roles.first() {
+        Some(r) => r.level,
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: There are no level role rewards")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let user_levels = UserLevel::from_guild_min_level(&ctx, guild_id, min_level).await?;
+
+    let mut sent_msg = msg
+        .channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "Syncing level roles for {} members, this may take a while...",
+                user_levels.len()
+            ),
+        )
+        .await?;
+
+    let mut updated_count = 0;
+    let mut failed_count = 0;
+
+    for user_level in user_levels {
+        let user_id = UserId(user_level.user_id.0 as u64);
+
+        // Skip members that left
+        let member = match ctx.cache.member(guild_id, user_id).await {
+            Some(m) => m,
+            None => continue,
+        };
+
+        match apply_level_roles(
+            &ctx,
+            guild_id,
+            user_id,
+            &member.roles,
+            user_level.level(),
+            &level_roles,
+            guild_conf.level_role_stack,
+        )
+        .await
+        {
+            Ok(true) => updated_count += 1,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!(?guild_id, ?user_id, "Failed to sync level roles: {}", e);
+                failed_count += 1;
+            }
+        }
+    }
+
+    let mut s = format!("Updated level roles for {} members", updated_count);
+
+    if failed_count > 0 {
+        s.push_str(&format!(
+            ", failed to update {} members. Check that my role is above all level roles",
+            failed_count
+        ));
+    }
+
+    sent_msg.edit(&ctx, |m| m.content(s)).await?;
+
+    Ok(())
+}
@@ -1,9 +1,12 @@
 use serenity::framework::standard::macros::group;
 
+pub mod appeal;
+pub mod ban_list;
 pub mod feeds;
 pub mod guild;
 pub mod help;
 pub mod lastfm;
+pub mod levels;
 pub mod meta;
 pub mod moderation;
 pub mod notifications;
@@ -14,13 +17,14 @@ pub mod roles;
 pub mod settings;
 pub mod tags;
 pub mod users;
+pub mod xp;
 
-use self::{help::*, meta::*, owner::*, prefix::*};
+use self::{appeal::*, help::*, meta::*, owner::*, prefix::*};
 
 #[group]
-#[commands(quit, say, listservers)]
+#[commands(quit, say, listservers, msgstorage)]
 pub struct Owner;
 
 #[group]
-#[commands(prefix, ping, invite, about, help)]
+#[commands(prefix, ping, invite, about, help, appeal)]
 pub struct Meta;
@@ -0,0 +1,100 @@
+use serenity::framework::standard::{macros::command, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::fmt::Write;
+
+use crate::model::sql::*;
+use crate::utils::user::{dehoisted_name, DEFAULT_DEHOIST_FALLBACK};
+
+#[command]
+#[required_permissions("MANAGE_NICKNAMES")]
+#[description(
+    "Normalizes the names of all members, removing hoisting characters and fancy unicode"
+)]
+async fn dehoist(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let members = match msg.guild(&ctx.cache).await {
+        Some(g) => g.members.values().cloned().collect::<Vec<_>>(),
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Couldn't find guild in cache")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let fallback = guild_conf
+        .dehoist_fallback
+        .as_deref()
+        .unwrap_or(DEFAULT_DEHOIST_FALLBACK);
+
+    let mut sent_msg = msg
+        .channel_id
+        .say(
+            &ctx.http,
+            format!("Checking the names of {} members...", members.len()),
+        )
+        .await?;
+
+    let mut changed = Vec::new();
+    let mut failed = 0;
+
+    for member in members.iter().filter(|m| !m.user.bot) {
+        let new_name = match dehoisted_name(&member, fallback) {
+            Some(n) => n,
+            None => continue,
+        };
+
+        match member.edit(&ctx.http, |m| m.nickname(&new_name)).await {
+            Ok(_) => changed.push((member.display_name().to_string(), new_name)),
+            Err(e) => {
+                tracing::warn!(?member.user.id, "Failed to dehoist member: {}", e);
+                failed += 1;
+            }
+        }
+    }
+
+    let mut s = String::new();
+
+    for (old_name, new_name) in &changed {
+        let line = format!("`{}` → `{}`\n", old_name, new_name);
+
+        // Embed descriptions are max 2048 characters
+        if s.len() + line.len() > 2000 {
+            let _ = writeln!(s, "...");
+            break;
+        }
+
+        s.push_str(&line);
+    }
+
+    if changed.is_empty() {
+        s.push_str("No names needed to be changed");
+    }
+
+    sent_msg
+        .edit(&ctx, |m| {
+            m.content("");
+            m.embed(|e| {
+                e.title(format!("Dehoisted {} members", changed.len()));
+                e.description(s);
+                e.color(0xe67e22);
+
+                if failed > 0 {
+                    e.footer(|f| {
+                        f.text(format!(
+                            "Failed to change {} names, check that I have a higher role than them",
+                            failed
+                        ))
+                    });
+                }
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
@@ -3,16 +3,23 @@ use serenity::framework::standard::macros::group;
 mod cases;
 mod channel;
 mod chat;
+mod dehoist;
+mod search;
+mod stats;
 
 use self::{
     cases::{ban::*, delete::*, history::*, kick::*, mute::*, reason::*, warn::*},
     channel::*,
     chat::*,
+    dehoist::*,
+    search::*,
+    stats::*,
 };
 
 #[group]
 #[commands(
-    prune, history, ban, unban, kick, mute, listmutes, reason, unmute, warn, slowmode, deletecase
+    prune, history, ban, unban, kick, mute, listmutes, reason, unmute, warn, slowmode, deletecase,
+    modstats, dehoist, search
 )]
 #[only_in("guild")]
 #[required_permissions("BAN_MEMBERS")]
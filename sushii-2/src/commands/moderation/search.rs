@@ -0,0 +1,367 @@
+use chrono::{naive::NaiveDateTime, Utc};
+use regex::Regex;
+use serenity::collector::reaction_collector::ReactionAction;
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use serenity::utils::parse_channel;
+use std::fmt::Write;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+use crate::model::sql::*;
+use crate::model::Paginator;
+use crate::utils::duration::parse_duration;
+use crate::utils::text::escape_markdown;
+use crate::utils::user::parse_id;
+
+const PAGE_SIZE: i64 = 10;
+
+/// Max characters of message content shown per result
+const MAX_CONTENT_LEN: usize = 150;
+
+/// Parses search filters, anything that isn't a filter is searched as content.
+/// `regex:` uses the rest of the query as the pattern
+fn parse_search(s: &str, now: NaiveDateTime) -> Result<MessageSearch, String> {
+    let mut search = MessageSearch::default();
+    let mut content = Vec::new();
+    let mut rest = s.trim();
+
+    while !rest.is_empty() {
+        let (token, remaining) = match rest.find(char::is_whitespace) {
+            Some(i) => (&rest[..i], rest[i..].trim_start()),
+            None => (rest, ""),
+        };
+
+        if let Some(pattern) = rest.strip_prefix("regex:") {
+            let pattern = pattern.trim();
+
+            if pattern.is_empty() {
+                return Err("Regex cannot be empty".into());
+            }
+
+            Regex::new(pattern).map_err(|_| "Invalid regex given".to_string())?;
+            search.regex = Some(pattern.to_string());
+
+            break;
+        } else if let Some(user) = token.strip_prefix("from:") {
+            let id = parse_id(user).ok_or_else(|| format!("Invalid user `{}`", user))?;
+            search.author_id = Some(id as i64);
+        } else if let Some(channel) = token.strip_prefix("in:") {
+            let id = parse_channel(channel)
+                .or_else(|| channel.parse::<u64>().ok())
+                .ok_or_else(|| format!("Invalid channel `{}`", channel))?;
+            search.channel_id = Some(id as i64);
+        } else if let Some(duration) = token.strip_prefix("after:") {
+            search.after = Some(
+                now.checked_sub_signed(parse_duration(duration)?)
+                    .ok_or("Duration is too long")?,
+            );
+        } else if let Some(duration) = token.strip_prefix("before:") {
+            search.before = Some(
+                now.checked_sub_signed(parse_duration(duration)?)
+                    .ok_or("Duration is too long")?,
+            );
+        } else {
+            content.push(token);
+        }
+
+        rest = remaining;
+    }
+
+    if !content.is_empty() {
+        search.content = Some(content.join(" "));
+    }
+
+    Ok(search)
+}
+
+/// Gets the IDs of text channels a member can read the message history of.
+/// Messages in deleted channels can't be checked so they aren't searched
+fn readable_channels(guild: &Guild, member: &Member) -> Vec<i64> {
+    guild
+        .channels
+        .values()
+        .filter(|c| c.kind == ChannelType::Text || c.kind == ChannelType::News)
+        .filter(|c| {
+            guild
+                .user_permissions_in(c, member)
+                .map_or(false, |p| p.read_messages() && p.read_message_history())
+        })
+        .map(|c| c.id.0 as i64)
+        .collect()
+}
+
+fn fmt_results(guild_id: GuildId, msgs: &[SavedMessage]) -> String {
+    let mut s = String::new();
+
+    for msg in msgs {
+        let mut content: String = msg.content.chars().take(MAX_CONTENT_LEN).collect();
+
+        if msg.content.chars().count() > MAX_CONTENT_LEN {
+            content.push_str("...");
+        }
+
+        // Jump links don't work for deleted messages
+        if msg.deleted_at.is_some() {
+            let _ = write!(s, "`{}` (deleted)", msg.created.format("%Y-%m-%d %H:%M"));
+        } else {
+            let _ = write!(
+                s,
+                "[`{}`](https://discord.com/channels/{}/{}/{})",
+                msg.created.format("%Y-%m-%d %H:%M"),
+                guild_id.0,
+                msg.channel_id as u64,
+                msg.message_id as u64,
+            );
+        }
+
+        let _ = writeln!(
+            s,
+            " <@{}> in <#{}>\n{}",
+            msg.author_id as u64,
+            msg.channel_id as u64,
+            escape_markdown(&content),
+        );
+    }
+
+    s
+}
+
+#[command]
+#[only_in("guild")]
+#[required_permissions("BAN_MEMBERS")]
+#[description(
+    "Searches saved messages. Filters are `from:user`, `in:channel`, \
+    `after:duration` and `before:duration`, e.g. `after:2d` for messages in the \
+    last 2 days. Anything else is searched as text, or use `regex:pattern` at \
+    the end to search with a regex"
+)]
+#[usage("[filters] [text or regex:pattern]")]
+#[example("from:@user in:#general after:1d hello")]
+async fn search(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => {
+            msg.channel_id.say(&ctx, "Error: Not in guild").await?;
+            return Ok(());
+        }
+    };
+
+    if args.rest().trim().is_empty() {
+        msg.channel_id
+            .say(&ctx, "Error: Please give a search")
+            .await?;
+
+        return Ok(());
+    }
+
+    let search = match parse_search(args.rest(), Utc::now().naive_utc()) {
+        Ok(s) => s,
+        Err(e) => {
+            msg.channel_id.say(&ctx, format!("Error: {}", e)).await?;
+
+            return Ok(());
+        }
+    };
+
+    let guild = match msg.guild(&ctx).await {
+        Some(g) => g,
+        None => {
+            msg.channel_id
+                .say(&ctx, "Error: Couldn't find this server")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let member = msg.member(&ctx).await?;
+    let channel_ids = readable_channels(&guild, &member);
+
+    if let Some(channel_id) = search.channel_id {
+        if !channel_ids.contains(&channel_id) {
+            msg.channel_id
+                .say(
+                    &ctx,
+                    format!("Error: You can't read messages in <#{}>", channel_id as u64),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    }
+
+    // Regex is validated before but Postgres regex syntax can still differ,
+    // searches that take too long are also stopped
+    let msg_count = match search.count(&ctx, guild_id, &channel_ids).await {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::warn!(?search, "Failed to search messages: {}", e);
+
+            msg.channel_id
+                .say(
+                    &ctx,
+                    "Error: Failed to search messages, is the regex valid? \
+                    Searches that take too long are also stopped.",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    if msg_count == 0 {
+        msg.channel_id.say(&ctx, "Error: No messages found").await?;
+
+        return Ok(());
+    }
+
+    let mut paginator = Paginator::new(PAGE_SIZE, msg_count);
+    let mut msgs = search
+        .get_page(&ctx, guild_id, &channel_ids, PAGE_SIZE, None)
+        .await?;
+
+    let mut sent_msg = msg
+        .channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title(format!("Message Search ({} found)", msg_count));
+                e.description(fmt_results(guild_id, &msgs));
+                e.color(0xe67e22);
+                e.footer(|f| {
+                    f.text(format!(
+                        "Page {}/{}",
+                        paginator.current_page, paginator.page_count
+                    ));
+                    f
+                });
+
+                e
+            });
+
+            if paginator.page_count > 1 {
+                m.reactions(vec![
+                    ReactionType::Unicode("⬅️".into()),
+                    ReactionType::Unicode("➡️".into()),
+                ]);
+            }
+
+            m
+        })
+        .await?;
+
+    // Don't listen for reactions if theres only 1 page
+    if paginator.page_count <= 1 {
+        return Ok(());
+    }
+
+    while let Some(reaction_action) = sent_msg
+        .await_reactions(&ctx)
+        .author_id(msg.author.id)
+        .filter(|r| ["⬅️", "➡️"].iter().any(|u| r.emoji.unicode_eq(u)))
+        .timeout(Duration::from_secs(60))
+        .await
+        .next()
+        .await
+    {
+        if let ReactionAction::Added(ref r) = *reaction_action {
+            if r.emoji.unicode_eq("➡️") {
+                let offset = msgs.last().map(|m| m.message_id);
+                if !paginator.next(offset) {
+                    r.delete(&ctx).await?;
+                    continue;
+                }
+
+                msgs = search
+                    .get_page(&ctx, guild_id, &channel_ids, PAGE_SIZE, offset)
+                    .await?;
+            } else if r.emoji.unicode_eq("⬅️") {
+                // Ignore on first page
+                if paginator.current_page == 1 {
+                    r.delete(&ctx).await?;
+                    continue;
+                }
+
+                // Use previous page's last message as offset
+                let offset = paginator.prev_offset().copied();
+                msgs = search
+                    .get_page(&ctx, guild_id, &channel_ids, PAGE_SIZE, offset)
+                    .await?;
+            }
+
+            sent_msg
+                .edit(&ctx, |m| {
+                    m.embed(|e| {
+                        e.title(format!("Message Search ({} found)", msg_count));
+                        e.description(fmt_results(guild_id, &msgs));
+                        e.color(0xe67e22);
+                        e.footer(|f| {
+                            f.text(format!(
+                                "Page {}/{}",
+                                paginator.current_page, paginator.page_count
+                            ));
+                            f
+                        });
+
+                        e
+                    });
+
+                    m
+                })
+                .await?;
+
+            // Delete reaction after handling, so that user can react again
+            r.delete(&ctx).await?;
+        }
+    }
+
+    // Delete all reactions after timed out to show user they can't react anymore
+    sent_msg.delete_reactions(&ctx).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn parses_search() {
+        let now = Utc::now().naive_utc();
+        let search = parse_search(
+            "from:<@145764790046818304> in:<#167058919611564043> after:2d some text",
+            now,
+        )
+        .unwrap();
+
+        assert_eq!(search.author_id, Some(145764790046818304));
+        assert_eq!(search.channel_id, Some(167058919611564043));
+        assert_eq!(search.after, Some(now - Duration::days(2)));
+        assert_eq!(search.before, None);
+        assert_eq!(search.content.as_deref(), Some("some text"));
+        assert_eq!(search.regex, None);
+    }
+
+    #[test]
+    fn parses_search_regex() {
+        let now = Utc::now().naive_utc();
+        let search = parse_search("in:167058919611564043 regex:foo (bar|baz)", now).unwrap();
+
+        assert_eq!(search.channel_id, Some(167058919611564043));
+        assert_eq!(search.regex.as_deref(), Some("foo (bar|baz)"));
+        assert_eq!(search.content, None);
+
+        assert!(parse_search("regex:(unclosed", now).is_err());
+        assert!(parse_search("from:someone", now).is_err());
+        assert_eq!(
+            parse_search("after:1000000years", now).unwrap_err(),
+            "Duration is too long"
+        );
+        assert_eq!(
+            parse_search("before:1000000years", now).unwrap_err(),
+            "Duration is too long"
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use chrono::{Duration, Utc};
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::model::sql::{ModActionCount, ModStats};
+use crate::utils::duration::parse_duration;
+use crate::utils::user::parse_id;
+
+/// Max timeframe, compared with the same length before it
+const MAX_PERIOD_DAYS: i64 = 365;
+
+/// Formats the change from a previous count, e.g. `▲ 3 (+50%)`
+fn fmt_trend(current: i64, previous: i64) -> String {
+    let diff = current - previous;
+
+    let arrow = match diff {
+        d if d > 0 => "▲",
+        d if d < 0 => "▼",
+        _ => return "no change".into(),
+    };
+
+    if previous == 0 {
+        return format!("{} {}", arrow, diff.abs());
+    }
+
+    format!(
+        "{} {} ({:+.0}%)",
+        arrow,
+        diff.abs(),
+        diff as f64 / previous as f64 * 100.0
+    )
+}
+
+#[command]
+#[only_in("guild")]
+#[required_permissions("BAN_MEMBERS")]
+#[usage("[@mod] [timeframe]")]
+async fn modstats(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => {
+            msg.channel_id.say(&ctx.http, "No guild found").await?;
+
+            return Ok(());
+        }
+    };
+
+    let mut rest = args.rest().trim();
+
+    // Optional moderator as the first argument
+    let executor_id = rest
+        .split_whitespace()
+        .next()
+        .and_then(parse_id)
+        .map(UserId);
+
+    if let Some(first) = executor_id.and_then(|_| rest.split_whitespace().next()) {
+        rest = rest[first.len()..].trim();
+    }
+
+    let period = if rest.is_empty() {
+        Duration::days(30)
+    } else {
+        match parse_duration(rest) {
+            Ok(d) => d,
+            Err(e) => {
+                msg.channel_id
+                    .say(&ctx.http, format!("Error: Invalid timeframe: {}", e))
+                    .await?;
+
+                return Ok(());
+            }
+        }
+    };
+
+    if period < Duration::hours(1) {
+        msg.channel_id
+            .say(&ctx.http, "Error: Timeframe must be at least 1 hour")
+            .await?;
+
+        return Ok(());
+    }
+
+    if period > Duration::days(MAX_PERIOD_DAYS) {
+        msg.channel_id
+            .say(
+                &ctx.http,
+                format!(
+                    "Error: Timeframe can't be longer than {} days",
+                    MAX_PERIOD_DAYS
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let stats =
+        match ModStats::from_period(&ctx, guild_id, executor_id, Utc::now().naive_utc(), period)
+            .await
+        {
+            Ok(s) => s,
+            Err(e) => {
+                msg.channel_id
+                    .say(&ctx.http, "Something went wrong getting mod stats :(")
+                    .await?;
+
+                tracing::error!(?msg, "Failed to get mod stats: {}", e);
+
+                return Ok(());
+            }
+        };
+
+    let total = ModActionCount::total(&stats.current);
+    let previous_total = ModActionCount::total(&stats.previous);
+
+    let previous_actions: HashMap<String, i64> = ModActionCount::action_totals(&stats.previous)
+        .into_iter()
+        .collect();
+
+    let mut actions_str = String::new();
+
+    for (action, count) in ModActionCount::action_totals(&stats.current) {
+        let _ = writeln!(
+            actions_str,
+            "**{}** - {} ({})",
+            action,
+            count,
+            fmt_trend(count, previous_actions.get(&action).copied().unwrap_or(0))
+        );
+    }
+
+    // Actions that only happened in the previous period
+    for (action, previous) in &previous_actions {
+        if !stats.current.iter().any(|c| &c.action == action) {
+            let _ = writeln!(
+                actions_str,
+                "**{}** - 0 ({})",
+                action,
+                fmt_trend(0, *previous)
+            );
+        }
+    }
+
+    if actions_str.is_empty() {
+        actions_str = "No cases".into();
+    }
+
+    let mut mods_str = String::new();
+
+    if executor_id.is_none() {
+        for (i, (id, count)) in ModActionCount::executor_totals(&stats.current)
+            .iter()
+            .take(10)
+            .enumerate()
+        {
+            let _ = write!(mods_str, "`{}.` ", i + 1);
+
+            match id {
+                Some(id) => {
+                    let _ = write!(mods_str, "<@{}>", id);
+                }
+                None => {
+                    let _ = write!(mods_str, "Unknown");
+                }
+            }
+
+            let _ = writeln!(mods_str, " - {}", count);
+        }
+    }
+
+    let period_str = humantime::format_duration(period.to_std().unwrap()).to_string();
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Mod Stats");
+
+                let mut desc = String::new();
+
+                if let Some(id) = executor_id {
+                    let _ = writeln!(desc, "Moderator: {}", id.mention());
+                }
+
+                let _ = write!(
+                    desc,
+                    "**{}** total cases in the last {} ({} from previous {})",
+                    total,
+                    period_str,
+                    fmt_trend(total, previous_total),
+                    period_str,
+                );
+
+                e.description(desc);
+
+                e.field("Actions", actions_str, false);
+
+                if !mods_str.is_empty() {
+                    e.field("Most Active Moderators", mods_str, false);
+                }
+
+                e.color(0xe67e22);
+                e.footer(|f| {
+                    f.text(format!(
+                        "{} to {} • Times in UTC",
+                        stats.start.format("%Y-%m-%d %H:%M"),
+                        stats.end.format("%Y-%m-%d %H:%M"),
+                    ))
+                });
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_trend() {
+        assert_eq!(fmt_trend(3, 3), "no change");
+        assert_eq!(fmt_trend(6, 4), "▲ 2 (+50%)");
+        assert_eq!(fmt_trend(1, 4), "▼ 3 (-75%)");
+        assert_eq!(fmt_trend(5, 0), "▲ 5");
+    }
+}
@@ -1,10 +1,13 @@
 use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::http::AttachmentType;
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 use serenity::utils::parse_channel;
+use std::borrow::Cow;
 use std::fmt::Write;
 
 use crate::keys::ShardManagerContainer;
+use crate::model::sql::MessageStorage;
 
 #[command]
 #[owners_only]
@@ -74,6 +77,55 @@ async fn listservers(ctx: &Context, msg: &Message) -> CommandResult {
     Ok(())
 }
 
+#[command]
+#[owners_only]
+async fn msgstorage(ctx: &Context, msg: &Message) -> CommandResult {
+    let storage = MessageStorage::all(ctx).await?;
+
+    let total_size: i64 = storage.iter().map(|s| s.size).sum();
+    let total_count: i64 = storage.iter().map(|s| s.message_count).sum();
+
+    let summary = format!(
+        "{} saved messages in {} guilds, {:.2} MiB total",
+        total_count,
+        storage.len(),
+        total_size as f64 / 1024.0 / 1024.0
+    );
+
+    // Sent as a file since all guilds can be longer than the 2000 character
+    // message limit
+    let mut s = String::new();
+
+    for guild_storage in &storage {
+        let guild_name = ctx
+            .cache
+            .guild_field(guild_storage.guild_id as u64, |g| g.name.clone())
+            .await
+            .unwrap_or_else(|| "Unknown guild".into());
+
+        writeln!(
+            s,
+            "{} - {}: {} messages in {} channels, {:.2} KiB",
+            guild_storage.guild_id as u64,
+            guild_name,
+            guild_storage.message_count,
+            guild_storage.channel_count,
+            guild_storage.size as f64 / 1024.0
+        )?;
+    }
+
+    let file = AttachmentType::Bytes {
+        data: Cow::from(s.into_bytes()),
+        filename: "msgstorage.txt".into(),
+    };
+
+    msg.channel_id
+        .send_files(ctx, vec![file], |m| m.content(summary))
+        .await?;
+
+    Ok(())
+}
+
 /*
 #[command]
 #[owners_only]
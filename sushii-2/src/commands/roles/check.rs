@@ -0,0 +1,145 @@
+use serenity::framework::standard::{macros::command, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::fmt::Write;
+
+use crate::error::Result;
+use crate::model::sql::*;
+
+/// Validates a role config against the guild's current roles. Returns None if
+/// the guild isn't cached
+pub async fn validate_config(
+    ctx: &Context,
+    guild_id: GuildId,
+    role_conf: &GuildRoles,
+) -> Result<Option<Vec<RoleConfigIssue>>> {
+    let guild = match ctx.cache.guild(guild_id).await {
+        Some(g) => g,
+        None => return Ok(None),
+    };
+
+    let bot_member = guild_id
+        .member(&ctx, ctx.cache.current_user_id().await)
+        .await?;
+
+    // Roles can only be assigned if they're below the bot's highest role
+    let bot_position = bot_member
+        .roles
+        .iter()
+        .filter_map(|id| guild.roles.get(id))
+        .map(|r| r.position)
+        .max()
+        .unwrap_or(0);
+
+    Ok(Some(role_conf.validate(&guild.roles, bot_position)))
+}
+
+/// Formats issues as a list, errors first
+pub fn fmt_issues(issues: &[RoleConfigIssue]) -> String {
+    let mut s = String::new();
+
+    for issue in issues.iter().filter(|i| i.is_error()) {
+        let _ = writeln!(s, "**Error** {}", issue);
+    }
+
+    for issue in issues.iter().filter(|i| !i.is_error()) {
+        let _ = writeln!(s, "**Warning** {}", issue);
+    }
+
+    s
+}
+
+/// Sends issues in an embed, or as a text file if they don't fit in one
+pub async fn send_issues(
+    ctx: &Context,
+    channel_id: ChannelId,
+    content: &str,
+    issues: &[RoleConfigIssue],
+    color: u64,
+) -> Result<()> {
+    let report = fmt_issues(issues);
+
+    // Large configs could have more issues than fit in an embed
+    if report.len() >= 2048 {
+        let mut s = String::new();
+
+        if !content.is_empty() {
+            let _ = writeln!(s, "{}", content);
+        }
+
+        let _ = write!(
+            s,
+            "Found {} issues in the role config, attached in the following text file",
+            issues.len()
+        );
+
+        let files = vec![(report.as_bytes(), "role-config-issues.txt")];
+
+        channel_id
+            .send_files(&ctx.http, files, |m| m.content(&s))
+            .await?;
+
+        return Ok(());
+    }
+
+    channel_id
+        .send_message(&ctx.http, |m| {
+            if !content.is_empty() {
+                m.content(content);
+            }
+
+            m.embed(|e| {
+                e.title(format!("Role Config Issues ({})", issues.len()));
+                e.description(&report);
+                e.color(color);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+async fn check(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let role_conf: GuildRoles = match conf.role_config {
+        Some(c) => serde_json::from_value(c)?,
+        None => {
+            msg.channel_id
+                .say(&ctx, "Error: There is no role config set")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let issues = match validate_config(&ctx, guild_id, &role_conf).await? {
+        Some(i) => i,
+        None => {
+            msg.channel_id.say(&ctx, "Error: No guild found").await?;
+
+            return Ok(());
+        }
+    };
+
+    if issues.is_empty() {
+        msg.channel_id
+            .say(&ctx, "All roles in the role config can be assigned")
+            .await?;
+
+        return Ok(());
+    }
+
+    send_issues(&ctx, msg.channel_id, "", &issues, 0xe74c3c).await?;
+
+    Ok(())
+}
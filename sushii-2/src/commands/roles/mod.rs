@@ -1,5 +1,6 @@
 use serenity::framework::standard::macros::group;
 
+pub mod check;
 pub mod default;
 pub mod get_roles;
 pub mod list_role_ids;
@@ -8,11 +9,12 @@ pub mod set_roles;
 pub mod set_roles_channel;
 
 use self::{
-    default::*, get_roles::*, list_role_ids::*, send_info::*, set_roles::*, set_roles_channel::*,
+    check::*, default::*, get_roles::*, list_role_ids::*, send_info::*, set_roles::*,
+    set_roles_channel::*,
 };
 
 #[group]
-#[commands(set, get, setchannel, listids, sendinfo)]
+#[commands(set, get, setchannel, listids, sendinfo, check)]
 #[prefix = "roles"]
 #[only_in("guild")]
 #[default_command(default)]
@@ -1,13 +1,20 @@
+use serenity::builder::CreateEmbed;
 use serenity::framework::standard::{macros::command, CommandResult};
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 
+use crate::error::Result;
 use crate::model::sql::*;
 
 #[command]
 #[aliases("sendhelp", "send info", "send help")]
 #[required_permissions("MANAGE_GUILD")]
 async fn sendinfo(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
     let conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
 
     let role_conf: GuildRoles = match conf.role_config {
@@ -78,12 +85,110 @@ Remove all roles: `clear` or `reset`
         return Ok(());
     }
 
-    msg.channel_id
-        .say(
-            &ctx.http,
-            format!("Sent role info message to <#{}>", role_channel),
-        )
-        .await?;
+    let (menu_count, skipped) =
+        match send_menus(ctx, guild_id, &role_conf, ChannelId(role_channel)).await {
+            Ok(res) => res,
+            Err(e) => {
+                tracing::warn!("Failed to send role menus: {}", e);
+                msg.channel_id
+                    .say(&ctx.http, "Error: Failed to send role menus")
+                    .await?;
+
+                return Ok(());
+            }
+        };
+
+    let mut s = format!("Sent role info message to <#{}>", role_channel);
+
+    if menu_count > 0 {
+        s.push_str(&format!(" with {} role menus", menu_count));
+    }
+
+    if !skipped.is_empty() {
+        s.push_str(&format!(
+            "\nSkipped roles with invalid emojis: {}",
+            skipped.join(", ")
+        ));
+    }
+
+    msg.channel_id.say(&ctx.http, s).await?;
 
     Ok(())
 }
+
+fn menu_embed<'a>(
+    e: &'a mut CreateEmbed,
+    group: &GuildGroup,
+    skip: &[&str],
+) -> &'a mut CreateEmbed {
+    e.title(&group.name);
+    e.description(group.menu_string(skip));
+    e.footer(|f| f.text("React to add a role, remove your reaction to remove it"));
+    e.color(0x7596ff);
+
+    e
+}
+
+/// Sends a reaction role menu for each group with role emojis, replacing any
+/// previous menus. Roles with emojis that can't be reacted with are left out
+/// of their menu. Returns the number of menus sent and the skipped roles
+async fn send_menus(
+    ctx: &Context,
+    guild_id: GuildId,
+    role_conf: &GuildRoles,
+    channel_id: ChannelId,
+) -> Result<(usize, Vec<String>)> {
+    // Old menus would have outdated roles, ignore if they're already deleted
+    for menu in RoleMenu::delete_guild(&ctx, guild_id).await? {
+        let _ = ChannelId(menu.channel_id as u64)
+            .delete_message(&ctx.http, menu.message_id as u64)
+            .await;
+    }
+
+    let mut count = 0;
+    let mut skipped = Vec::new();
+
+    for group in role_conf.groups.iter().filter(|g| g.has_menu()) {
+        let mut menu_msg = channel_id
+            .send_message(&ctx.http, |m| m.embed(|e| menu_embed(e, group, &[])))
+            .await?;
+
+        RoleMenu::new(&menu_msg, guild_id, &group.name)
+            .save(&ctx)
+            .await?;
+
+        count += 1;
+
+        // Emojis can be valid but still fail, e.g. custom emojis from other
+        // servers, so each one is tried separately
+        let mut failed = Vec::new();
+
+        for role in &group.roles {
+            let reaction = match role.reaction_type() {
+                Some(r) => r,
+                None => continue,
+            };
+
+            if let Err(e) = menu_msg.react(&ctx, reaction).await {
+                tracing::warn!(?role, "Failed to add role menu reaction: {}", e);
+                failed.push(role.name.as_str());
+            }
+        }
+
+        if failed.is_empty() {
+            continue;
+        }
+
+        menu_msg
+            .edit(&ctx, |m| m.embed(|e| menu_embed(e, group, &failed)))
+            .await?;
+
+        skipped.extend(
+            failed
+                .iter()
+                .map(|name| format!("`{}` in `{}`", name, group.name)),
+        );
+    }
+
+    Ok((count, skipped))
+}
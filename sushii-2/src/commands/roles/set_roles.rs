@@ -2,6 +2,7 @@ use serenity::framework::standard::{macros::command, Args, CommandResult};
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 
+use super::check::{send_issues, validate_config};
 use crate::model::sql::*;
 
 enum ConfigType {
@@ -42,6 +43,29 @@ async fn set(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         Ok(c) => c,
     };
 
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    // Skip validation if the guild isn't cached
+    let issues = validate_config(&ctx, guild_id, &roles_conf)
+        .await?
+        .unwrap_or_default();
+
+    if issues.iter().any(|i| i.is_error()) {
+        send_issues(
+            &ctx,
+            msg.channel_id,
+            "Error: The roles configuration was not updated",
+            &issues,
+            0xe74c3c,
+        )
+        .await?;
+
+        return Ok(());
+    }
+
     let conf_value = match serde_json::to_value(roles_conf) {
         Ok(c) => c,
         Err(e) => {
@@ -59,9 +83,20 @@ async fn set(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 
     conf.save(&ctx).await?;
 
-    msg.channel_id
-        .say(&ctx.http, "Updated the roles configuration")
+    if issues.is_empty() {
+        msg.channel_id
+            .say(&ctx.http, "Updated the roles configuration")
+            .await?;
+    } else {
+        send_issues(
+            &ctx,
+            msg.channel_id,
+            "Updated the roles configuration, but some roles can't be assigned yet",
+            &issues,
+            0xe67e22,
+        )
         .await?;
+    }
 
     Ok(())
 }
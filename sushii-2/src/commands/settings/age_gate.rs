@@ -0,0 +1,297 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::model::sql::*;
+use crate::utils::user::parse_id;
+
+/// Max minimum account age in days
+const MAX_AGE_DAYS: u64 = 365 * 3;
+
+#[command]
+#[sub_commands(duration, action, dm, allow, disallow, allowlist)]
+#[required_permissions("MANAGE_GUILD")]
+async fn agegate(ctx: &Context, msg: &Message) -> CommandResult {
+    let _ = msg
+        .channel_id
+        .say(
+            &ctx.http,
+            "Available sub-commands for `agegate` are `duration`, `action`, `dm`, `allow`, `disallow`, `allowlist`",
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Sets the minimum account age to join, or `off` to disable")]
+#[usage("[duration|off]")]
+#[example("3 days")]
+async fn duration(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let duration_str = args.rest();
+
+    if duration_str.is_empty() {
+        msg.channel_id
+            .say(
+                &ctx,
+                "Error: Please provide a minimum account age or `off` to disable. Example: `3 days`",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let duration = match duration_str {
+        "off" | "disable" | "0" => None,
+        _ => match crate::utils::duration::parse_duration_std(&duration_str) {
+            Ok(d) if d.as_secs() <= MAX_AGE_DAYS * 24 * 60 * 60 => Some(d),
+            Ok(_) => {
+                msg.channel_id
+                    .say(
+                        &ctx.http,
+                        format!(
+                            "Error: Minimum account age can't be longer than {} days",
+                            MAX_AGE_DAYS
+                        ),
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+            Err(e) => {
+                msg.channel_id
+                    .say(
+                        &ctx.http,
+                        format!("Error: Failed to parse duration -- {}", e),
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        },
+    };
+
+    conf.age_gate_duration = duration.map(|d| d.as_secs() as i64);
+    conf.save(&ctx).await?;
+
+    let s = if let Some(dur) = duration {
+        format!(
+            "Accounts younger than `{}` will now be {} on join",
+            humantime::format_duration(dur),
+            if conf.age_gate_quarantine {
+                "quarantined"
+            } else {
+                "kicked"
+            }
+        )
+    } else {
+        "Disabled the account age gate".into()
+    };
+
+    msg.channel_id.say(&ctx.http, s).await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Sets if new accounts are kicked or quarantined with the mute role")]
+#[usage("[kick|quarantine]")]
+async fn action(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    conf.age_gate_quarantine = match args.rest() {
+        "kick" => false,
+        "quarantine" | "mute" => true,
+        _ => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    "Error: Action must be either `kick` or `quarantine`",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    conf.save(&ctx).await?;
+
+    let s = if !conf.age_gate_quarantine {
+        "New accounts will now be kicked"
+    } else if conf.mute_role.is_some() {
+        "New accounts will now be quarantined with the mute role"
+    } else {
+        "New accounts will now be quarantined, but there is no mute role set so \
+        they will be kicked until you set one with `settings mute role`"
+    };
+
+    msg.channel_id.say(&ctx.http, s).await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Sets the message DMed to new accounts, or turn it `on` / `off`")]
+#[usage("[message|on|off]")]
+async fn dm(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let s = match args.rest() {
+        "" => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Give a message to DM, or `on` / `off`")
+                .await?;
+
+            return Ok(());
+        }
+        "on" | "enable" => {
+            conf.age_gate_dm_enabled = true;
+            "<:online:316354435745972244> Turned on account age gate DMs".to_string()
+        }
+        "off" | "disable" => {
+            conf.age_gate_dm_enabled = false;
+            "<:offline:316354467031416832> Turned off account age gate DMs".to_string()
+        }
+        text => {
+            conf.age_gate_dm_text.replace(text.into());
+            format!("Updated account age gate DM to: {}", text)
+        }
+    };
+
+    conf.save(&ctx).await?;
+
+    msg.channel_id.say(&ctx.http, s).await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Allows a user to join regardless of their account age")]
+#[usage("[user ID or mention]")]
+async fn allow(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let user_id = match parse_id(args.rest()) {
+        Some(id) => id as i64,
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Invalid user ID given")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let allowlist = conf.age_gate_allowlist.get_or_insert_with(Vec::new);
+
+    if allowlist.contains(&user_id) {
+        msg.channel_id
+            .say(&ctx.http, "Error: User is already allowed")
+            .await?;
+
+        return Ok(());
+    }
+
+    allowlist.push(user_id);
+    conf.save(&ctx).await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!("<@{}> can now join regardless of account age", user_id),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Removes a user from the account age gate allowlist")]
+#[usage("[user ID or mention]")]
+async fn disallow(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let user_id = match parse_id(args.rest()) {
+        Some(id) => id as i64,
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Invalid user ID given")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let allowlist = conf.age_gate_allowlist.get_or_insert_with(Vec::new);
+
+    if !allowlist.contains(&user_id) {
+        msg.channel_id
+            .say(&ctx.http, "Error: User is not in the allowlist")
+            .await?;
+
+        return Ok(());
+    }
+
+    allowlist.retain(|&id| id != user_id);
+
+    if allowlist.is_empty() {
+        conf.age_gate_allowlist = None;
+    }
+
+    conf.save(&ctx).await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!("Removed <@{}> from the account age gate allowlist", user_id),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Lists users that bypass the account age gate")]
+async fn allowlist(ctx: &Context, msg: &Message) -> CommandResult {
+    let conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let users = match conf.age_gate_allowlist {
+        Some(u) if !u.is_empty() => u,
+        _ => {
+            msg.channel_id
+                .say(&ctx.http, "There are no users in the allowlist")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let users_str = users
+        .into_iter()
+        .map(|id| format!("<@{}> `{}`", id as u64, id as u64))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Account Age Gate Allowlist");
+                e.color(0xe67e22);
+
+                e.description(users_str);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
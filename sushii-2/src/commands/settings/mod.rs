@@ -1,14 +1,31 @@
 use serenity::framework::standard::macros::group;
 
+mod age_gate;
 mod default;
 mod disable_channel;
 mod list;
+mod msg_log_ignore;
+mod msg_retention;
 mod mute;
 
-use self::{default::*, disable_channel::*, list::*, mute::*};
+use self::{
+    age_gate::*, default::*, disable_channel::*, list::*, msg_log_ignore::*, msg_retention::*,
+    mute::*,
+};
 
 #[group]
-#[commands(list, mute, disablechannel, enablechannel, disabledchannels)]
+#[commands(
+    list,
+    mute,
+    agegate,
+    msgretention,
+    disablechannel,
+    enablechannel,
+    disabledchannels,
+    msglogignore,
+    msglogunignore,
+    msglogignored
+)]
 #[description("Guild settings, requires MANAGE_GUILD permissions")]
 #[prefix("settings")]
 #[only_in("guild")]
@@ -0,0 +1,145 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::model::sql::*;
+use crate::utils::guild::{fmt_targets, parse_targets};
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Ignores channels, categories or roles in the message log")]
+#[usage("[channels, categories or roles]")]
+async fn msglogignore(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut guild_conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let (channels, roles) = match parse_targets(&ctx, msg, args.rest()).await {
+        Some(t) if !t.0.is_empty() || !t.1.is_empty() => t,
+        _ => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    "Error: Please give channels, categories or roles to ignore",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let targets_str = fmt_targets(&channels, &roles);
+
+    for (ignored, new_ids) in vec![
+        (&mut guild_conf.log_msg_ignore_channels, channels),
+        (&mut guild_conf.log_msg_ignore_roles, roles),
+    ] {
+        let ignored = ignored.get_or_insert_with(Vec::new);
+
+        ignored.extend(new_ids);
+        ignored.sort_unstable();
+        ignored.dedup();
+    }
+
+    guild_conf.save(ctx).await?;
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Ignored in Message Log");
+                e.color(0xe67e22);
+
+                e.description(targets_str);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Stops ignoring channels, categories or roles in the message log")]
+#[usage("[channels, categories or roles]")]
+async fn msglogunignore(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut guild_conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let (channels, roles) = match parse_targets(&ctx, msg, args.rest()).await {
+        Some(t) if !t.0.is_empty() || !t.1.is_empty() => t,
+        _ => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    "Error: Please give channels, categories or roles to stop ignoring",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let targets_str = fmt_targets(&channels, &roles);
+
+    for (ignored, removed_ids) in vec![
+        (&mut guild_conf.log_msg_ignore_channels, channels),
+        (&mut guild_conf.log_msg_ignore_roles, roles),
+    ] {
+        if let Some(ids) = ignored {
+            ids.retain(|id| !removed_ids.contains(id));
+
+            if ids.is_empty() {
+                *ignored = None;
+            }
+        }
+    }
+
+    guild_conf.save(ctx).await?;
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("No Longer Ignored in Message Log");
+                e.color(0x2ecc71);
+
+                e.description(targets_str);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Lists channels, categories and roles ignored in the message log")]
+async fn msglogignored(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let channels = guild_conf.log_msg_ignore_channels.unwrap_or_default();
+    let roles = guild_conf.log_msg_ignore_roles.unwrap_or_default();
+
+    if channels.is_empty() && roles.is_empty() {
+        msg.channel_id
+            .say(&ctx.http, "There are no ignored channels or roles")
+            .await?;
+
+        return Ok(());
+    }
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Ignored in Message Log");
+                e.color(0xe67e22);
+
+                e.description(fmt_targets(&channels, &roles));
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::model::sql::*;
+
+#[command]
+#[sub_commands(count, age)]
+#[required_permissions("MANAGE_GUILD")]
+async fn msgretention(ctx: &Context, msg: &Message) -> CommandResult {
+    let _ = msg
+        .channel_id
+        .say(
+            &ctx.http,
+            "Available sub-commands for `msgretention` are `count`, `age`",
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Sets how many messages per channel are kept for the message log")]
+#[usage("[number|default]")]
+#[example("500")]
+async fn count(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    conf.log_msg_retention_count = match args.rest() {
+        "default" | "reset" => None,
+        s => match s.parse::<i32>() {
+            Ok(n) if n > 0 && n <= SavedMessage::MAX_RETENTION_COUNT => Some(n),
+            _ => {
+                msg.channel_id
+                    .say(
+                        &ctx.http,
+                        format!(
+                            "Error: Give a number of messages between 1 and {}, or `default`",
+                            SavedMessage::MAX_RETENTION_COUNT
+                        ),
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        },
+    };
+
+    conf.save(&ctx).await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "The message log will now keep the last {} messages per channel",
+                conf.log_msg_retention_count
+                    .unwrap_or(SavedMessage::DEFAULT_RETENTION_COUNT)
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description(
+    "Sets the max age of messages kept for the message log, or `off` to only limit by count"
+)]
+#[usage("[duration|off]")]
+#[example("24 hours")]
+async fn age(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let duration_str = args.rest();
+
+    if duration_str.is_empty() {
+        msg.channel_id
+            .say(
+                &ctx,
+                "Error: Please provide a max message age or `off` to disable. Example: `24 hours`",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let duration = match duration_str {
+        "off" | "disable" | "0" => None,
+        _ => match crate::utils::duration::parse_duration_std(&duration_str) {
+            Ok(d) if d.as_secs() <= SavedMessage::MAX_RETENTION_SECS as u64 => Some(d),
+            Ok(_) => {
+                msg.channel_id
+                    .say(
+                        &ctx.http,
+                        format!(
+                            "Error: Max message age can't be longer than {} days",
+                            SavedMessage::MAX_RETENTION_SECS / 60 / 60 / 24
+                        ),
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+            Err(e) => {
+                msg.channel_id
+                    .say(
+                        &ctx.http,
+                        format!("Error: Failed to parse duration -- {}", e),
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        },
+    };
+
+    conf.log_msg_retention_secs = duration.map(|d| d.as_secs() as i64);
+    conf.save(&ctx).await?;
+
+    let s = if let Some(dur) = duration {
+        format!(
+            "Messages older than `{}` will no longer be kept for the message log",
+            humantime::format_duration(dur),
+        )
+    } else {
+        "Messages are now only limited by count for the message log".into()
+    };
+
+    msg.channel_id.say(&ctx.http, s).await?;
+
+    Ok(())
+}
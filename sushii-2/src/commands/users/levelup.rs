@@ -0,0 +1,71 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::model::sql::*;
+
+#[command]
+#[description("Turns level up messages for yourself on or off in all servers")]
+#[usage("[on|off]")]
+async fn levelup(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut user_data = UserData::from_id_or_new(ctx, msg.author.id).await?;
+
+    let opt_out = match args.single::<String>().ok().as_deref() {
+        Some("on") => false,
+        Some("off") => true,
+        Some(_) => {
+            msg.channel_id
+                .say(
+                    &ctx,
+                    "Error: Level up messages can only be turned `on` or `off`",
+                )
+                .await?;
+
+            return Ok(());
+        }
+        None => {
+            msg.channel_id
+                .say(
+                    &ctx,
+                    format!(
+                        "Your level up messages are currently **{}**",
+                        if user_data.level_up_opt_out {
+                            "off"
+                        } else {
+                            "on"
+                        }
+                    ),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    if user_data.level_up_opt_out == opt_out {
+        msg.channel_id
+            .say(
+                &ctx,
+                format!(
+                    "Error: Your level up messages are already {}",
+                    if opt_out { "off" } else { "on" }
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    user_data.level_up_opt_out = opt_out;
+    user_data.save(ctx).await?;
+
+    let s = if opt_out {
+        "<:offline:316354467031416832> Turned off your level up messages"
+    } else {
+        "<:online:316354435745972244> Turned on your level up messages"
+    };
+
+    msg.channel_id.say(&ctx, s).await?;
+
+    Ok(())
+}
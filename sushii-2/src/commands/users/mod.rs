@@ -2,13 +2,15 @@ use serenity::framework::standard::macros::group;
 
 mod avatar;
 mod fishy;
+mod levelup;
+mod names;
 mod rank;
 mod rep;
 mod userinfo;
 
-use self::{avatar::*, fishy::*, rank::*, rep::*, userinfo::*};
+use self::{avatar::*, fishy::*, levelup::*, names::*, rank::*, rep::*, userinfo::*};
 
 #[group]
-#[commands(avatar, rank, rep, fishy, userinfo)]
+#[commands(avatar, rank, rep, fishy, userinfo, names, levelup)]
 #[only_in("guild")]
 pub struct Users;
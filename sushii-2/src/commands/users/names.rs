@@ -0,0 +1,103 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::fmt::Write;
+
+use crate::model::sql::NameHistory;
+use crate::utils::user::parse_id;
+
+#[command]
+#[aliases("namehistory", "nicknames")]
+#[only_in("guild")]
+#[description("Lists past usernames and nicknames of a user")]
+#[usage("[user]")]
+async fn names(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => {
+            msg.channel_id.say(&ctx.http, "No guild found").await?;
+
+            return Ok(());
+        }
+    };
+
+    let target_str = args.rest();
+
+    let target_id = match parse_id(target_str) {
+        Some(id) => UserId(id),
+        None => {
+            if !target_str.is_empty() {
+                msg.channel_id
+                    .say(ctx, "Error: Invalid user given.")
+                    .await?;
+
+                return Ok(());
+            }
+
+            // If empty use self
+            msg.author.id
+        }
+    };
+
+    let user = match target_id.to_user(&ctx).await {
+        Ok(u) => u,
+        Err(_) => {
+            msg.reply(
+                &ctx,
+                "Error: Failed to fetch user, are you using a correct user ID?",
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    let history = NameHistory::from_user(&ctx, guild_id, target_id, 25).await?;
+
+    if history.is_empty() {
+        msg.channel_id
+            .say(
+                &ctx.http,
+                format!("No name changes found for {}", user.tag()),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let mut s = String::new();
+
+    for entry in history {
+        writeln!(
+            s,
+            "`{}` {} {}",
+            entry.changed_at.format("%Y-%m-%d %H:%M"),
+            if entry.guild_id.is_some() {
+                "Nickname"
+            } else {
+                "Username"
+            },
+            entry.name
+        )?;
+    }
+
+    msg.channel_id
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                e.author(|a| {
+                    a.icon_url(user.face());
+                    a.name(format!("Name history for {}", user.tag()));
+
+                    a
+                });
+
+                e.description(s);
+                e.color(0xe67e22);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ use serenity::model::prelude::*;
 use serenity::prelude::*;
 use std::fmt::Write;
 
+use crate::model::sql::VoiceActivity;
 use crate::utils::user::parse_id;
 
 #[command]
@@ -89,6 +90,22 @@ async fn userinfo(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
             )?;
         }
 
+        if let Some(activity) = VoiceActivity::from_id(&ctx, guild_id, target_id).await? {
+            let voice_time = activity.total_duration(now.naive_utc());
+
+            if voice_time > Duration::zero() {
+                writeln!(
+                    user_str,
+                    "**Voice Time:** {}",
+                    humantime::format_duration(
+                        Duration::minutes(voice_time.num_minutes())
+                            .to_std()
+                            .unwrap()
+                    )
+                )?;
+            }
+        }
+
         if !member.roles.is_empty() {
             write!(user_str, "**Roles:**")?;
         }
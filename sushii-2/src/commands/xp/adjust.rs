@@ -0,0 +1,186 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::model::sql::*;
+use crate::model::Confirmation;
+use crate::utils::user::parse_id;
+
+/// Max XP that can be given, removed or set at once
+pub(super) const MAX_XP_CHANGE: i64 = 1_000_000_000;
+
+#[derive(Clone, Copy)]
+enum XpAction {
+    Add,
+    Remove,
+    Set,
+}
+
+impl XpAction {
+    fn name(self) -> &'static str {
+        match self {
+            XpAction::Add => "add",
+            XpAction::Remove => "remove",
+            XpAction::Set => "set",
+        }
+    }
+
+    fn apply(self, user_level: UserLevel, amount: i64) -> UserLevel {
+        match self {
+            XpAction::Add => user_level.adjust(amount),
+            XpAction::Remove => user_level.adjust(-amount),
+            XpAction::Set => user_level.set_xp(amount),
+        }
+    }
+}
+
+/// Gets a member's level, members without any XP start at 0
+async fn get_user_level(ctx: &Context, user_id: UserId, guild_id: GuildId) -> Result<UserLevel> {
+    match UserLevel::from_id(&ctx, user_id, guild_id).await? {
+        Some(l) => Ok(l),
+        None => Ok(UserLevel::new(user_id, guild_id).set_xp(0)),
+    }
+}
+
+async fn adjust_xp(
+    ctx: &Context,
+    msg: &Message,
+    mut args: Args,
+    action: XpAction,
+) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let user = match args.single::<String>().ok().and_then(parse_id) {
+        Some(id) => match UserId(id).to_user(&ctx).await {
+            Ok(u) => u,
+            Err(_) => {
+                msg.channel_id
+                    .say(&ctx.http, "Error: Failed to fetch user")
+                    .await?;
+
+                return Ok(());
+            }
+        },
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a user")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let amount = match args.single::<i64>() {
+        Ok(n) if (0..=MAX_XP_CHANGE).contains(&n) => n,
+        _ => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    format!(
+                        "Error: Please give an amount of XP between 0 and {}",
+                        MAX_XP_CHANGE
+                    ),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let user_level = get_user_level(ctx, user.id, guild_id).await?;
+    let preview = action.apply(user_level.clone(), amount);
+
+    let user_tag = user.tag();
+    let old_xp = user_level.msg_all_time.0;
+    let old_level = user_level.level();
+    let new_xp = preview.msg_all_time.0;
+    let new_level = preview.level();
+
+    if old_xp == new_xp {
+        msg.channel_id
+            .say(
+                &ctx.http,
+                format!("Error: {} already has {} XP", user_tag, old_xp),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let mut conf = Confirmation::new(msg.author.id, move |e| {
+        e.title(format!("Change XP for {}?", user_tag));
+        e.description(format!(
+            "XP: {} → {}\nLevel: {} → {}",
+            old_xp, new_xp, old_level, new_level
+        ));
+        e.color(0xe67e22);
+
+        e
+    })
+    .options(vec![
+        (ReactionType::Unicode("✅".into()), "confirm"),
+        (ReactionType::Unicode("❌".into()), "cancel"),
+    ]);
+
+    if conf.await_confirmation(&ctx, msg.channel_id).await? != Some("confirm") {
+        msg.channel_id.say(&ctx.http, "Cancelled").await?;
+
+        return Ok(());
+    }
+
+    // Fetch again since XP could have changed while waiting for confirmation
+    let user_level = get_user_level(ctx, user.id, guild_id).await?;
+    let old_xp = user_level.msg_all_time.0;
+    let user_level = action.apply(user_level, amount).save(&ctx).await?;
+
+    XpAdjustment::new(guild_id, msg.author.id, action.name())
+        .user(user.id, old_xp, user_level.msg_all_time.0)
+        .save(&ctx)
+        .await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "Updated XP for {}: {} → {} (level {}), use `levels sync` to update level role rewards",
+                user.tag(),
+                old_xp,
+                user_level.msg_all_time.0,
+                user_level.level()
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Gives XP to a member")]
+#[usage("[user] [amount]")]
+#[example("@user 500")]
+async fn add(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    adjust_xp(ctx, msg, args, XpAction::Add).await
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Removes XP from a member")]
+#[usage("[user] [amount]")]
+#[example("@user 500")]
+async fn remove(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    adjust_xp(ctx, msg, args, XpAction::Remove).await
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Sets a member's total XP")]
+#[usage("[user] [amount]")]
+#[example("@user 500")]
+async fn set(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    adjust_xp(ctx, msg, args, XpAction::Set).await
+}
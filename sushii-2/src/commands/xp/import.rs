@@ -0,0 +1,443 @@
+use serde_json::Value;
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use super::adjust::MAX_XP_CHANGE;
+use crate::model::sql::*;
+use crate::model::Confirmation;
+use sushii_model::model::user::user_level::{checked_next_level, get_level};
+
+/// Max size of an uploaded export
+const MAX_IMPORT_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Keys of the list of users in JSON exports. Keys are compared lowercase
+/// without spaces or underscores, so `userid` matches `user_id` and `User ID`
+const LIST_KEYS: &[&str] = &[
+    "players",
+    "users",
+    "members",
+    "leaderboard",
+    "levels",
+    "data",
+];
+const ID_KEYS: &[&str] = &["id", "userid", "user", "memberid", "discordid"];
+const XP_KEYS: &[&str] = &["xp", "exp", "experience", "totalxp", "points"];
+const LEVEL_KEYS: &[&str] = &["level", "lvl"];
+
+/// Number of top users shown in the import preview
+const PREVIEW_COUNT: usize = 5;
+
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn parse_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Gets XP from an XP or level field, XP is used if both are given. XP is
+/// limited to the same max as XP adjustments
+fn parse_xp(xp: Option<i64>, level: Option<i64>) -> Option<i64> {
+    xp.or_else(|| level.filter(|l| *l >= 0).and_then(checked_next_level))
+        .filter(|xp| (0..=MAX_XP_CHANGE).contains(xp))
+}
+
+/// Gets a field from an object with normalized keys
+fn get_field<'a>(obj: &'a serde_json::Map<String, Value>, keys: &[&str]) -> Option<&'a Value> {
+    obj.iter()
+        .find(|(k, _)| keys.contains(&normalize_key(k).as_str()))
+        .map(|(_, v)| v)
+}
+
+fn parse_json_entry(id: Option<u64>, value: &Value) -> Option<(u64, i64)> {
+    match value {
+        // Maps of user ID to XP
+        Value::Number(_) | Value::String(_) => Some((id?, parse_xp(parse_i64(value), None)?)),
+        Value::Object(obj) => {
+            let id = get_field(obj, ID_KEYS).and_then(parse_u64).or(id)?;
+            let xp = parse_xp(
+                get_field(obj, XP_KEYS).and_then(parse_i64),
+                get_field(obj, LEVEL_KEYS).and_then(parse_i64),
+            )?;
+
+            Some((id, xp))
+        }
+        _ => None,
+    }
+}
+
+fn parse_json(s: &str) -> Result<Vec<(u64, i64)>, String> {
+    let value: Value = serde_json::from_str(s).map_err(|e| format!("Invalid JSON, {}", e))?;
+
+    let entries: Vec<(Option<u64>, &Value)> = match value {
+        Value::Array(ref entries) => entries.iter().map(|e| (None, e)).collect(),
+        Value::Object(ref obj) => match get_field(obj, LIST_KEYS) {
+            Some(Value::Array(entries)) => entries.iter().map(|e| (None, e)).collect(),
+            // Object with user IDs as keys
+            _ => obj.iter().map(|(k, v)| (k.parse().ok(), v)).collect(),
+        },
+        _ => return Err("JSON should be a list of users".into()),
+    };
+
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, (id, entry))| {
+            parse_json_entry(id, entry).ok_or_else(|| {
+                format!(
+                    "Invalid user #{}, users need an ID and XP between 0 and {}",
+                    i + 1,
+                    MAX_XP_CHANGE
+                )
+            })
+        })
+        .collect()
+}
+
+/// Splits a CSV line into trimmed fields. Delimiters in quoted fields are
+/// kept and doubled quotes are unescaped, quoted fields can't span lines
+fn split_csv_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => {
+                fields.push(field.trim().to_string());
+                field.clear();
+            }
+            c => field.push(c),
+        }
+    }
+
+    fields.push(field.trim().to_string());
+
+    fields
+}
+
+fn parse_csv(s: &str) -> Result<Vec<(u64, i64)>, String> {
+    // Delimiter that appears the most in the first line
+    let first_line = s.lines().next().unwrap_or_default();
+    let delimiter = [',', ';', '\t']
+        .iter()
+        .copied()
+        .max_by_key(|&d| first_line.matches(d).count())
+        .unwrap_or(',');
+
+    let mut lines = s
+        .lines()
+        .map(|l| split_csv_line(l, delimiter))
+        .filter(|fields| fields.iter().any(|f| !f.is_empty()))
+        .peekable();
+
+    let find_column = |header: &[String], keys: &[&str]| {
+        header
+            .iter()
+            .position(|f| keys.contains(&normalize_key(f).as_str()))
+    };
+
+    // Without a header the columns are ID and XP
+    let (id_col, xp_col, level_col) = match lines.peek() {
+        Some(header) if header.iter().any(|f| f.parse::<u64>().is_err()) => {
+            let cols = (
+                find_column(header, ID_KEYS),
+                find_column(header, XP_KEYS),
+                find_column(header, LEVEL_KEYS),
+            );
+            lines.next();
+
+            match cols {
+                (Some(id), xp, level) if xp.is_some() || level.is_some() => (id, xp, level),
+                _ => return Err("CSV header should have an ID and XP or level column".into()),
+            }
+        }
+        _ => (0, Some(1), None),
+    };
+
+    lines
+        .enumerate()
+        .map(|(i, fields)| {
+            let get =
+                |col: Option<usize>| col.and_then(|c| fields.get(c)).and_then(|f| f.parse().ok());
+
+            let id = fields.get(id_col).and_then(|f| f.parse().ok());
+            let xp = parse_xp(get(xp_col), get(level_col));
+
+            id.zip(xp).ok_or_else(|| {
+                format!(
+                    "Invalid user on row {}, users need an ID and XP between 0 and {}",
+                    i + 1,
+                    MAX_XP_CHANGE
+                )
+            })
+        })
+        .collect()
+}
+
+/// Parses a JSON or CSV export of user IDs with XP or levels. Duplicate users
+/// use the last entry
+fn parse_import(s: &str) -> Result<Vec<(u64, i64)>, String> {
+    let s = s.trim_start_matches('\u{feff}').trim();
+
+    let users = if s.starts_with('{') || s.starts_with('[') {
+        parse_json(s)?
+    } else {
+        parse_csv(s)?
+    };
+
+    let mut deduped = HashMap::new();
+    for (id, xp) in users {
+        deduped.insert(id, xp);
+    }
+
+    let mut users: Vec<(u64, i64)> = deduped.into_iter().collect();
+    users.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    Ok(users)
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description(
+    "Imports XP from another bot's JSON or CSV export with user IDs and XP or \
+    levels, up to 1,000,000,000 XP per user. Modes are `max` to keep the \
+    higher XP (default), `add` to add to current XP, or `overwrite` to replace \
+    current XP"
+)]
+#[usage("[max|add|overwrite] (attach export file)")]
+async fn import(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let mode_str = args.rest().trim();
+    let mode = if mode_str.is_empty() {
+        XpImportMode::Max
+    } else {
+        match XpImportMode::from_str(mode_str) {
+            Ok(m) => m,
+            Err(_) => {
+                msg.channel_id
+                    .say(
+                        &ctx.http,
+                        "Error: Invalid mode, available modes are `max`, `add` and `overwrite`",
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        }
+    };
+
+    let attachment = match msg.attachments.first() {
+        Some(a) if a.size <= MAX_IMPORT_SIZE => a,
+        Some(_) => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Export file is too large")
+                .await?;
+
+            return Ok(());
+        }
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please attach a JSON or CSV export file")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let content = match String::from_utf8(attachment.download().await?) {
+        Ok(c) => c,
+        Err(_) => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Export file is not valid text")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let users = match parse_import(&content) {
+        Ok(u) if !u.is_empty() => u,
+        Ok(_) => {
+            msg.channel_id
+                .say(&ctx.http, "Error: No users found in export file")
+                .await?;
+
+            return Ok(());
+        }
+        Err(e) => {
+            msg.channel_id
+                .say(&ctx.http, format!("Error: {}", e))
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let mut preview = String::new();
+    for (id, xp) in users.iter().take(PREVIEW_COUNT) {
+        writeln!(preview, "<@{}>: {} XP (level {})", id, xp, get_level(*xp))?;
+    }
+
+    let user_count = users.len();
+    let total_xp = users
+        .iter()
+        .try_fold(0i64, |total, u| total.checked_add(u.1))
+        .unwrap_or(i64::MAX);
+
+    let mut conf = Confirmation::new(msg.author.id, move |e| {
+        e.title(format!("Import XP for {} users?", user_count));
+        e.description(format!(
+            "Top users:\n{}\nTotal XP: {}\nMode: `{}`",
+            preview,
+            total_xp,
+            mode.as_str()
+        ));
+        e.color(0xe67e22);
+
+        e
+    })
+    .options(vec![
+        (ReactionType::Unicode("✅".into()), "confirm"),
+        (ReactionType::Unicode("❌".into()), "cancel"),
+    ]);
+
+    if conf.await_confirmation(&ctx, msg.channel_id).await? != Some("confirm") {
+        msg.channel_id.say(&ctx.http, "Cancelled").await?;
+
+        return Ok(());
+    }
+
+    let users: Vec<(i64, i64)> = users.into_iter().map(|(id, xp)| (id as i64, xp)).collect();
+    let count = UserLevel::import(&ctx, guild_id, &users, mode).await?;
+
+    XpAdjustment::new(guild_id, msg.author.id, "import")
+        .save(&ctx)
+        .await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "Imported XP for {} users, use `levels sync` to give level role rewards",
+                count
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sushii_model::model::user::user_level::next_level;
+
+    #[test]
+    fn parses_json_import() {
+        let users = parse_import(
+            r#"{"players": [
+                {"id": "145764790046818304", "xp": 1500, "level": 5},
+                {"id": 167058919611564043, "level": 3}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            users,
+            vec![
+                (145764790046818304, 1500),
+                (167058919611564043, next_level(3))
+            ]
+        );
+
+        let users = parse_import(r#"{"145764790046818304": 20}"#).unwrap();
+        assert_eq!(users, vec![(145764790046818304, 20)]);
+
+        assert!(parse_import(r#"[{"id": "abc", "xp": 5}]"#).is_err());
+    }
+
+    #[test]
+    fn limits_import_xp() {
+        assert_eq!(parse_xp(None, Some(25)), Some(next_level(25)));
+        assert_eq!(parse_xp(Some(20), Some(25)), Some(20));
+        assert_eq!(parse_xp(None, Some(i64::MAX)), None);
+        assert_eq!(parse_xp(None, Some(-3)), None);
+
+        let users = parse_import(r#"{"145764790046818304": 1000000000}"#).unwrap();
+        assert_eq!(users, vec![(145764790046818304, MAX_XP_CHANGE)]);
+
+        assert!(parse_import(r#"{"145764790046818304": 1000000001}"#).is_err());
+        assert!(parse_import(r#"{"145764790046818304": -5}"#).is_err());
+        assert!(parse_import(r#"[{"id": "145764790046818304", "level": 9999999999}]"#).is_err());
+        assert!(parse_import("145764790046818304,99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn parses_csv_import() {
+        let users =
+            parse_import("User ID,Level\n145764790046818304,2\n167058919611564043,4\n").unwrap();
+
+        assert_eq!(
+            users,
+            vec![
+                (167058919611564043, next_level(4)),
+                (145764790046818304, next_level(2))
+            ]
+        );
+
+        // No header, duplicates use last entry
+        let users = parse_import("145764790046818304,100\n145764790046818304,50").unwrap();
+        assert_eq!(users, vec![(145764790046818304, 50)]);
+
+        assert!(parse_import("name,rank\nfoo,1").is_err());
+    }
+
+    #[test]
+    fn parses_quoted_csv_import() {
+        let users = parse_import(
+            "Name,User ID,XP\n\
+            \"Doe, John\",145764790046818304,100\n\
+            \"Smith \"\"JS\"\", Jr\",\"167058919611564043\",50\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            users,
+            vec![(145764790046818304, 100), (167058919611564043, 50)]
+        );
+
+        // Semicolons as the delimiter keep commas in unquoted names
+        let users = parse_import("name;id;xp\nDoe, John;145764790046818304;100").unwrap();
+        assert_eq!(users, vec![(145764790046818304, 100)]);
+    }
+}
@@ -0,0 +1,21 @@
+use serenity::framework::standard::macros::group;
+
+mod adjust;
+mod import;
+mod reset;
+mod rules;
+mod season;
+
+use self::{adjust::*, import::*, reset::*, rules::*, season::*};
+
+#[group]
+#[commands(
+    settings, range, cooldown, ignore, unignore, multiplier, voice, add, remove, set, reset,
+    import, season
+)]
+#[description("Manage member XP and how it's earned, requires MANAGE_GUILD permissions")]
+#[default_command(settings)]
+#[prefix("xp")]
+#[only_in("guild")]
+#[required_permissions("MANAGE_GUILD")]
+pub struct Xp;
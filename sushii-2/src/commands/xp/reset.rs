@@ -0,0 +1,123 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::model::sql::*;
+use crate::model::Confirmation;
+use crate::utils::user::parse_id;
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Resets a member's XP, or XP of all members with `all`. This can't be undone")]
+#[usage("[user|all]")]
+async fn reset(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let target = args.rest().trim();
+
+    if target == "all" {
+        let mut conf = Confirmation::new(msg.author.id, |e| {
+            e.title("Reset XP for all members?");
+            e.description(
+                "This will reset the XP of every member in this server and can't be undone.",
+            );
+            e.color(0xe74c3c);
+
+            e
+        })
+        .options(vec![
+            (ReactionType::Unicode("✅".into()), "confirm"),
+            (ReactionType::Unicode("❌".into()), "cancel"),
+        ]);
+
+        if conf.await_confirmation(&ctx, msg.channel_id).await? != Some("confirm") {
+            msg.channel_id.say(&ctx.http, "Cancelled").await?;
+
+            return Ok(());
+        }
+
+        let count = UserLevel::delete_guild(&ctx, guild_id).await?;
+
+        XpAdjustment::new(guild_id, msg.author.id, "reset")
+            .save(&ctx)
+            .await?;
+
+        msg.channel_id
+            .say(&ctx.http, format!("Reset XP for {} members", count))
+            .await?;
+
+        return Ok(());
+    }
+
+    let user = match parse_id(target) {
+        Some(id) => match UserId(id).to_user(&ctx).await {
+            Ok(u) => u,
+            Err(_) => {
+                msg.channel_id
+                    .say(&ctx.http, "Error: Failed to fetch user")
+                    .await?;
+
+                return Ok(());
+            }
+        },
+        None => {
+            msg.channel_id
+                .say(&ctx.http, "Error: Please give a user or `all`")
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let user_level = match UserLevel::from_id(&ctx, user.id, guild_id).await? {
+        Some(l) => l,
+        None => {
+            msg.channel_id
+                .say(&ctx.http, format!("Error: {} has no XP", user.tag()))
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let user_tag = user.tag();
+    let xp = user_level.msg_all_time.0;
+    let level = user_level.level();
+
+    let mut conf = Confirmation::new(msg.author.id, move |e| {
+        e.title(format!("Reset XP for {}?", user_tag));
+        e.description(format!(
+            "They currently have {} XP and are level {}. This can't be undone.",
+            xp, level
+        ));
+        e.color(0xe74c3c);
+
+        e
+    })
+    .options(vec![
+        (ReactionType::Unicode("✅".into()), "confirm"),
+        (ReactionType::Unicode("❌".into()), "cancel"),
+    ]);
+
+    if conf.await_confirmation(&ctx, msg.channel_id).await? != Some("confirm") {
+        msg.channel_id.say(&ctx.http, "Cancelled").await?;
+
+        return Ok(());
+    }
+
+    if let Some(deleted) = UserLevel::delete(&ctx, user.id, guild_id).await? {
+        XpAdjustment::new(guild_id, msg.author.id, "reset")
+            .user(user.id, deleted.msg_all_time.0, 0)
+            .save(&ctx)
+            .await?;
+    }
+
+    msg.channel_id
+        .say(&ctx.http, format!("Reset XP for {}", user.tag()))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,444 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::handlers::voice_xp;
+use crate::model::sql::*;
+use crate::utils::duration::parse_duration_std;
+use crate::utils::guild::{fmt_targets, parse_targets};
+
+/// Max XP that can be given per message
+const MAX_XP: i32 = 1000;
+
+/// Max XP that can be given per minute in voice channels
+const MAX_VOICE_XP: i32 = 100;
+
+/// Max multiplier for a channel or role
+const MAX_MULTIPLIER: f64 = 10.0;
+
+/// Max cooldown between messages earning XP, 1 day
+const MAX_COOLDOWN_SECS: u64 = 24 * 60 * 60;
+
+fn fmt_multipliers(multipliers: &HashMap<i64, f64>, mention: &str) -> String {
+    let mut multipliers: Vec<_> = multipliers.iter().collect();
+    multipliers.sort_by_key(|m| *m.0);
+
+    multipliers
+        .into_iter()
+        .map(|(id, m)| format!("<{}{}> x{}", mention, *id as u64, m))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Shows how members earn XP in this server")]
+async fn settings(ctx: &Context, msg: &Message) -> CommandResult {
+    let conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let (min, max) = conf.xp_range();
+    let cooldown = Duration::from_secs(conf.xp_cooldown() as u64);
+
+    let ignored = fmt_targets(
+        &conf.xp_ignore_channels.clone().unwrap_or_default(),
+        &conf.xp_ignore_roles.clone().unwrap_or_default(),
+    );
+
+    let mut multipliers = String::new();
+    for s in &[
+        fmt_multipliers(&conf.xp_channel_multipliers(), "#"),
+        fmt_multipliers(&conf.xp_role_multipliers(), "@&"),
+    ] {
+        if !s.is_empty() {
+            writeln!(multipliers, "{}", s)?;
+        }
+    }
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("XP Settings");
+                e.color(0xe67e22);
+
+                e.field(
+                    "XP per Message",
+                    if min == max {
+                        min.to_string()
+                    } else {
+                        format!("{} - {}", min, max)
+                    },
+                    true,
+                );
+                e.field("Cooldown", humantime::format_duration(cooldown), true);
+                e.field(
+                    "Voice XP per Minute",
+                    if conf.voice_xp_enabled {
+                        conf.voice_xp_rate().to_string()
+                    } else {
+                        "Disabled".into()
+                    },
+                    true,
+                );
+                e.field(
+                    "Ignored",
+                    if ignored.is_empty() {
+                        "None".into()
+                    } else {
+                        ignored
+                    },
+                    false,
+                );
+                e.field(
+                    "Multipliers",
+                    if multipliers.is_empty() {
+                        "None".into()
+                    } else {
+                        multipliers
+                    },
+                    false,
+                );
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Sets the min and max XP given per message, a random amount in between is given")]
+#[usage("[min] [max]")]
+#[example("15 25")]
+async fn range(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let min = args.single::<i32>().ok();
+    // Max is optional to give a fixed amount
+    let max = args.single::<i32>().ok().or(min);
+
+    let (min, max) = match (min, max) {
+        (Some(min), Some(max)) if (0..=max).contains(&min) && max <= MAX_XP => (min, max),
+        _ => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    format!(
+                        "Error: Please give a min and max XP between 0 and {}, with min not greater than max",
+                        MAX_XP
+                    ),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    conf.xp_min = Some(min);
+    conf.xp_max = Some(max);
+    conf.save(&ctx).await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!("Members will now get {} - {} XP per message", min, max),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Sets how long members have to wait between messages to earn XP")]
+#[usage("[duration|default]")]
+#[example("2 minutes")]
+async fn cooldown(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let duration_str = args.rest().trim();
+
+    if duration_str.is_empty() {
+        msg.channel_id
+            .say(
+                &ctx,
+                "Error: Please provide a cooldown or `default`. Example: `2 minutes`",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    conf.xp_cooldown_secs = match duration_str {
+        "default" | "reset" => None,
+        "0" => Some(0),
+        _ => match parse_duration_std(&duration_str) {
+            Ok(d) if d.as_secs() <= MAX_COOLDOWN_SECS => Some(d.as_secs() as i64),
+            Ok(_) => {
+                msg.channel_id
+                    .say(&ctx.http, "Error: Cooldown can't be longer than 1 day")
+                    .await?;
+
+                return Ok(());
+            }
+            Err(e) => {
+                msg.channel_id
+                    .say(
+                        &ctx.http,
+                        format!("Error: Failed to parse duration -- {}", e),
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        },
+    };
+
+    conf.save(&ctx).await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "Members can now earn XP once every `{}`",
+                humantime::format_duration(Duration::from_secs(conf.xp_cooldown() as u64))
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Stops channels, categories or roles from earning XP")]
+#[usage("[channels, categories or roles]")]
+async fn ignore(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let (channels, roles) = match parse_targets(&ctx, msg, args.rest()).await {
+        Some(t) if !t.0.is_empty() || !t.1.is_empty() => t,
+        _ => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    "Error: Please give channels, categories or roles to ignore",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let targets_str = fmt_targets(&channels, &roles);
+
+    for (ignored, new_ids) in vec![
+        (&mut conf.xp_ignore_channels, channels),
+        (&mut conf.xp_ignore_roles, roles),
+    ] {
+        let ignored = ignored.get_or_insert_with(Vec::new);
+
+        ignored.extend(new_ids);
+        ignored.sort_unstable();
+        ignored.dedup();
+    }
+
+    conf.save(ctx).await?;
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("No Longer Earning XP");
+                e.color(0xe67e22);
+
+                e.description(targets_str);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Lets ignored channels, categories or roles earn XP again")]
+#[usage("[channels, categories or roles]")]
+async fn unignore(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let (channels, roles) = match parse_targets(&ctx, msg, args.rest()).await {
+        Some(t) if !t.0.is_empty() || !t.1.is_empty() => t,
+        _ => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    "Error: Please give channels, categories or roles to stop ignoring",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let targets_str = fmt_targets(&channels, &roles);
+
+    for (ignored, removed_ids) in vec![
+        (&mut conf.xp_ignore_channels, channels),
+        (&mut conf.xp_ignore_roles, roles),
+    ] {
+        if let Some(ids) = ignored {
+            ids.retain(|id| !removed_ids.contains(id));
+
+            if ids.is_empty() {
+                *ignored = None;
+            }
+        }
+    }
+
+    conf.save(ctx).await?;
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("Earning XP Again");
+                e.color(0x2ecc71);
+
+                e.description(targets_str);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description(
+    "Sets an XP multiplier for channels, categories or roles, use 1 to remove it. \
+    Channel multipliers are used over category multipliers, and members with \
+    multiple roles use the highest role multiplier"
+)]
+#[usage("[multiplier] [channels, categories or roles]")]
+#[example("2 #general @Booster")]
+async fn multiplier(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    let multiplier = match args.single::<f64>() {
+        Ok(m) if (0.0..=MAX_MULTIPLIER).contains(&m) => m,
+        _ => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    format!(
+                        "Error: Please give a multiplier between 0 and {}",
+                        MAX_MULTIPLIER
+                    ),
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let (channels, roles) = match parse_targets(&ctx, msg, args.rest()).await {
+        Some(t) if !t.0.is_empty() || !t.1.is_empty() => t,
+        _ => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    "Error: Please give channels, categories or roles for the multiplier",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    GuildConfig::set_xp_multipliers(&mut conf.xp_channel_multipliers, &channels, multiplier);
+    GuildConfig::set_xp_multipliers(&mut conf.xp_role_multipliers, &roles, multiplier);
+
+    conf.save(ctx).await?;
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title(format!("Set XP Multiplier to x{}", multiplier));
+                e.color(0xe67e22);
+
+                e.description(fmt_targets(&channels, &roles));
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description(
+    "Enables or disables voice XP, or sets the XP given per minute. Members earn \
+    voice XP while unmuted in a non-AFK channel with at least one other unmuted \
+    member. Ignored channels, roles and multipliers also apply"
+)]
+#[usage("[on|off|xp per minute]")]
+#[example("3")]
+async fn voice(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut conf = GuildConfig::from_msg_or_respond(&ctx, msg).await?;
+
+    match args.rest().trim() {
+        "on" | "enable" => conf.voice_xp_enabled = true,
+        "off" | "disable" => conf.voice_xp_enabled = false,
+        s => match s.parse::<i32>() {
+            Ok(n) if (1..=MAX_VOICE_XP).contains(&n) => {
+                conf.voice_xp_enabled = true;
+                conf.voice_xp_per_minute = Some(n);
+            }
+            _ => {
+                msg.channel_id
+                    .say(
+                        &ctx.http,
+                        format!(
+                            "Error: Please give `on`, `off` or the XP per minute between 1 and {}",
+                            MAX_VOICE_XP
+                        ),
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        },
+    }
+
+    conf.save(&ctx).await?;
+
+    // Members already in voice would only be tracked after their next voice
+    // state update
+    if conf.voice_xp_enabled {
+        if let Some(guild) = msg.guild(&ctx).await {
+            voice_xp::track_members(&ctx, &guild, &conf, None).await?;
+        }
+    }
+
+    let response = if conf.voice_xp_enabled {
+        format!(
+            "Members will now get {} XP per minute in voice channels",
+            conf.voice_xp_rate()
+        )
+    } else {
+        "Members will no longer get XP in voice channels".to_string()
+    };
+
+    msg.channel_id.say(&ctx.http, response).await?;
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::fmt::Write;
+
+use crate::model::sql::*;
+use crate::model::Confirmation;
+
+/// Max length of a season name
+const MAX_NAME_LEN: usize = 100;
+
+#[command]
+#[sub_commands(end)]
+#[required_permissions("MANAGE_GUILD")]
+#[description("Lists ended XP seasons, the leaderboard of each season is saved")]
+async fn season(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let seasons = XpSeason::from_guild(&ctx, guild_id).await?;
+
+    if seasons.is_empty() {
+        msg.channel_id
+            .say(
+                &ctx.http,
+                "There are no ended seasons, end the current one with `xp season end`",
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let mut s = String::new();
+
+    for season in &seasons {
+        writeln!(
+            s,
+            "**{}** ended {}",
+            season.name,
+            season.ended_at.format("%Y-%m-%d")
+        )?;
+    }
+
+    msg.channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title(format!("XP Seasons ({})", seasons.len()));
+                e.description(s);
+                e.color(0xe67e22);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[required_permissions("MANAGE_GUILD")]
+#[description(
+    "Ends the current XP season, saving the leaderboard and then resetting all \
+    XP or removing a percentage of everyone's XP"
+)]
+#[usage("[reset|decay percent] (name)")]
+#[example("decay 50 Summer 2021")]
+async fn end(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let decay_percent = match args.single::<String>().ok().as_deref() {
+        Some("reset") => 100,
+        Some("decay") => match args.single::<i64>() {
+            Ok(n) if (1..=100).contains(&n) => n,
+            _ => {
+                msg.channel_id
+                    .say(
+                        &ctx.http,
+                        "Error: Please give a percentage of XP to remove between 1 and 100",
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        },
+        _ => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    "Error: Please give `reset` to reset all XP or `decay [percent]` to remove a percentage of XP",
+                )
+                .await?;
+
+            return Ok(());
+        }
+    };
+
+    let name = match args.rest().trim() {
+        "" => {
+            let count = XpSeason::from_guild(&ctx, guild_id).await?.len();
+
+            format!("Season {}", count + 1)
+        }
+        s if s.chars().count() > MAX_NAME_LEN => {
+            msg.channel_id
+                .say(
+                    &ctx.http,
+                    format!(
+                        "Error: Season name can't be longer than {} characters",
+                        MAX_NAME_LEN
+                    ),
+                )
+                .await?;
+
+            return Ok(());
+        }
+        s => s.to_string(),
+    };
+
+    let description = format!(
+        "The current leaderboard will be saved as **{}** and then {}. This can't be undone.",
+        name,
+        if decay_percent == 100 {
+            "all XP will be reset".to_string()
+        } else {
+            format!("{}% of everyone's XP will be removed", decay_percent)
+        }
+    );
+
+    let mut conf = Confirmation::new(msg.author.id, move |e| {
+        e.title("End XP season?");
+        e.description(description);
+        e.color(0xe74c3c);
+
+        e
+    })
+    .options(vec![
+        (ReactionType::Unicode("✅".into()), "confirm"),
+        (ReactionType::Unicode("❌".into()), "cancel"),
+    ]);
+
+    if conf.await_confirmation(&ctx, msg.channel_id).await? != Some("confirm") {
+        msg.channel_id.say(&ctx.http, "Cancelled").await?;
+
+        return Ok(());
+    }
+
+    let (season, user_count) =
+        XpSeason::end(&ctx, guild_id, msg.author.id, &name, decay_percent).await?;
+
+    msg.channel_id
+        .say(
+            &ctx.http,
+            format!(
+                "Ended **{}** and saved the leaderboard with {} members. Level role rewards aren't removed",
+                season.name, user_count
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
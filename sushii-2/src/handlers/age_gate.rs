@@ -0,0 +1,147 @@
+use chrono::{Duration, Utc};
+use serenity::{model::prelude::*, prelude::*};
+
+use crate::error::Result;
+use crate::model::moderation::ModLogReporter;
+use crate::model::sql::*;
+
+/// Returns true if the member was kicked or quarantined
+pub async fn guild_member_addition(ctx: &Context, guild_id: &GuildId, member: &Member) -> bool {
+    match _guild_member_addition(&ctx, &guild_id, &member).await {
+        Ok(gated) => gated,
+        Err(e) => {
+            tracing::error!("Failed to handle age gate guild_member_addition: {}", e);
+
+            false
+        }
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _guild_member_addition(
+    ctx: &Context,
+    guild_id: &GuildId,
+    member: &Member,
+) -> Result<bool> {
+    let guild_conf = match GuildConfig::from_id(&ctx, &guild_id).await? {
+        Some(c) => c,
+        None => {
+            tracing::error!(
+                ?guild_id,
+                ?member,
+                "No guild config found while handling age gate guild_member_addition"
+            );
+            return Ok(false);
+        }
+    };
+
+    let min_age = match guild_conf.age_gate_duration {
+        Some(secs) => Duration::seconds(secs),
+        None => return Ok(false),
+    };
+
+    if member.user.bot {
+        return Ok(false);
+    }
+
+    if let Some(ref allowlist) = guild_conf.age_gate_allowlist {
+        if allowlist.contains(&(member.user.id.0 as i64)) {
+            return Ok(false);
+        }
+    }
+
+    let age = Utc::now().signed_duration_since(member.user.id.created_at());
+
+    if age >= min_age {
+        return Ok(false);
+    }
+
+    let min_age_str = humantime::format_duration(min_age.to_std().unwrap()).to_string();
+
+    // Quarantine needs a mute role, otherwise fallback to kicking
+    let mute_role = guild_conf
+        .mute_role
+        .filter(|_| guild_conf.age_gate_quarantine);
+
+    let action = if mute_role.is_some() {
+        "quarantined"
+    } else {
+        "kicked"
+    };
+
+    // Quarantine lasts until the account reaches the minimum age, rounded to
+    // seconds for a readable duration
+    let quarantine_duration = Duration::seconds((min_age - age).num_seconds());
+
+    // DM before kicking since users can't be messaged after if they don't
+    // share any other guilds
+    if guild_conf.age_gate_dm_enabled {
+        let guild_name = guild_id
+            .to_guild_cached(&ctx)
+            .await
+            .map(|g| g.name)
+            .unwrap_or_else(|| format!("Unknown Guild (ID: {})", guild_id.0));
+
+        let s = guild_conf.age_gate_dm_text.clone().unwrap_or_else(|| {
+            format!(
+                "Your account is too new to join {}, accounts must be at least {} old.",
+                guild_name, min_age_str
+            )
+        });
+
+        let mut content = format!("You have been {}: {}", action, s);
+
+        if mute_role.is_some() {
+            let dur_str = humantime::format_duration(quarantine_duration.to_std().unwrap());
+            content.push_str(&format!("\nDuration: {}", dur_str));
+        }
+
+        // Ignore if dm fails, could be disabled
+        let _ = member.user.dm(ctx, |m| m.content(content)).await;
+    }
+
+    let reason = Some(format!(
+        "Automated: Account is younger than the minimum age of {}",
+        min_age_str
+    ));
+
+    if let Some(role_id) = mute_role {
+        // Pending mute and entry are picked up by the mute handler when the
+        // role is added, same as the mute command. The age gate DM already
+        // told the user why, so the mute DM is skipped
+        Mute::new(guild_id.0, member.user.id.0, Some(quarantine_duration))
+            .pending(true)
+            .skip_dm(true)
+            .save(&ctx)
+            .await?;
+
+        ModLogEntry::new("mute", true, guild_id.0, &member.user)
+            .reason(&reason)
+            .save(&ctx)
+            .await?;
+
+        let mut member = member.clone();
+        member.add_role(&ctx.http, role_id as u64).await?;
+    } else {
+        let entry = ModLogEntry::new("kick", true, guild_id.0, &member.user)
+            .reason(&reason)
+            .save(&ctx)
+            .await?;
+
+        if let Err(e) = member
+            .kick_with_reason(&ctx, reason.as_deref().unwrap_or_default())
+            .await
+        {
+            entry.delete(&ctx).await?;
+
+            return Err(e.into());
+        }
+
+        // There isn't a kick event, so report it here
+        ModLogReporter::new(guild_id, &member.user, "kick")
+            .execute(&ctx)
+            .await?;
+    }
+
+    Ok(true)
+}
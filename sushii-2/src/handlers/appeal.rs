@@ -0,0 +1,39 @@
+use serenity::{model::prelude::*, prelude::*};
+
+use crate::error::Result;
+use crate::model::moderation::{appeal, staff_member};
+use crate::model::sql::*;
+
+pub async fn reaction_add(ctx: &Context, reaction: &Reaction) {
+    if let Err(e) = _reaction_add(&ctx, &reaction).await {
+        tracing::error!(?reaction, "Failed to handle appeal reaction_add: {}", e);
+    }
+}
+
+async fn _reaction_add(ctx: &Context, reaction: &Reaction) -> Result<()> {
+    let guild_id = match reaction.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    if reaction.user_id == Some(ctx.cache.current_user_id().await) {
+        return Ok(());
+    }
+
+    let accepted = match appeal::reaction_decision(&reaction.emoji) {
+        Some(a) => a,
+        None => return Ok(()),
+    };
+
+    let mod_appeal = match ModAppeal::from_msg_id(&ctx, reaction.message_id.0).await? {
+        Some(a) if a.status == "pending" => a,
+        _ => return Ok(()),
+    };
+
+    let moderator = match staff_member(&ctx, guild_id, &reaction).await? {
+        Some(m) => m.user,
+        None => return Ok(()),
+    };
+
+    appeal::decide(&ctx, mod_appeal, accepted, &moderator, reaction.channel_id).await
+}
@@ -0,0 +1,332 @@
+use chrono::offset::Utc;
+use serenity::{model::prelude::*, prelude::*};
+use std::time::Duration;
+
+use crate::error::{Error as SushiiError, Result};
+use crate::model::moderation::{revert_shared_ban, staff_member};
+use crate::model::sql::*;
+
+/// How long subscribed guilds have to respond to a ban alert
+const ALERT_TIMEOUT: Duration = Duration::from_secs(60 * 60 * 24);
+
+const BAN_EMOJI: &str = "🔨";
+
+async fn guild_name(ctx: &Context, guild_id: GuildId) -> String {
+    guild_id
+        .to_guild_cached(&ctx)
+        .await
+        .map(|g| g.name)
+        .unwrap_or_else(|| format!("Unknown Guild (ID: {})", guild_id.0))
+}
+
+pub async fn guild_ban_addition(
+    ctx: &Context,
+    guild_id: &GuildId,
+    banned_user: &User,
+    entry: &ModLogEntry,
+) {
+    if let Err(e) = _guild_ban_addition(&ctx, &guild_id, &banned_user, &entry).await {
+        tracing::error!("Failed to handle ban_list guild_ban_addition: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _guild_ban_addition(
+    ctx: &Context,
+    guild_id: &GuildId,
+    banned_user: &User,
+    entry: &ModLogEntry,
+) -> Result<()> {
+    // Bans from another guild's ban list are not shared again, this prevents
+    // guilds subscribed to each other from banning back and forth
+    if SharedBan::from_id(&ctx, *guild_id, banned_user.id)
+        .await?
+        .is_some()
+    {
+        return Ok(());
+    }
+
+    let subs = BanListSubscription::from_source_guild_id(&ctx, *guild_id).await?;
+
+    if subs.is_empty() {
+        return Ok(());
+    }
+
+    let source_name = guild_name(&ctx, *guild_id).await;
+
+    for sub in subs {
+        let res = if sub.auto_ban {
+            ban_subscriber(&ctx, &sub, banned_user, entry, &source_name, None).await
+        } else {
+            send_alert(&ctx, sub.clone(), banned_user, entry, &source_name).await
+        };
+
+        if let Err(e) = res {
+            tracing::warn!(?sub, "Failed to share ban: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn guild_ban_removal(ctx: &Context, guild_id: &GuildId, unbanned_user: &User) {
+    if let Err(e) = _guild_ban_removal(&ctx, &guild_id, &unbanned_user).await {
+        tracing::error!("Failed to handle ban_list guild_ban_removal: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _guild_ban_removal(ctx: &Context, guild_id: &GuildId, unbanned_user: &User) -> Result<()> {
+    // Ban was lifted in this guild, so no longer needs to be tracked
+    if let Some(shared_ban) = SharedBan::from_id(&ctx, *guild_id, unbanned_user.id).await? {
+        shared_ban.delete(&ctx).await?;
+    }
+
+    // Revert bans that were automatically added from this guild
+    for shared_ban in SharedBan::from_source_user(&ctx, *guild_id, unbanned_user.id).await? {
+        let target_guild_id = GuildId(shared_ban.guild_id as u64);
+
+        let sub = BanListSubscription::from_id(&ctx, target_guild_id, *guild_id).await?;
+
+        if !sub.map_or(false, |s| s.auto_ban) {
+            continue;
+        }
+
+        if let Err(e) = revert_shared_ban(&ctx, &shared_ban, unbanned_user).await {
+            tracing::warn!(?shared_ban, "Failed to revert shared ban: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Bans a user in a subscribed guild
+async fn ban_subscriber(
+    ctx: &Context,
+    sub: &BanListSubscription,
+    user: &User,
+    source_entry: &ModLogEntry,
+    source_name: &str,
+    executor: Option<&User>,
+) -> Result<()> {
+    let guild_id = GuildId(sub.guild_id as u64);
+
+    let reason = format!(
+        "Shared ban from {} (case #{}): {}",
+        source_name,
+        source_entry.case_id,
+        source_entry
+            .reason
+            .clone()
+            .unwrap_or_else(|| "No reason given".into())
+    );
+
+    // Save before banning so the resulting ban event knows it is shared
+    let shared_ban = SharedBan::new(
+        guild_id,
+        user.id,
+        GuildId(source_entry.guild_id as u64),
+        source_entry.case_id,
+    )
+    .save(&ctx)
+    .await?;
+
+    let mut entry = ModLogEntry::new("ban", true, guild_id.0, user).reason(&Some(reason.clone()));
+
+    if let Some(executor) = executor {
+        entry = entry.executor_id(executor.id.0);
+    }
+
+    let entry = entry.save(&ctx).await?;
+
+    // Discord audit log reasons are max 512 characters
+    let audit_reason: String = reason.chars().take(512).collect();
+
+    if let Err(e) = guild_id
+        .ban_with_reason(&ctx.http, user, 0u8, audit_reason)
+        .await
+    {
+        entry.delete(&ctx).await?;
+        shared_ban.delete(&ctx).await?;
+
+        let _ = ChannelId(sub.channel_id as u64)
+            .say(
+                &ctx.http,
+                format!(
+                    "Error: Failed to ban {} from {}'s ban list: {}",
+                    user.tag(),
+                    source_name,
+                    e
+                ),
+            )
+            .await;
+
+        return Err(e.into());
+    }
+
+    shared_ban.case_id(entry.case_id).save(&ctx).await?;
+
+    Ok(())
+}
+
+/// Sends a ban alert to a subscribed guild, staff reactions are handled in
+/// reaction_add
+async fn send_alert(
+    ctx: &Context,
+    sub: BanListSubscription,
+    user: &User,
+    source_entry: &ModLogEntry,
+    source_name: &str,
+) -> Result<()> {
+    let guild_id = GuildId(sub.guild_id as u64);
+    let channel_id = ChannelId(sub.channel_id as u64);
+
+    let msg = channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.author(|a| {
+                    a.icon_url(user.face());
+                    a.name(format!("{} (ID: {})", user.tag(), user.id.0));
+
+                    a
+                });
+                e.title(format!("User banned in {}", source_name));
+                e.field(
+                    format!("Case #{}", source_entry.case_id),
+                    source_entry
+                        .reason
+                        .clone()
+                        .unwrap_or_else(|| "No reason given".into()),
+                    false,
+                );
+                e.description(format!("React with {} to ban them here too.", BAN_EMOJI));
+                e.timestamp(
+                    source_entry
+                        .action_time
+                        .format("%Y-%m-%dT%H:%M:%S")
+                        .to_string(),
+                );
+                e.color(0xe67e22);
+
+                e
+            })
+        })
+        .await?;
+
+    BanListAlert::new(
+        msg.id,
+        guild_id,
+        channel_id,
+        user.id,
+        GuildId(source_entry.guild_id as u64),
+        source_entry.case_id,
+    )
+    .save(&ctx)
+    .await?;
+
+    // Add reaction after saving so a failed save doesn't leave a message that
+    // looks like it can be answered
+    msg.react(&ctx, ReactionType::Unicode(BAN_EMOJI.into()))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn reaction_add(ctx: &Context, reaction: &Reaction) {
+    if let Err(e) = _reaction_add(&ctx, &reaction).await {
+        tracing::error!(?reaction, "Failed to handle ban_list reaction_add: {}", e);
+    }
+}
+
+async fn _reaction_add(ctx: &Context, reaction: &Reaction) -> Result<()> {
+    let guild_id = match reaction.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    if reaction.user_id == Some(ctx.cache.current_user_id().await)
+        || reaction.emoji != ReactionType::Unicode(BAN_EMOJI.into())
+    {
+        return Ok(());
+    }
+
+    let alert = match BanListAlert::from_msg_id(&ctx, reaction.message_id).await? {
+        Some(a) => a,
+        None => return Ok(()),
+    };
+
+    // Not deleted yet by the expiry task
+    let timeout = chrono::Duration::from_std(ALERT_TIMEOUT).unwrap();
+    if Utc::now().naive_utc() - alert.created_at > timeout {
+        return Ok(());
+    }
+
+    let moderator = match staff_member(&ctx, guild_id, &reaction).await? {
+        Some(m) => m.user,
+        None => return Ok(()),
+    };
+
+    // Claimed before banning so that multiple reactions can't ban twice
+    if !alert.delete(&ctx).await? {
+        return Ok(());
+    }
+
+    let source_guild_id = GuildId(alert.source_guild_id as u64);
+    let user = UserId(alert.user_id as u64).to_user(ctx).await?;
+    let source_name = guild_name(&ctx, source_guild_id).await;
+
+    let sub = BanListSubscription::from_id(&ctx, guild_id, source_guild_id).await?;
+    let source_entry =
+        ModLogEntry::from_case_id(&ctx, source_guild_id.0, alert.source_case_id as u64).await?;
+
+    let res = match (sub, source_entry) {
+        (Some(sub), Some(source_entry)) => {
+            ban_subscriber(
+                &ctx,
+                &sub,
+                &user,
+                &source_entry,
+                &source_name,
+                Some(&moderator),
+            )
+            .await
+        }
+        (None, _) => Err(SushiiError::Sushii(
+            "No longer subscribed to this ban list".into(),
+        )),
+        (_, None) => Err(SushiiError::Sushii("Source case not found".into())),
+    };
+
+    let description = match res {
+        Ok(()) => format!("Banned by {}", moderator.mention()),
+        Err(e) => format!("Failed to ban: {}", e),
+    };
+
+    reaction
+        .channel_id
+        .edit_message(&ctx, reaction.message_id, |m| {
+            m.embed(|e| {
+                e.author(|a| {
+                    a.icon_url(user.face());
+                    a.name(format!("{} (ID: {})", user.tag(), user.id.0));
+
+                    a
+                });
+                e.title(format!("User banned in {}", source_name));
+                e.description(description);
+                e.color(0xe74c3c);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Deletes ban alerts that staff didn't respond to in time
+pub async fn expire_alerts(ctx: &Context) -> Result<()> {
+    let timeout = chrono::Duration::from_std(ALERT_TIMEOUT).unwrap();
+
+    BanListAlert::delete_expired(&ctx, timeout).await
+}
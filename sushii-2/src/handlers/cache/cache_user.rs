@@ -15,7 +15,9 @@ async fn _message(ctx: &Context, msg: &Message) -> Result<()> {
         return Ok(());
     }
 
-    CachedUser::update(ctx, &msg.author).await?;
+    if let Some(old_user) = CachedUser::update(ctx, &msg.author).await? {
+        crate::handlers::member_log::user_update(ctx, &old_user, &msg.author).await;
+    }
 
     Ok(())
 }
@@ -0,0 +1,57 @@
+use serenity::{model::prelude::*, prelude::*};
+
+use crate::error::Result;
+use crate::model::sql::*;
+use crate::utils::user::{dehoisted_name, DEFAULT_DEHOIST_FALLBACK};
+
+pub async fn guild_member_addition(ctx: &Context, guild_id: &GuildId, member: &Member) {
+    if let Err(e) = _dehoist_member(&ctx, &guild_id, &member).await {
+        tracing::error!("Failed to handle dehoist guild_member_addition: {}", e);
+    }
+}
+
+pub async fn guild_member_update(ctx: &Context, new_member: &Member) {
+    if let Err(e) = _dehoist_member(&ctx, &new_member.guild_id, &new_member).await {
+        tracing::error!("Failed to handle dehoist guild_member_update: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _dehoist_member(ctx: &Context, guild_id: &GuildId, member: &Member) -> Result<()> {
+    if member.user.bot {
+        return Ok(());
+    }
+
+    let guild_conf = match GuildConfig::from_id(&ctx, &guild_id).await? {
+        Some(c) => c,
+        None => {
+            tracing::error!(
+                ?guild_id,
+                ?member,
+                "No guild config found while handling dehoist"
+            );
+            return Ok(());
+        }
+    };
+
+    if !guild_conf.dehoist_enabled {
+        return Ok(());
+    }
+
+    let fallback = guild_conf
+        .dehoist_fallback
+        .as_deref()
+        .unwrap_or(DEFAULT_DEHOIST_FALLBACK);
+
+    let new_name = match dehoisted_name(&member, fallback) {
+        Some(n) => n,
+        None => return Ok(()),
+    };
+
+    // Missing permissions or higher roles shouldn't be logged as errors
+    if let Err(e) = member.edit(&ctx.http, |m| m.nickname(&new_name)).await {
+        tracing::warn!(?guild_id, ?member.user.id, "Failed to dehoist member: {}", e);
+    }
+
+    Ok(())
+}
@@ -1,9 +1,11 @@
 use chrono::{Duration, Utc};
+use serenity::builder::CreateEmbed;
 use serenity::{model::prelude::*, prelude::*};
 use std::fmt::Write;
 
 use crate::error::Result;
 use crate::model::sql::*;
+use crate::utils::guild::{handle_log_error, LogChannel};
 
 pub async fn guild_member_addition(ctx: &Context, guild_id: &GuildId, member: &Member) {
     if let Err(e) = _guild_member_addition(&ctx, &guild_id, &member).await {
@@ -76,22 +78,7 @@ async fn _guild_member_addition(ctx: &Context, guild_id: &GuildId, member: &Memb
         })
         .await;
 
-    if let Err(SerenityError::Http(e)) = res {
-        // Box cant be matched
-        if let HttpError::UnsuccessfulRequest(e) = *e {
-            // Unknown channel -- deleted channel so just unset
-            if e.error.code == 10003 {
-                guild_conf.log_member = None;
-                guild_conf.save(ctx).await?;
-            }
-
-            // Missing access -- no perms so might as well just disable
-            if e.error.code == 50001 {
-                guild_conf.log_member_enabled = false;
-                guild_conf.save(ctx).await?;
-            }
-        }
-    }
+    handle_log_error(ctx, &mut guild_conf, LogChannel::Member, &res).await?;
 
     Ok(())
 }
@@ -172,20 +159,151 @@ async fn _guild_member_removal(
         })
         .await;
 
-    if let Err(SerenityError::Http(e)) = res {
-        // Box cant be matched
-        if let HttpError::UnsuccessfulRequest(e) = *e {
-            // Unknown channel -- deleted channel so just unset
-            if e.error.code == 10003 {
-                guild_conf.log_member = None;
-                guild_conf.save(ctx).await?;
-            }
-
-            // Missing access -- no perms so might as well just disable
-            if e.error.code == 50001 {
-                guild_conf.log_member_enabled = false;
-                guild_conf.save(ctx).await?;
-            }
+    handle_log_error(ctx, &mut guild_conf, LogChannel::Member, &res).await?;
+
+    Ok(())
+}
+
+/// Sends an embed with the user as the author to the member log if it's enabled
+async fn send_member_log<F>(ctx: &Context, guild_id: GuildId, user: &User, f: F) -> Result<()>
+where
+    F: FnOnce(&mut CreateEmbed) -> &mut CreateEmbed,
+{
+    let mut guild_conf = match GuildConfig::from_id(&ctx, &guild_id).await? {
+        Some(c) => c,
+        None => {
+            tracing::error!(?guild_id, "No guild config found while handling member log");
+            return Ok(());
+        }
+    };
+
+    if !guild_conf.log_member_enabled {
+        return Ok(());
+    }
+
+    let member_log_channel = match guild_conf.log_member {
+        Some(id) => ChannelId(id as u64),
+        None => return Ok(()),
+    };
+
+    let res = member_log_channel
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                e.author(|a| {
+                    a.icon_url(user.face());
+                    a.name(format!("{} (ID: {})", user.tag(), user.id));
+
+                    a
+                });
+
+                f(e);
+                e.timestamp(Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string());
+
+                e
+            })
+        })
+        .await;
+
+    handle_log_error(ctx, &mut guild_conf, LogChannel::Member, &res).await?;
+
+    Ok(())
+}
+
+pub async fn guild_member_update(ctx: &Context, old_member: &Option<Member>, new_member: &Member) {
+    if let Err(e) = _guild_member_update(ctx, old_member, new_member).await {
+        tracing::error!("Failed to handle member log guild_member_update: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _guild_member_update(
+    ctx: &Context,
+    old_member: &Option<Member>,
+    new_member: &Member,
+) -> Result<()> {
+    // Old member is only available if it was cached
+    let old_member = match old_member {
+        Some(m) => m,
+        None => return Ok(()),
+    };
+
+    if old_member.nick == new_member.nick || new_member.user.bot {
+        return Ok(());
+    }
+
+    NameHistory::save_nickname(
+        &ctx,
+        new_member.guild_id,
+        new_member.user.id,
+        &new_member.display_name(),
+    )
+    .await?;
+
+    let fmt_nick = |nick: &Option<String>| nick.clone().unwrap_or_else(|| "None".into());
+
+    send_member_log(&ctx, new_member.guild_id, &new_member.user, |e| {
+        e.title("Nickname Changed");
+        e.field("Before", fmt_nick(&old_member.nick), false);
+        e.field("After", fmt_nick(&new_member.nick), false);
+        e.color(0xe67e22)
+    })
+    .await
+}
+
+/// Logs username and avatar changes found when updating the cached user
+pub async fn user_update(ctx: &Context, old_user: &CachedUser, new_user: &User) {
+    if let Err(e) = _user_update(ctx, old_user, new_user).await {
+        tracing::error!("Failed to handle member log user_update: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _user_update(ctx: &Context, old_user: &CachedUser, new_user: &User) -> Result<()> {
+    let old_tag = format!("{}#{:04}", old_user.name, old_user.discriminator);
+    let username_changed = old_tag != new_user.tag();
+    let avatar_changed = old_user.avatar_url != new_user.face();
+
+    if username_changed {
+        NameHistory::save_username(&ctx, new_user).await?;
+    }
+
+    let embed = |e: &mut CreateEmbed| {
+        if username_changed {
+            e.field("Username Before", &old_tag, false);
+            e.field("Username After", new_user.tag(), false);
+        }
+
+        if avatar_changed {
+            e.field(
+                "Avatar Changed",
+                format!(
+                    "[Before]({}) → [After]({})",
+                    old_user.avatar_url,
+                    new_user.face()
+                ),
+                false,
+            );
+            e.thumbnail(new_user.face());
+        }
+
+        e.title("User Updated");
+        e.color(0xe67e22);
+    };
+
+    // Changes are only found when the user sends a message, which could be in
+    // DMs or any guild, so it's logged in every guild they are a member of
+    for guild_id in ctx.cache.guilds().await {
+        if ctx.cache.member(guild_id, new_user.id).await.is_none() {
+            continue;
+        }
+
+        if let Err(e) = send_member_log(&ctx, guild_id, new_user, |e| {
+            embed(e);
+            e
+        })
+        .await
+        {
+            tracing::warn!(?guild_id, "Failed to log user update: {}", e);
         }
     }
 
@@ -1,7 +1,12 @@
 use crate::tasks;
 use serenity::{async_trait, model::prelude::*, prelude::*};
+use std::collections::HashMap;
 
+mod age_gate;
+mod appeal;
+pub mod ban_list;
 mod cache;
+mod dehoist;
 mod join_msg;
 mod member_log;
 mod mention;
@@ -10,7 +15,10 @@ mod msg_log;
 mod notification;
 mod raw_event_handler;
 mod roles;
-mod user_levels;
+mod server_log;
+pub mod user_levels;
+pub mod voice_log;
+pub mod voice_xp;
 
 pub use raw_event_handler::RawHandler;
 
@@ -52,6 +60,17 @@ impl EventHandler for Handler {
         msg_log::message_delete(&ctx, channel_id, msg_id, guild_id).await;
     }
 
+    async fn message_delete_bulk(
+        &self,
+        ctx: Context,
+        channel_id: ChannelId,
+        multiple_deleted_messages_ids: Vec<MessageId>,
+        guild_id: Option<GuildId>,
+    ) {
+        msg_log::message_delete_bulk(&ctx, channel_id, &multiple_deleted_messages_ids, guild_id)
+            .await;
+    }
+
     async fn message_update(
         &self,
         ctx: Context,
@@ -62,16 +81,39 @@ impl EventHandler for Handler {
         msg_log::message_update(&ctx, &old_msg, &new_msg, &event).await;
     }
 
+    async fn reaction_add(&self, ctx: Context, add_reaction: Reaction) {
+        tokio::join!(
+            roles::reaction_add(&ctx, &add_reaction),
+            appeal::reaction_add(&ctx, &add_reaction),
+            ban_list::reaction_add(&ctx, &add_reaction),
+        );
+    }
+
+    async fn reaction_remove(&self, ctx: Context, removed_reaction: Reaction) {
+        roles::reaction_remove(&ctx, &removed_reaction).await;
+    }
+
     async fn guild_ban_addition(&self, ctx: Context, guild_id: GuildId, banned_user: User) {
-        mod_log::ban::guild_ban_addition(&ctx, &guild_id, &banned_user).await;
+        // Ban list sharing needs the finished mod log entry for the reason
+        if let Some(entry) = mod_log::ban::guild_ban_addition(&ctx, &guild_id, &banned_user).await {
+            ban_list::guild_ban_addition(&ctx, &guild_id, &banned_user, &entry).await;
+        }
     }
 
     async fn guild_ban_removal(&self, ctx: Context, guild_id: GuildId, unbanned_user: User) {
-        mod_log::ban::guild_ban_removal(&ctx, &guild_id, &unbanned_user).await;
+        tokio::join!(
+            mod_log::ban::guild_ban_removal(&ctx, &guild_id, &unbanned_user),
+            ban_list::guild_ban_removal(&ctx, &guild_id, &unbanned_user),
+        );
     }
 
     async fn guild_create(&self, ctx: Context, guild: Guild, is_new: bool) {
-        cache::cache_guild::guild_create(&ctx, &guild, is_new).await;
+        tokio::join!(
+            cache::cache_guild::guild_create(&ctx, &guild, is_new),
+            server_log::guild_create(&ctx, &guild),
+            voice_log::guild_create(&ctx, &guild),
+            voice_xp::guild_create(&ctx, &guild),
+        );
     }
 
     async fn guild_update(
@@ -80,7 +122,74 @@ impl EventHandler for Handler {
         old_guild_if_avail: Option<Guild>,
         partial_guild: PartialGuild,
     ) {
-        cache::cache_guild::guild_update(&ctx, &old_guild_if_avail, &partial_guild).await;
+        tokio::join!(
+            cache::cache_guild::guild_update(&ctx, &old_guild_if_avail, &partial_guild),
+            server_log::guild_update(&ctx, &old_guild_if_avail, &partial_guild),
+        );
+    }
+
+    async fn guild_emojis_update(
+        &self,
+        ctx: Context,
+        guild_id: GuildId,
+        current_state: HashMap<EmojiId, Emoji>,
+    ) {
+        server_log::guild_emojis_update(&ctx, &guild_id, &current_state).await;
+    }
+
+    async fn guild_role_create(&self, ctx: Context, guild_id: GuildId, new: Role) {
+        server_log::guild_role_create(&ctx, &guild_id, &new).await;
+    }
+
+    async fn guild_role_update(
+        &self,
+        ctx: Context,
+        guild_id: GuildId,
+        old_data_if_available: Option<Role>,
+        new: Role,
+    ) {
+        server_log::guild_role_update(&ctx, &guild_id, &old_data_if_available, &new).await;
+    }
+
+    async fn guild_role_delete(
+        &self,
+        ctx: Context,
+        guild_id: GuildId,
+        removed_role_id: RoleId,
+        removed_role_data_if_available: Option<Role>,
+    ) {
+        server_log::guild_role_delete(
+            &ctx,
+            &guild_id,
+            &removed_role_id,
+            &removed_role_data_if_available,
+        )
+        .await;
+    }
+
+    async fn voice_state_update(
+        &self,
+        ctx: Context,
+        guild_id: Option<GuildId>,
+        old: Option<VoiceState>,
+        new: VoiceState,
+    ) {
+        tokio::join!(
+            voice_log::voice_state_update(&ctx, &guild_id, &old, &new),
+            voice_xp::voice_state_update(&ctx, &guild_id, &new),
+        );
+    }
+
+    async fn channel_create(&self, ctx: Context, channel: &GuildChannel) {
+        server_log::channel_create(&ctx, channel).await;
+    }
+
+    async fn channel_update(&self, ctx: Context, old: Option<Channel>, new: Channel) {
+        server_log::channel_update(&ctx, &old, &new).await;
+    }
+
+    async fn channel_delete(&self, ctx: Context, channel: &GuildChannel) {
+        server_log::channel_delete(&ctx, channel).await;
     }
 
     async fn guild_member_update(
@@ -89,16 +198,28 @@ impl EventHandler for Handler {
         old_member: Option<Member>,
         new_member: Member,
     ) {
-        mod_log::mute::guild_member_update(&ctx, &old_member, &new_member).await;
+        tokio::join!(
+            mod_log::mute::guild_member_update(&ctx, &old_member, &new_member),
+            dehoist::guild_member_update(&ctx, &new_member),
+            member_log::guild_member_update(&ctx, &old_member, &new_member),
+        );
     }
 
     async fn guild_member_addition(&self, ctx: Context, guild_id: GuildId, mut member: Member) {
         // TODO: Run these concurrently instead of one by one
         mod_log::mute::guild_member_addition(&ctx, &guild_id, &mut member).await;
 
+        // Members that are kicked or quarantined shouldn't get a join message
+        if age_gate::guild_member_addition(&ctx, &guild_id, &member).await {
+            member_log::guild_member_addition(&ctx, &guild_id, &member).await;
+
+            return;
+        }
+
         tokio::join!(
             join_msg::guild_member_addition(&ctx, &guild_id, &member),
             member_log::guild_member_addition(&ctx, &guild_id, &member),
+            dehoist::guild_member_addition(&ctx, &guild_id, &member),
         );
     }
 
@@ -1,20 +1,32 @@
 use serenity::{model::prelude::*, prelude::*};
 
 use crate::model::moderation::ModLogReporter;
-use crate::model::sql::delete_mute;
+use crate::model::sql::{delete_mute, ModLogEntry};
 
-pub async fn guild_ban_addition(ctx: &Context, guild_id: &GuildId, banned_user: &User) {
-    if let Err(e) = ModLogReporter::new(guild_id, banned_user, "ban")
+/// Returns the mod log entry of the ban if it was successfully saved
+pub async fn guild_ban_addition(
+    ctx: &Context,
+    guild_id: &GuildId,
+    banned_user: &User,
+) -> Option<ModLogEntry> {
+    let entry = match ModLogReporter::new(guild_id, banned_user, "ban")
         .execute(&ctx)
         .await
     {
-        tracing::error!("Failed to handle guild_ban_addition: {}", e);
-    }
+        Ok(entry) => Some(entry),
+        Err(e) => {
+            tracing::error!("Failed to handle guild_ban_addition: {}", e);
+
+            None
+        }
+    };
 
     // Delete any mute entries if any
     if let Err(e) = delete_mute(&ctx, guild_id.0, banned_user.id.0).await {
         tracing::error!("Failed to delete mute: {}", e);
     }
+
+    entry
 }
 
 pub async fn guild_ban_removal(ctx: &Context, guild_id: &GuildId, unbanned_user: &User) {
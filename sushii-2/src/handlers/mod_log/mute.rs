@@ -4,7 +4,7 @@ use std::collections::HashSet;
 use std::fmt::Write;
 
 use crate::error::Result;
-use crate::model::moderation::ModLogReporter;
+use crate::model::moderation::{appeal, ModLogReporter};
 use crate::model::sql::*;
 
 pub async fn guild_member_addition(ctx: &Context, guild_id: &GuildId, mut member: &mut Member) {
@@ -158,8 +158,8 @@ async fn _guild_member_update(
         None
     };
 
-    // If dm isn't enabled skip the rest
-    if !guild_conf.mute_dm_enabled {
+    // If dm isn't enabled or the user was already notified skip the rest
+    if !guild_conf.mute_dm_enabled || mute_entry.as_ref().map_or(false, |m| m.skip_dm) {
         return Ok(());
     }
 
@@ -185,7 +185,15 @@ async fn _guild_member_update(
     }
 
     if let Some(dur_str) = mute_entry.and_then(|m| m.get_human_duration()) {
-        write!(s, "Duration: {}", dur_str)?;
+        writeln!(s, "Duration: {}", dur_str)?;
+    }
+
+    if action == "mute" {
+        if let Some(instructions) =
+            appeal::appeal_instructions(ctx, &guild_conf, entry.case_id as u64).await
+        {
+            write!(s, "\n{}", instructions)?;
+        }
     }
 
     // Ignore if dm fails, could be disabled
@@ -1,11 +1,53 @@
 use chrono::Utc;
-use serenity::http::error::Error as HttpError;
-use serenity::Error as SerenityError;
+use serenity::http::AttachmentType;
 use serenity::{model::prelude::*, prelude::*};
+use std::borrow::Cow;
 use std::fmt::Write;
 
 use crate::error::Result;
+use crate::keys::ReqwestContainer;
 use crate::model::sql::*;
+use crate::utils::guild::{handle_log_error, LogChannel};
+use crate::utils::text::{split_chunks, word_diff};
+
+/// Max total size of deleted attachments to re-upload, Discord's upload limit
+/// for guilds without boosts
+const MAX_ARCHIVE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Max embed fields of 1024 characters for edit diffs, the embed total is
+/// limited to 6000 characters
+const MAX_DIFF_FIELDS: usize = 4;
+
+/// If messages in a channel or from members with any of the given roles should
+/// not be logged
+async fn is_ignored(
+    ctx: &Context,
+    guild_conf: &GuildConfig,
+    channel_id: ChannelId,
+    roles: &[RoleId],
+) -> bool {
+    let category_id = ctx
+        .cache
+        .guild_channel(channel_id)
+        .await
+        .and_then(|c| c.category_id);
+
+    guild_conf.msg_log_ignored(channel_id, category_id, roles)
+}
+
+/// If the message log is enabled and has a channel set
+fn is_msg_log_enabled(guild_conf: &GuildConfig) -> bool {
+    guild_conf.log_msg_enabled && guild_conf.log_msg.is_some()
+}
+
+/// Roles of the author when the message was sent
+fn saved_msg_roles(saved_msg: &SavedMessage) -> &[RoleId] {
+    saved_msg
+        .msg
+        .member
+        .as_ref()
+        .map_or(&[][..], |m| &m.roles[..])
+}
 
 pub async fn message(ctx: &Context, msg: &Message) {
     if let Err(e) = _message(ctx, msg).await {
@@ -28,16 +70,15 @@ async fn _message(ctx: &Context, msg: &Message) -> Result<()> {
         None => return Ok(()),
     };
 
-    // Don't log messages if message log isn't enabled or channel isn't set
-    if !guild_conf.log_msg_enabled || guild_conf.log_msg.is_none() {
+    // Messages are only saved for the message log and snipe
+    if !is_msg_log_enabled(&guild_conf) && !guild_conf.snipe_enabled {
         return Ok(());
     }
 
-    // Ignore role channel
-    if let Some(channel) = guild_conf.role_channel {
-        if msg.channel_id.0 == channel as u64 {
-            return Ok(());
-        }
+    let roles = msg.member.as_ref().map_or(&[][..], |m| &m.roles[..]);
+
+    if is_ignored(ctx, &guild_conf, msg.channel_id, roles).await {
+        return Ok(());
     }
 
     let saved_msg = match SavedMessage::from_msg(msg) {
@@ -45,15 +86,64 @@ async fn _message(ctx: &Context, msg: &Message) -> Result<()> {
         None => return Ok(()),
     };
 
-    // Save message to db
+    // Save message to db, old messages are pruned in tasks::msg_log
     saved_msg.save(ctx).await?;
 
-    // Delete old messages past max # (100) to save per channel
-    SavedMessage::prune_old(ctx, msg.channel_id).await?;
-
     Ok(())
 }
 
+/// Downloads deleted image attachments so they can be re-uploaded before the
+/// proxy url stops working. Attachments that fail to download are skipped
+async fn archive_attachments(
+    ctx: &Context,
+    attachments: &[Attachment],
+) -> Vec<AttachmentType<'static>> {
+    let reqwest_client = ctx
+        .data
+        .read()
+        .await
+        .get::<ReqwestContainer>()
+        .cloned()
+        .unwrap();
+
+    let mut files = Vec::new();
+    let mut total_size = 0;
+
+    // Only images have dimensions
+    for attachment in attachments.iter().filter(|a| a.width.is_some()) {
+        if total_size + attachment.size > MAX_ARCHIVE_SIZE {
+            continue;
+        }
+
+        let res = reqwest_client
+            .get(&attachment.proxy_url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+
+        let bytes = match res {
+            Ok(r) => r.bytes().await,
+            Err(e) => Err(e),
+        };
+
+        match bytes {
+            Ok(bytes) => {
+                total_size += attachment.size;
+
+                files.push(AttachmentType::Bytes {
+                    data: Cow::from(bytes.to_vec()),
+                    filename: attachment.filename.clone(),
+                });
+            }
+            Err(e) => {
+                tracing::warn!(?attachment, "Failed to archive deleted attachment: {}", e);
+            }
+        }
+    }
+
+    files
+}
+
 pub async fn message_delete(
     ctx: &Context,
     channel_id: ChannelId,
@@ -82,28 +172,29 @@ async fn _message_delete(
         None => return Ok(()),
     };
 
-    // Don't log messages if message log isn't enabled or channel isn't set
-    if !guild_conf.log_msg_enabled {
+    if !is_msg_log_enabled(&guild_conf) && !guild_conf.snipe_enabled {
         return Ok(());
     }
 
-    let log_msg_channel = match guild_conf.log_msg {
-        Some(c) => c,
-        None => return Ok(()),
+    let saved_msg = match SavedMessage::from_id(ctx, msg_id).await? {
+        Some(msg) => msg,
+        None => return Ok(()), // Not found
     };
 
-    // Ignore role channel
-    if let Some(channel) = guild_conf.role_channel {
-        if channel_id.0 == channel as u64 {
-            return Ok(());
-        }
+    if guild_conf.snipe_enabled {
+        SavedMessage::mark_deleted(ctx, &[msg_id]).await?;
     }
 
-    let saved_msg = match SavedMessage::from_id(ctx, msg_id).await? {
-        Some(msg) => msg,
-        None => return Ok(()), // Not found
+    // Don't log messages if message log isn't enabled or channel isn't set
+    let log_msg_channel = match guild_conf.log_msg {
+        Some(c) if guild_conf.log_msg_enabled => c,
+        _ => return Ok(()),
     };
 
+    if is_ignored(ctx, &guild_conf, channel_id, saved_msg_roles(&saved_msg)).await {
+        return Ok(());
+    }
+
     let mut attachments_s = String::new();
 
     for (i, attachment_url) in saved_msg
@@ -127,10 +218,18 @@ async fn _message_delete(
         saved_msg.content, attachments_s,
     );
 
+    let files = archive_attachments(ctx, &saved_msg.msg.attachments).await;
+
+    // Show the first image in the embed, the rest are attached below it
+    let embed_image = files.first().and_then(|f| match f {
+        AttachmentType::Bytes { filename, .. } => Some(format!("attachment://{}", filename)),
+        _ => None,
+    });
+
     let now = Utc::now().naive_utc();
 
     let res = ChannelId(log_msg_channel as u64)
-        .send_message(ctx, |m| {
+        .send_files(ctx, files, |m| {
             m.embed(|e| {
                 e.description(format!(
                     "<@{}> in {}",
@@ -139,6 +238,10 @@ async fn _message_delete(
                 ));
                 e.field("Message Deleted", s, false);
 
+                if let Some(url) = embed_image {
+                    e.image(url);
+                }
+
                 e.footer(|f| {
                     f.text("Deleted at");
 
@@ -155,25 +258,142 @@ async fn _message_delete(
         })
         .await;
 
-    if let Err(SerenityError::Http(e)) = res {
-        // Box cant be matched
-        if let HttpError::UnsuccessfulRequest(e) = *e {
-            tracing::warn!(?e, "HttpError::UnsuccessfulRequest");
+    handle_log_error(ctx, &mut guild_conf, LogChannel::Msg, &res).await?;
 
-            // Unknown channel -- deleted channel so just unset
-            if e.error.code == 10003 {
-                guild_conf.log_msg = None;
-                guild_conf.save(ctx).await?;
-            }
+    Ok(())
+}
 
-            // Missing access -- no perms so might as well just disable
-            if e.error.code == 50001 {
-                guild_conf.log_msg_enabled = false;
-                guild_conf.save(ctx).await?;
-            }
+pub async fn message_delete_bulk(
+    ctx: &Context,
+    channel_id: ChannelId,
+    msg_ids: &[MessageId],
+    guild_id: Option<GuildId>,
+) {
+    if let Err(e) = _message_delete_bulk(ctx, channel_id, msg_ids, guild_id).await {
+        tracing::error!("Failed to run message_delete_bulk handler: {}", e);
+    }
+}
+
+/// Creates a plain text transcript of deleted messages
+fn bulk_delete_transcript(
+    channel_id: ChannelId,
+    saved_msgs: &[SavedMessage],
+    total_deleted: usize,
+) -> String {
+    let mut s = String::new();
+
+    let _ = writeln!(
+        s,
+        "{} messages deleted in channel {}, {} recovered",
+        total_deleted,
+        channel_id.0,
+        saved_msgs.len()
+    );
+
+    for saved_msg in saved_msgs {
+        let _ = writeln!(
+            s,
+            "\n[{}] {} ({}):",
+            saved_msg.created.format("%Y-%m-%d %H:%M:%S UTC"),
+            saved_msg.msg.author.tag(),
+            saved_msg.author_id as u64,
+        );
+
+        if !saved_msg.content.is_empty() {
+            let _ = writeln!(s, "{}", saved_msg.content);
         }
+
+        for attachment in &saved_msg.msg.attachments {
+            let _ = writeln!(s, "Attachment: {}", attachment.proxy_url);
+        }
+    }
+
+    s
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _message_delete_bulk(
+    ctx: &Context,
+    channel_id: ChannelId,
+    msg_ids: &[MessageId],
+    guild_id: Option<GuildId>,
+) -> Result<()> {
+    let guild_id = match guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let mut guild_conf = match GuildConfig::from_id(ctx, &guild_id).await? {
+        Some(conf) => conf,
+        None => return Ok(()),
+    };
+
+    if guild_conf.snipe_enabled {
+        SavedMessage::mark_deleted(ctx, msg_ids).await?;
+    }
+
+    // Don't log messages if message log isn't enabled or channel isn't set
+    if !guild_conf.log_msg_enabled {
+        return Ok(());
     }
 
+    let log_msg_channel = match guild_conf.log_msg {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+
+    if is_ignored(ctx, &guild_conf, channel_id, &[]).await {
+        return Ok(());
+    }
+
+    let mut saved_msgs = SavedMessage::from_ids(ctx, msg_ids).await?;
+
+    // Channel is already checked, so only roles are left
+    saved_msgs.retain(|m| !guild_conf.msg_log_ignored(channel_id, None, saved_msg_roles(m)));
+
+    // Nothing to show if none of them were saved
+    if saved_msgs.is_empty() {
+        return Ok(());
+    }
+
+    let transcript = bulk_delete_transcript(channel_id, &saved_msgs, msg_ids.len());
+
+    let file = AttachmentType::Bytes {
+        data: Cow::from(transcript.into_bytes()),
+        filename: format!("deleted-messages-{}.txt", channel_id.0),
+    };
+
+    let now = Utc::now().naive_utc();
+
+    let res = ChannelId(log_msg_channel as u64)
+        .send_files(ctx, vec![file], |m| {
+            m.embed(|e| {
+                e.description(format!(
+                    "{} messages deleted in {}, {} were recovered in the attached transcript",
+                    msg_ids.len(),
+                    channel_id.mention(),
+                    saved_msgs.len(),
+                ));
+                e.title("Messages Bulk Deleted");
+
+                e.footer(|f| {
+                    f.text("Deleted at");
+
+                    f
+                });
+
+                e.timestamp(now.format("%Y-%m-%dT%H:%M:%S").to_string());
+                e.colour(0xe74c3c);
+
+                e
+            });
+
+            m
+        })
+        .await;
+
+    handle_log_error(ctx, &mut guild_conf, LogChannel::Msg, &res).await?;
+
     Ok(())
 }
 
@@ -211,39 +431,82 @@ async fn _message_update(
             None => return Ok(()),
         };
 
-    // Don't log messages if message log isn't enabled or channel isn't set
-    if !guild_conf.log_msg_enabled {
+    if !is_msg_log_enabled(&guild_conf) && !guild_conf.snipe_enabled {
         return Ok(());
     }
 
+    if guild_conf.snipe_enabled {
+        saved_msg.prev_content = Some(saved_msg.content.clone());
+        saved_msg.edited_at = Some(Utc::now().naive_utc());
+    }
+
+    // Only the snipe needs the edit if the message log is disabled
     let log_msg_channel = match guild_conf.log_msg {
-        Some(c) => c,
-        None => return Ok(()),
-    };
+        Some(c) if guild_conf.log_msg_enabled => c,
+        _ => {
+            saved_msg.content = new_content.clone();
+            saved_msg.save(ctx).await?;
 
-    // Ignore role channel
-    if let Some(channel) = guild_conf.role_channel {
-        if saved_msg.channel_id == channel {
             return Ok(());
         }
+    };
+
+    if is_ignored(
+        ctx,
+        &guild_conf,
+        ChannelId(saved_msg.channel_id as u64),
+        saved_msg_roles(&saved_msg),
+    )
+    .await
+    {
+        return Ok(());
     }
 
-    let s = format!(
-        "**Before:** {}\n\
-        **+After:** {}",
-        saved_msg.content, new_content,
-    );
+    let diff_chunks = split_chunks(&word_diff(&saved_msg.content, new_content), 1024);
+
+    // Attach the full message instead if the diff doesn't fit in a few fields
+    let mut files = Vec::new();
+
+    if diff_chunks.len() > MAX_DIFF_FIELDS {
+        files.push(AttachmentType::Bytes {
+            data: Cow::from(
+                format!(
+                    "Before:\n{}\n\nAfter:\n{}\n",
+                    saved_msg.content, new_content
+                )
+                .into_bytes(),
+            ),
+            filename: format!("edited-message-{}.txt", saved_msg.message_id as u64),
+        });
+    }
 
     let now = Utc::now().naive_utc();
 
     let res = ChannelId(log_msg_channel as u64)
-        .send_message(ctx, |m| {
+        .send_files(ctx, files, |m| {
             m.embed(|e| {
                 e.description(format!(
                     "<@{}> in <#{}>",
                     saved_msg.author_id as u64, saved_msg.channel_id as u64
                 ));
-                e.field("Message Edited", s, false);
+
+                if diff_chunks.len() > MAX_DIFF_FIELDS {
+                    e.field(
+                        "Message Edited",
+                        "Edit is too long to show, the full message before and after is attached",
+                        false,
+                    );
+                } else {
+                    for (i, chunk) in diff_chunks.iter().enumerate() {
+                        let name = if i == 0 {
+                            "Message Edited"
+                        } else {
+                            "Message Edited (continued)"
+                        };
+
+                        e.field(name, chunk, false);
+                    }
+                }
 
                 e.footer(|f| {
                     f.text("Edited at");
@@ -266,24 +529,7 @@ async fn _message_update(
     // This doesn't update the actual serenity Message object in saved_msg.msg
     saved_msg.save(ctx).await?;
 
-    if let Err(SerenityError::Http(e)) = res {
-        // Box cant be matched
-        if let HttpError::UnsuccessfulRequest(e) = *e {
-            tracing::warn!(?e, "HttpError::UnsuccessfulRequest");
-
-            // Unknown channel -- deleted channel so just unset
-            if e.error.code == 10003 {
-                guild_conf.log_msg = None;
-                guild_conf.save(ctx).await?;
-            }
-
-            // Missing access -- no perms so might as well just disable
-            if e.error.code == 50001 {
-                guild_conf.log_msg_enabled = false;
-                guild_conf.save(ctx).await?;
-            }
-        }
-    }
+    handle_log_error(ctx, &mut guild_conf, LogChannel::Msg, &res).await?;
 
     Ok(())
 }
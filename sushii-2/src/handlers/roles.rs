@@ -10,6 +10,7 @@ use tokio::time::sleep;
 
 use crate::error::Result;
 use crate::model::sql::*;
+use sushii_model::model::user::UserLevelProgress;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum RoleActionKind {
@@ -24,6 +25,17 @@ struct RoleAction {
     pub role_name: String,
 }
 
+/// Role or group requirement a member doesn't meet
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum UnmetRequirement<'a> {
+    /// Missing a required role
+    Role(u64),
+    /// Has roles in a mutually exclusive group
+    ExcludedGroup(&'a str),
+    /// Below the minimum level
+    Level(i64),
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 struct CalculatedRoles<'a> {
     pub member_new_all_roles: HashSet<u64>,
@@ -32,6 +44,7 @@ struct CalculatedRoles<'a> {
     pub added_existing_roles: Vec<&'a str>,
     pub removed_missing_roles: Vec<&'a str>,
     pub over_limit_roles: HashMap<&'a str, Vec<&'a str>>,
+    pub unmet_requirements: Vec<(&'a str, UnmetRequirement<'a>)>,
 }
 
 fn pluralize(s: &str, qty: usize) -> String {
@@ -263,12 +276,65 @@ fn dedupe_role_actions(role_actions: &[RoleAction]) -> Vec<&RoleAction> {
     role_actions_deduped
 }
 
+/// Finds the first requirement of a group or role that the member doesn't meet
+fn find_unmet_requirement<'a>(
+    role_config: &'a GuildRoles,
+    group: &GuildGroup,
+    role: &GuildRole,
+    member_all_roles: &HashSet<u64>,
+    member_config_roles: &HashMap<&'a str, HashSet<u64>>,
+    member_level: i64,
+) -> Option<UnmetRequirement<'a>> {
+    for req in &[&group.requirements, &role.requirements] {
+        if let Some(id) = req.required_role {
+            if !member_all_roles.contains(&id) {
+                return Some(UnmetRequirement::Role(id));
+            }
+        }
+
+        if let Some(min_level) = req.min_level {
+            if member_level < min_level {
+                return Some(UnmetRequirement::Level(min_level));
+            }
+        }
+    }
+
+    // Exclusions go both ways, either this group or role excludes another
+    // group or a group or role the member has excludes this one
+    for other_group in &role_config.groups {
+        if other_group.name == group.name {
+            continue;
+        }
+
+        let other_roles = match member_config_roles.get(other_group.name.as_str()) {
+            Some(roles) if !roles.is_empty() => roles,
+            _ => continue,
+        };
+
+        let is_excluded = group.requirements.excludes(&other_group.name)
+            || role.requirements.excludes(&other_group.name)
+            || other_group.requirements.excludes(&group.name)
+            || other_group.roles.iter().any(|r| {
+                r.requirements.excludes(&group.name)
+                    && (other_roles.contains(&r.primary_id)
+                        || r.secondary_id.map_or(false, |id| other_roles.contains(&id)))
+            });
+
+        if is_excluded {
+            return Some(UnmetRequirement::ExcludedGroup(&other_group.name));
+        }
+    }
+
+    None
+}
+
 fn calculate_roles<'a>(
-    role_config: &GuildRoles,
+    role_config: &'a GuildRoles,
     role_actions_deduped: Vec<&'a RoleAction>,
     role_name_map: HashMap<String, (&'a str, &'a GuildRole, &'a str, usize)>,
     mut member_all_roles: HashSet<u64>,
     mut member_config_roles: HashMap<&'a str, HashSet<u64>>,
+    member_level: i64,
 ) -> CalculatedRoles<'a> {
     let mut added_role_names: Vec<&str> = Vec::new();
     let mut removed_role_names: Vec<&str> = Vec::new();
@@ -276,18 +342,33 @@ fn calculate_roles<'a>(
     let mut added_existing_roles: Vec<&str> = Vec::new();
     let mut removed_missing_roles: Vec<&str> = Vec::new();
     let mut over_limit_roles: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut unmet_requirements: Vec<(&str, UnmetRequirement)> = Vec::new();
 
     for action in role_actions_deduped {
         if let Some((orig_role_name, role, group_name, group_index)) =
             role_name_map.get(action.role_name.trim())
         {
+            let conf_group = role_config.groups.get(*group_index).unwrap();
+
+            // Checked before borrowing the member's group roles mutably
+            let unmet_requirement = if action.kind == RoleActionKind::Add {
+                find_unmet_requirement(
+                    role_config,
+                    conf_group,
+                    role,
+                    &member_all_roles,
+                    &member_config_roles,
+                    member_level,
+                )
+            } else {
+                None
+            };
+
             // Member's current roles in this group
             let cur_group_roles = member_config_roles
                 .entry(group_name)
                 .or_insert_with(HashSet::new);
 
-            let conf_group = role_config.groups.get(*group_index).unwrap();
-
             if action.kind == RoleActionKind::Add {
                 // If member already has it
                 if cur_group_roles.contains(&role.primary_id) {
@@ -296,6 +377,12 @@ fn calculate_roles<'a>(
                     continue;
                 }
 
+                if let Some(unmet) = unmet_requirement {
+                    unmet_requirements.push((orig_role_name, unmet));
+
+                    continue;
+                }
+
                 // Check limits if limit is set to greater than 0 (0 is disabled)
                 if conf_group.limit > 0 && cur_group_roles.len() >= conf_group.limit as usize {
                     let entry = over_limit_roles.entry(group_name).or_insert_with(Vec::new);
@@ -355,16 +442,22 @@ fn calculate_roles<'a>(
         added_existing_roles,
         removed_missing_roles,
         over_limit_roles,
+        unmet_requirements,
     }
 }
 
-fn format_response(role_config: &GuildRoles, calc_roles: &CalculatedRoles) -> String {
+fn format_response(
+    role_config: &GuildRoles,
+    calc_roles: &CalculatedRoles,
+    guild_roles: &HashMap<RoleId, Role>,
+) -> String {
     let CalculatedRoles {
         added_role_names,
         removed_role_names,
         added_existing_roles,
         removed_missing_roles,
         over_limit_roles,
+        unmet_requirements,
         ..
     } = calc_roles;
 
@@ -426,9 +519,55 @@ fn format_response(role_config: &GuildRoles, calc_roles: &CalculatedRoles) -> St
         }
     }
 
+    if !unmet_requirements.is_empty() {
+        let _ = writeln!(s, "Cannot add roles with requirements you do not meet:");
+    }
+
+    for (role_name, unmet) in unmet_requirements {
+        let _ = match unmet {
+            UnmetRequirement::Role(id) => writeln!(
+                s,
+                "`{}` (requires the `{}` role)",
+                role_name,
+                guild_roles
+                    .get(&RoleId(*id))
+                    .map_or_else(|| id.to_string(), |r| r.name.clone()),
+            ),
+            UnmetRequirement::ExcludedGroup(group_name) => writeln!(
+                s,
+                "`{}` (cannot be combined with roles in the `{}` group)",
+                role_name, group_name,
+            ),
+            UnmetRequirement::Level(level) => {
+                writeln!(s, "`{}` (requires level `{}`)", role_name, level)
+            }
+        };
+    }
+
     s
 }
 
+/// Level of a member for role requirements, only fetched if the role config
+/// has a minimum level
+async fn member_level(
+    ctx: &Context,
+    role_config: &GuildRoles,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<i64> {
+    if !role_config.uses_levels() {
+        return Ok(0);
+    }
+
+    let level = UserLevel::from_id(&ctx, user_id, guild_id)
+        .await?
+        .map_or(0, |lvl| {
+            UserLevelProgress::from_xp(lvl.msg_all_time.0).level.0
+        });
+
+    Ok(level)
+}
+
 pub async fn _message(ctx: &Context, msg: &Message) -> Result<Option<String>> {
     // ignore self
     if msg.is_own(&ctx).await {
@@ -507,6 +646,7 @@ pub async fn _message(ctx: &Context, msg: &Message) -> Result<Option<String>> {
     let (member_all_roles, member_config_roles) =
         categorize_member_roles(&role_config, member.roles.clone(), is_reset);
     let role_name_map = build_role_name_map(&role_config);
+    let level = member_level(&ctx, &role_config, guild.id, msg.author.id).await?;
 
     let calc_roles = calculate_roles(
         &role_config,
@@ -514,9 +654,10 @@ pub async fn _message(ctx: &Context, msg: &Message) -> Result<Option<String>> {
         role_name_map,
         member_all_roles,
         member_config_roles,
+        level,
     );
 
-    let s = format_response(&role_config, &calc_roles);
+    let s = format_response(&role_config, &calc_roles, &guild.roles);
 
     // After all checks if the responding string is empty then all previous ones are empty
     if s.is_empty() && !is_reset {
@@ -554,6 +695,117 @@ pub async fn _message(ctx: &Context, msg: &Message) -> Result<Option<String>> {
     Ok(Some(s))
 }
 
+pub async fn reaction_add(ctx: &Context, reaction: &Reaction) {
+    if let Err(e) = _reaction(ctx, reaction, RoleActionKind::Add).await {
+        tracing::error!(?reaction, "Failed to handle role menu reaction add: {}", e);
+    }
+}
+
+pub async fn reaction_remove(ctx: &Context, reaction: &Reaction) {
+    if let Err(e) = _reaction(ctx, reaction, RoleActionKind::Remove).await {
+        tracing::error!(
+            ?reaction,
+            "Failed to handle role menu reaction remove: {}",
+            e
+        );
+    }
+}
+
+/// Adds or removes a role from a role menu reaction, with the same limits as
+/// the role channel
+async fn _reaction(ctx: &Context, reaction: &Reaction, kind: RoleActionKind) -> Result<()> {
+    let (guild_id, user_id) = match (reaction.guild_id, reaction.user_id) {
+        (Some(guild_id), Some(user_id)) => (guild_id, user_id),
+        _ => return Ok(()),
+    };
+
+    // Ignore own reactions added to the menu
+    if user_id == ctx.cache.current_user_id().await {
+        return Ok(());
+    }
+
+    let menu = match RoleMenu::from_message_id(&ctx, reaction.message_id).await? {
+        Some(m) => m,
+        None => return Ok(()),
+    };
+
+    let guild_conf = match GuildConfig::from_id(&ctx, &guild_id).await? {
+        Some(c) => c,
+        None => {
+            tracing::error!(?guild_id, "No guild config found while handling role menu");
+            return Ok(());
+        }
+    };
+
+    let role_config: GuildRoles = match guild_conf.role_config {
+        Some(c) => serde_json::from_value(c)?,
+        None => return Ok(()),
+    };
+
+    let role = match role_config
+        .groups
+        .iter()
+        .find(|g| g.name == menu.group_name)
+        .and_then(|g| g.roles.iter().find(|r| r.matches_reaction(&reaction.emoji)))
+    {
+        Some(r) => r,
+        None => return Ok(()),
+    };
+
+    let member = guild_id.member(&ctx, user_id).await?;
+
+    if member.user.bot {
+        return Ok(());
+    }
+
+    let role_actions = vec![RoleAction {
+        index: 0,
+        kind: kind.clone(),
+        role_name: role.name.trim().to_lowercase(),
+    }];
+
+    let (member_all_roles, member_config_roles) =
+        categorize_member_roles(&role_config, member.roles.clone(), false);
+    let role_name_map = build_role_name_map(&role_config);
+    let level = member_level(&ctx, &role_config, guild_id, user_id).await?;
+
+    let calc_roles = calculate_roles(
+        &role_config,
+        role_actions.iter().collect(),
+        role_name_map,
+        member_all_roles,
+        member_config_roles,
+        level,
+    );
+
+    // Remove the reaction so the menu shows the member doesn't have the role
+    if kind == RoleActionKind::Add
+        && (!calc_roles.over_limit_roles.is_empty() || !calc_roles.unmet_requirements.is_empty())
+    {
+        reaction.delete(&ctx).await?;
+
+        return Ok(());
+    }
+
+    let before_roles: HashSet<_> = member.roles.iter().map(|r| r.0).collect();
+
+    if before_roles != calc_roles.member_new_all_roles {
+        guild_id
+            .edit_member(&ctx.http, user_id, |m| {
+                m.roles(
+                    &calc_roles
+                        .member_new_all_roles
+                        .iter()
+                        .map(|i| RoleId(*i))
+                        .collect::<Vec<RoleId>>(),
+                )
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -569,18 +821,22 @@ mod tests {
                             name: "FirstRole".into(),
                             primary_id: 1,
                             secondary_id: None,
+                            ..Default::default()
                         },
                         GuildRole {
                             name: "SecondRole".into(),
                             primary_id: 2,
                             secondary_id: Some(20),
+                            ..Default::default()
                         },
                         GuildRole {
                             name: "ThirdRole".into(),
                             primary_id: 3,
                             secondary_id: Some(30),
+                            ..Default::default()
                         },
                     ],
+                    ..Default::default()
                 },
                 GuildGroup {
                     name: "SecondGroup".into(),
@@ -589,7 +845,34 @@ mod tests {
                         name: "Dog".into(),
                         primary_id: 100,
                         secondary_id: Some(1000),
+                        ..Default::default()
                     }],
+                    ..Default::default()
+                },
+                GuildGroup {
+                    name: "ThirdGroup".into(),
+                    limit: 0,
+                    roles: vec![
+                        GuildRole {
+                            name: "Cat".into(),
+                            primary_id: 200,
+                            requirements: RoleRequirements {
+                                min_level: Some(5),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        GuildRole {
+                            name: "Bird".into(),
+                            primary_id: 300,
+                            ..Default::default()
+                        },
+                    ],
+                    requirements: RoleRequirements {
+                        required_role: Some(999),
+                        excluded_groups: vec!["SecondGroup".into()],
+                        ..Default::default()
+                    },
                 },
             ],
         }
@@ -610,6 +893,15 @@ mod tests {
     }
 
     fn test_calc_roles(roles: Vec<u64>, s: &str, calc_roles_exp: CalculatedRoles) {
+        test_calc_roles_level(roles, s, 0, calc_roles_exp);
+    }
+
+    fn test_calc_roles_level(
+        roles: Vec<u64>,
+        s: &str,
+        level: i64,
+        calc_roles_exp: CalculatedRoles,
+    ) {
         let role_config = role_conf();
 
         let role_actions = parse_role_actions(&s);
@@ -625,6 +917,7 @@ mod tests {
             role_name_map,
             member_all_roles,
             member_config_roles,
+            level,
         );
 
         assert_eq!(calc_roles, calc_roles_exp);
@@ -707,4 +1000,66 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn calculates_roles_handles_required_role() {
+        test_calc_roles(
+            vec![],
+            "+Bird",
+            CalculatedRoles {
+                unmet_requirements: vec![("Bird", UnmetRequirement::Role(999))],
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn calculates_roles_handles_excluded_groups() {
+        test_calc_roles(
+            vec![999, 100],
+            "+Bird",
+            CalculatedRoles {
+                member_new_all_roles: [999, 100].iter().cloned().collect(),
+                unmet_requirements: vec![("Bird", UnmetRequirement::ExcludedGroup("SecondGroup"))],
+                ..Default::default()
+            },
+        );
+
+        // Exclusions apply both ways
+        test_calc_roles(
+            vec![300],
+            "+Dog",
+            CalculatedRoles {
+                member_new_all_roles: [300].iter().cloned().collect(),
+                unmet_requirements: vec![("Dog", UnmetRequirement::ExcludedGroup("ThirdGroup"))],
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn calculates_roles_handles_min_level() {
+        test_calc_roles_level(
+            vec![999],
+            "+Cat +Bird",
+            1,
+            CalculatedRoles {
+                member_new_all_roles: [999, 300].iter().cloned().collect(),
+                added_role_names: vec!["Bird"],
+                unmet_requirements: vec![("Cat", UnmetRequirement::Level(5))],
+                ..Default::default()
+            },
+        );
+
+        test_calc_roles_level(
+            vec![999],
+            "+Cat +Bird",
+            5,
+            CalculatedRoles {
+                member_new_all_roles: [999, 200, 300].iter().cloned().collect(),
+                added_role_names: vec!["Cat", "Bird"],
+                ..Default::default()
+            },
+        );
+    }
 }
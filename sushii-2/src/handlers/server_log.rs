@@ -0,0 +1,484 @@
+use chrono::Utc;
+use serenity::builder::CreateEmbed;
+use serenity::{model::prelude::*, prelude::*};
+use std::collections::HashMap;
+use std::fmt::Write;
+use sushii_model::keys::SushiiCache;
+
+use crate::error::Result;
+use crate::model::sql::*;
+use crate::utils::guild::{handle_log_error, LogChannel};
+
+/// Sends an embed to the server log channel if it's enabled
+async fn send_log<F>(ctx: &Context, guild_id: GuildId, f: F) -> Result<()>
+where
+    F: FnOnce(&mut CreateEmbed) -> &mut CreateEmbed,
+{
+    let mut guild_conf = match GuildConfig::from_id(&ctx, &guild_id).await? {
+        Some(c) => c,
+        None => {
+            tracing::error!(?guild_id, "No guild config found while handling server log");
+            return Ok(());
+        }
+    };
+
+    if !guild_conf.log_server_enabled {
+        return Ok(());
+    }
+
+    let server_log_channel = match guild_conf.log_server {
+        Some(id) => ChannelId(id as u64),
+        None => return Ok(()),
+    };
+
+    let now = Utc::now().naive_utc();
+
+    let res = server_log_channel
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                f(e);
+                e.timestamp(now.format("%Y-%m-%dT%H:%M:%S").to_string());
+
+                e
+            })
+        })
+        .await;
+
+    handle_log_error(ctx, &mut guild_conf, LogChannel::Server, &res).await?;
+
+    Ok(())
+}
+
+/// Embed descriptions are max 2048 characters
+fn truncate(s: &str) -> String {
+    if s.chars().count() <= 2048 {
+        return s.to_string();
+    }
+
+    let mut truncated: String = s.chars().take(2045).collect();
+    truncated.push_str("...");
+
+    truncated
+}
+
+fn fmt_channel(id: Option<ChannelId>) -> String {
+    id.map(|id| id.mention().to_string())
+        .unwrap_or_else(|| "None".into())
+}
+
+/// Adds a line with the old and new value if it changed
+fn diff_field<T: PartialEq>(s: &mut String, name: &str, old: T, new: T, fmt: impl Fn(T) -> String) {
+    if old == new {
+        return;
+    }
+
+    let _ = writeln!(s, "**{}:** {} → {}", name, fmt(old), fmt(new));
+}
+
+/// Lists permissions added and removed, None if they are the same
+fn diff_permissions(old: Permissions, new: Permissions) -> Option<String> {
+    if old == new {
+        return None;
+    }
+
+    let mut s = String::new();
+
+    let added = new - old;
+    if !added.is_empty() {
+        let _ = writeln!(s, "+ {}", added.get_permission_names().join(", "));
+    }
+
+    let removed = old - new;
+    if !removed.is_empty() {
+        let _ = writeln!(s, "- {}", removed.get_permission_names().join(", "));
+    }
+
+    Some(s)
+}
+
+fn fmt_overwrite_target(kind: &PermissionOverwriteType) -> String {
+    match kind {
+        PermissionOverwriteType::Member(id) => id.mention().to_string(),
+        PermissionOverwriteType::Role(id) => id.mention().to_string(),
+        _ => "Unknown".into(),
+    }
+}
+
+/// Describes permission overwrites that were added, removed or changed
+fn diff_overwrites(old: &[PermissionOverwrite], new: &[PermissionOverwrite]) -> String {
+    let mut s = String::new();
+
+    for new_ow in new {
+        let target = fmt_overwrite_target(&new_ow.kind);
+
+        match old.iter().find(|o| o.kind == new_ow.kind) {
+            Some(old_ow) => {
+                if let Some(allow) = diff_permissions(old_ow.allow, new_ow.allow) {
+                    let _ = write!(s, "**Allowed for {}:**\n```diff\n{}```", target, allow);
+                }
+
+                if let Some(deny) = diff_permissions(old_ow.deny, new_ow.deny) {
+                    let _ = write!(s, "**Denied for {}:**\n```diff\n{}```", target, deny);
+                }
+            }
+            None => {
+                let _ = writeln!(s, "**Added overwrite for {}**", target);
+            }
+        }
+    }
+
+    for old_ow in old {
+        if !new.iter().any(|n| n.kind == old_ow.kind) {
+            let _ = writeln!(
+                s,
+                "**Removed overwrite for {}**",
+                fmt_overwrite_target(&old_ow.kind)
+            );
+        }
+    }
+
+    s
+}
+
+pub async fn channel_create(ctx: &Context, channel: &GuildChannel) {
+    if let Err(e) = _channel_create(&ctx, &channel).await {
+        tracing::error!("Failed to handle server log channel_create: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _channel_create(ctx: &Context, channel: &GuildChannel) -> Result<()> {
+    send_log(&ctx, channel.guild_id, |e| {
+        e.title("Channel Created");
+        e.description(format!(
+            "{} (`#{}`, {:?} channel)",
+            channel.mention(),
+            channel.name,
+            channel.kind
+        ));
+        e.footer(|f| f.text(format!("Channel ID: {}", channel.id.0)));
+        e.color(0x2ecc71)
+    })
+    .await
+}
+
+pub async fn channel_update(ctx: &Context, old: &Option<Channel>, new: &Channel) {
+    if let Err(e) = _channel_update(&ctx, &old, &new).await {
+        tracing::error!("Failed to handle server log channel_update: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _channel_update(ctx: &Context, old: &Option<Channel>, new: &Channel) -> Result<()> {
+    // Old channel is only available if it was cached
+    let (old, new) = match (old, new) {
+        (Some(Channel::Guild(old)), Channel::Guild(new)) => (old, new),
+        _ => return Ok(()),
+    };
+
+    let mut s = String::new();
+
+    diff_field(&mut s, "Name", &old.name, &new.name, |n| {
+        format!("`#{}`", n)
+    });
+    diff_field(&mut s, "Topic", &old.topic, &new.topic, |t| {
+        t.clone().unwrap_or_else(|| "None".into())
+    });
+    diff_field(&mut s, "NSFW", old.nsfw, new.nsfw, |n| n.to_string());
+    diff_field(
+        &mut s,
+        "Slowmode",
+        old.rate_limit_per_user,
+        new.rate_limit_per_user,
+        |r| format!("{}s", r.unwrap_or(0)),
+    );
+    diff_field(
+        &mut s,
+        "Category",
+        old.category_id,
+        new.category_id,
+        fmt_channel,
+    );
+
+    s.push_str(&diff_overwrites(
+        &old.permission_overwrites,
+        &new.permission_overwrites,
+    ));
+
+    // Position changes are sent for every channel that moves, so these are
+    // ignored along with anything else not shown
+    if s.is_empty() {
+        return Ok(());
+    }
+
+    send_log(&ctx, new.guild_id, |e| {
+        e.title("Channel Updated");
+        e.description(truncate(&format!("{}\n{}", new.mention(), s)));
+        e.footer(|f| f.text(format!("Channel ID: {}", new.id.0)));
+        e.color(0xe67e22)
+    })
+    .await
+}
+
+pub async fn channel_delete(ctx: &Context, channel: &GuildChannel) {
+    if let Err(e) = _channel_delete(&ctx, &channel).await {
+        tracing::error!("Failed to handle server log channel_delete: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _channel_delete(ctx: &Context, channel: &GuildChannel) -> Result<()> {
+    send_log(&ctx, channel.guild_id, |e| {
+        e.title("Channel Deleted");
+        e.description(format!("`#{}` ({:?} channel)", channel.name, channel.kind));
+        e.footer(|f| f.text(format!("Channel ID: {}", channel.id.0)));
+        e.color(0xe74c3c)
+    })
+    .await
+}
+
+pub async fn guild_role_create(ctx: &Context, guild_id: &GuildId, role: &Role) {
+    if let Err(e) = _guild_role_create(&ctx, &guild_id, &role).await {
+        tracing::error!("Failed to handle server log guild_role_create: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _guild_role_create(ctx: &Context, guild_id: &GuildId, role: &Role) -> Result<()> {
+    send_log(&ctx, *guild_id, |e| {
+        e.title("Role Created");
+        e.description(format!("{} (`{}`)", role.mention(), role.name));
+        e.footer(|f| f.text(format!("Role ID: {}", role.id.0)));
+        e.color(0x2ecc71)
+    })
+    .await
+}
+
+pub async fn guild_role_update(ctx: &Context, guild_id: &GuildId, old: &Option<Role>, new: &Role) {
+    if let Err(e) = _guild_role_update(&ctx, &guild_id, &old, &new).await {
+        tracing::error!("Failed to handle server log guild_role_update: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _guild_role_update(
+    ctx: &Context,
+    guild_id: &GuildId,
+    old: &Option<Role>,
+    new: &Role,
+) -> Result<()> {
+    let old = match old {
+        Some(r) => r,
+        None => return Ok(()),
+    };
+
+    let mut s = String::new();
+
+    diff_field(&mut s, "Name", &old.name, &new.name, |n| format!("`{}`", n));
+    diff_field(&mut s, "Color", old.colour.0, new.colour.0, |c| {
+        format!("`#{:06X}`", c)
+    });
+    diff_field(&mut s, "Hoisted", old.hoist, new.hoist, |h| h.to_string());
+    diff_field(
+        &mut s,
+        "Mentionable",
+        old.mentionable,
+        new.mentionable,
+        |m| m.to_string(),
+    );
+
+    if let Some(perms) = diff_permissions(old.permissions, new.permissions) {
+        let _ = write!(s, "**Permissions:**\n```diff\n{}```", perms);
+    }
+
+    // Position changes are sent for every role that moves
+    if s.is_empty() {
+        return Ok(());
+    }
+
+    send_log(&ctx, *guild_id, |e| {
+        e.title("Role Updated");
+        e.description(truncate(&format!("{}\n{}", new.mention(), s)));
+        e.footer(|f| f.text(format!("Role ID: {}", new.id.0)));
+        e.color(0xe67e22)
+    })
+    .await
+}
+
+pub async fn guild_role_delete(
+    ctx: &Context,
+    guild_id: &GuildId,
+    role_id: &RoleId,
+    role: &Option<Role>,
+) {
+    if let Err(e) = _guild_role_delete(&ctx, &guild_id, &role_id, &role).await {
+        tracing::error!("Failed to handle server log guild_role_delete: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _guild_role_delete(
+    ctx: &Context,
+    guild_id: &GuildId,
+    role_id: &RoleId,
+    role: &Option<Role>,
+) -> Result<()> {
+    let name = role
+        .as_ref()
+        .map(|r| format!("`{}`", r.name))
+        .unwrap_or_else(|| "Unknown role".into());
+
+    send_log(&ctx, *guild_id, |e| {
+        e.title("Role Deleted");
+        e.description(name);
+        e.footer(|f| f.text(format!("Role ID: {}", role_id.0)));
+        e.color(0xe74c3c)
+    })
+    .await
+}
+
+/// Saves the initial emojis to compare with later emoji updates
+pub async fn guild_create(ctx: &Context, guild: &Guild) {
+    let sushii_cache = ctx.data.read().await.get::<SushiiCache>().cloned().unwrap();
+
+    sushii_cache
+        .guild_emojis
+        .insert(guild.id, guild.emojis.clone());
+}
+
+pub async fn guild_emojis_update(
+    ctx: &Context,
+    guild_id: &GuildId,
+    current_state: &HashMap<EmojiId, Emoji>,
+) {
+    if let Err(e) = _guild_emojis_update(&ctx, &guild_id, &current_state).await {
+        tracing::error!("Failed to handle server log guild_emojis_update: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _guild_emojis_update(
+    ctx: &Context,
+    guild_id: &GuildId,
+    current_state: &HashMap<EmojiId, Emoji>,
+) -> Result<()> {
+    let sushii_cache = ctx.data.read().await.get::<SushiiCache>().cloned().unwrap();
+
+    let old = match sushii_cache
+        .guild_emojis
+        .insert(*guild_id, current_state.clone())
+    {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+
+    let mut s = String::new();
+
+    for (id, emoji) in current_state {
+        match old.get(id) {
+            Some(old_emoji) => diff_field(
+                &mut s,
+                &format!("Renamed {}", emoji),
+                &old_emoji.name,
+                &emoji.name,
+                |n| format!("`:{}:`", n),
+            ),
+            None => {
+                let _ = writeln!(s, "**Added:** {} `:{}:`", emoji, emoji.name);
+            }
+        }
+    }
+
+    for (id, emoji) in &old {
+        if !current_state.contains_key(id) {
+            let _ = writeln!(s, "**Removed:** `:{}:`", emoji.name);
+        }
+    }
+
+    if s.is_empty() {
+        return Ok(());
+    }
+
+    send_log(&ctx, *guild_id, |e| {
+        e.title("Emojis Updated");
+        e.description(truncate(&s));
+        e.color(0xe67e22)
+    })
+    .await
+}
+
+pub async fn guild_update(
+    ctx: &Context,
+    old_guild_if_avail: &Option<Guild>,
+    partial_guild: &PartialGuild,
+) {
+    if let Err(e) = _guild_update(&ctx, &old_guild_if_avail, &partial_guild).await {
+        tracing::error!("Failed to handle server log guild_update: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _guild_update(
+    ctx: &Context,
+    old_guild_if_avail: &Option<Guild>,
+    new: &PartialGuild,
+) -> Result<()> {
+    let old = match old_guild_if_avail {
+        Some(g) => g,
+        None => return Ok(()),
+    };
+
+    let mut s = String::new();
+
+    diff_field(&mut s, "Name", &old.name, &new.name, |n| format!("`{}`", n));
+    diff_field(&mut s, "Icon", &old.icon, &new.icon, |i| {
+        i.clone().unwrap_or_else(|| "None".into())
+    });
+    diff_field(&mut s, "Region", &old.region, &new.region, |r| {
+        format!("`{}`", r)
+    });
+    diff_field(
+        &mut s,
+        "Verification Level",
+        old.verification_level,
+        new.verification_level,
+        |v| format!("{:?}", v),
+    );
+    diff_field(
+        &mut s,
+        "AFK Channel",
+        old.afk_channel_id,
+        new.afk_channel_id,
+        fmt_channel,
+    );
+
+    if s.is_empty() {
+        return Ok(());
+    }
+
+    send_log(&ctx, new.id, |e| {
+        e.title("Server Updated");
+        e.description(truncate(&s));
+        e.color(0xe67e22)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_permissions() {
+        let old = Permissions::SEND_MESSAGES | Permissions::ADD_REACTIONS;
+        let new = Permissions::SEND_MESSAGES | Permissions::ATTACH_FILES;
+
+        let diff = diff_permissions(old, new).unwrap();
+
+        let added = Permissions::ATTACH_FILES.get_permission_names().join(", ");
+        let removed = Permissions::ADD_REACTIONS.get_permission_names().join(", ");
+
+        assert_eq!(diff, format!("+ {}\n- {}\n", added, removed));
+        assert!(diff_permissions(old, old).is_none());
+    }
+}
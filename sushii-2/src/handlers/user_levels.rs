@@ -1,8 +1,11 @@
+use chrono::Duration;
 use serenity::{model::prelude::*, prelude::*};
 
 use crate::error::Result;
 use crate::model::sql::*;
 
+const DEFAULT_LEVEL_UP_MSG: &str = "Congrats {user}, you reached level {level}!";
+
 pub async fn message(ctx: &Context, msg: &Message) {
     if let Err(e) = _message(ctx, msg).await {
         tracing::error!(?msg, "Failed to run user_levels handler: {}", e);
@@ -21,6 +24,23 @@ async fn _message(ctx: &Context, msg: &Message) -> Result<()> {
         None => return Ok(()),
     };
 
+    let guild_conf = match GuildConfig::from_id(&ctx, &guild_id).await? {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+
+    let category_id = ctx
+        .cache
+        .guild_channel(msg.channel_id)
+        .await
+        .and_then(|c| c.category_id);
+    let member_roles = msg.member.as_ref().map_or(&[][..], |m| &m.roles[..]);
+
+    let xp = match guild_conf.xp_for_message(msg.channel_id, category_id, member_roles) {
+        Some(xp) => xp,
+        None => return Ok(()),
+    };
+
     // Get user level or create a new one
     let user_level = if let Some(lvl) = UserLevel::from_id(&ctx, msg.author.id, guild_id).await? {
         lvl
@@ -30,13 +50,183 @@ async fn _message(ctx: &Context, msg: &Message) -> Result<()> {
         UserLevel::new(msg.author.id, guild_id).save(&ctx).await?
     };
 
-    // < 1 minute since last XP inc
-    if !user_level.eligible() {
+    // Within cooldown since last XP inc
+    if !user_level.eligible(Duration::seconds(guild_conf.xp_cooldown())) {
         return Ok(());
     }
 
+    let old_level = user_level.level();
+
     // Increment XP and save to DB
-    user_level.inc().save(&ctx).await?;
+    let user_level = user_level.inc(xp).save(&ctx).await?;
+
+    if user_level.level() > old_level {
+        let member_roles = msg.member.as_ref().map_or(&[][..], |m| &m.roles[..]);
+
+        level_up(
+            ctx,
+            guild_id,
+            &msg.author,
+            member_roles,
+            Some(msg.channel_id),
+            &guild_conf,
+            user_level.level(),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Gives level role rewards and announces a level up. Announcements are sent
+/// in the given channel if there isn't a level up channel set
+pub async fn level_up(
+    ctx: &Context,
+    guild_id: GuildId,
+    user: &User,
+    member_roles: &[RoleId],
+    channel_id: Option<ChannelId>,
+    guild_conf: &GuildConfig,
+    level: i64,
+) -> Result<()> {
+    let level_roles = LevelRole::from_guild(&ctx, guild_id).await?;
+
+    if !level_roles.is_empty() {
+        // Missing permissions for roles shouldn't skip the announcement
+        if let Err(e) = apply_level_roles(
+            ctx,
+            guild_id,
+            user.id,
+            member_roles,
+            level,
+            &level_roles,
+            guild_conf.level_role_stack,
+        )
+        .await
+        {
+            tracing::warn!(?guild_id, user_id = ?user.id, "Failed to apply level roles: {}", e);
+        }
+    }
+
+    if guild_conf.level_up_enabled {
+        // Failing to announce, e.g. closed DMs, shouldn't be treated as an error
+        if let Err(e) = announce_level_up(ctx, guild_id, user, channel_id, guild_conf, level).await
+        {
+            tracing::warn!(?guild_id, user_id = ?user.id, "Failed to send level up message: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces `{user}`, `{level}` and `{guild}` in a level up message
+fn fmt_level_up_msg(template: &str, user: &str, level: i64, guild: &str) -> String {
+    template
+        .replace("{user}", user)
+        .replace("{level}", &level.to_string())
+        .replace("{guild}", guild)
+}
+
+async fn announce_level_up(
+    ctx: &Context,
+    guild_id: GuildId,
+    user: &User,
+    channel_id: Option<ChannelId>,
+    guild_conf: &GuildConfig,
+    level: i64,
+) -> Result<()> {
+    let user_data = UserData::from_id(&ctx, user.id).await?;
+
+    if user_data.map_or(false, |d| d.level_up_opt_out) {
+        return Ok(());
+    }
+
+    let level_up_msg = fmt_level_up_msg(
+        guild_conf
+            .level_up_msg
+            .as_deref()
+            .unwrap_or(DEFAULT_LEVEL_UP_MSG),
+        &user.mention().to_string(),
+        level,
+        &guild_id.name(&ctx).await.unwrap_or_else(|| "".into()),
+    );
+
+    if guild_conf.level_up_dm {
+        user.direct_message(&ctx, |m| m.content(level_up_msg))
+            .await?;
+
+        return Ok(());
+    }
+
+    let channel_id = match guild_conf
+        .level_up_channel
+        .map(|id| ChannelId(id as u64))
+        .or(channel_id)
+    {
+        Some(id) => id,
+        // Nowhere to announce, e.g. voice XP without a level up channel
+        None => return Ok(()),
+    };
+
+    channel_id.say(&ctx, level_up_msg).await?;
 
     Ok(())
 }
+
+/// Adds and removes level role rewards for a member at a given level. Returns
+/// true if any roles were changed
+pub async fn apply_level_roles(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    member_roles: &[RoleId],
+    level: i64,
+    level_roles: &[LevelRole],
+    stack: bool,
+) -> Result<bool> {
+    let (add, remove) = LevelRole::rewards_for_level(level_roles, level, stack);
+
+    let add: Vec<RoleId> = add
+        .into_iter()
+        .filter(|id| !member_roles.contains(id))
+        .collect();
+    let remove: Vec<RoleId> = remove
+        .into_iter()
+        .filter(|id| member_roles.contains(id))
+        .collect();
+
+    if add.is_empty() && remove.is_empty() {
+        return Ok(false);
+    }
+
+    for role_id in add {
+        ctx.http
+            .add_member_role(guild_id.0, user_id.0, role_id.0)
+            .await?;
+    }
+
+    for role_id in remove {
+        ctx.http
+            .remove_member_role(guild_id.0, user_id.0, role_id.0)
+            .await?;
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_level_up_msg() {
+        let s = fmt_level_up_msg(
+            "{user} reached level {level} in {guild}, {user}!",
+            "<@1234>",
+            12,
+            "sushii",
+        );
+
+        assert_eq!(s, "<@1234> reached level 12 in sushii, <@1234>!");
+    }
+}
@@ -0,0 +1,214 @@
+use chrono::Utc;
+use serenity::{model::prelude::*, prelude::*};
+use std::fmt::Write;
+
+use crate::error::Result;
+use crate::model::sql::*;
+use crate::utils::guild::{handle_log_error, LogChannel};
+
+/// Gets members in voice channels that voice activity is tracked for
+pub fn tracked_voice_members(guild: &Guild) -> Vec<UserId> {
+    guild
+        .voice_states
+        .values()
+        .filter(|state| state.channel_id.is_some())
+        .filter(|state| {
+            let member = state
+                .member
+                .as_ref()
+                .or_else(|| guild.members.get(&state.user_id));
+
+            !member.map_or(false, |m| m.user.bot)
+        })
+        .map(|state| state.user_id)
+        .collect()
+}
+
+pub async fn guild_create(ctx: &Context, guild: &Guild) {
+    if let Err(e) = _guild_create(&ctx, &guild).await {
+        tracing::error!("Failed to handle voice log guild_create: {}", e);
+    }
+}
+
+/// Syncs voice sessions with the current voice states since joins and leaves
+/// could have been missed while offline
+async fn _guild_create(ctx: &Context, guild: &Guild) -> Result<()> {
+    let in_voice = tracked_voice_members(guild);
+
+    let ended = VoiceActivity::end_stale_sessions(&ctx, guild.id, &in_voice).await?;
+    tracing::debug!(?guild.id, "Ended {} stale voice sessions", ended);
+
+    let now = Utc::now().naive_utc();
+
+    for user_id in in_voice {
+        let activity = VoiceActivity::from_id(&ctx, guild.id, user_id).await?;
+
+        // Only members without an ongoing session need to be started
+        if activity
+            .as_ref()
+            .map_or(false, |a| a.session_start.is_some())
+        {
+            continue;
+        }
+
+        activity
+            .unwrap_or_else(|| VoiceActivity::new(guild.id, user_id))
+            .start_session(now)
+            .save(&ctx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn voice_state_update(
+    ctx: &Context,
+    guild_id: &Option<GuildId>,
+    old: &Option<VoiceState>,
+    new: &VoiceState,
+) {
+    if let Err(e) = _voice_state_update(&ctx, &guild_id, &old, &new).await {
+        tracing::error!("Failed to handle voice_state_update: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _voice_state_update(
+    ctx: &Context,
+    guild_id: &Option<GuildId>,
+    old: &Option<VoiceState>,
+    new: &VoiceState,
+) -> Result<()> {
+    let guild_id = match guild_id {
+        Some(id) => *id,
+        None => return Ok(()),
+    };
+
+    if new.member.as_ref().map_or(false, |m| m.user.bot) {
+        return Ok(());
+    }
+
+    let old_channel = old.as_ref().and_then(|s| s.channel_id);
+    let now = Utc::now().naive_utc();
+
+    // Track session durations, moving between channels is the same session
+    if old_channel.is_none() != new.channel_id.is_none() {
+        let activity = VoiceActivity::from_id(&ctx, guild_id, new.user_id)
+            .await?
+            .unwrap_or_else(|| VoiceActivity::new(guild_id, new.user_id));
+
+        let activity = if new.channel_id.is_some() {
+            activity.start_session(now)
+        } else {
+            activity.end_session(now)
+        };
+
+        activity.save(&ctx).await?;
+    }
+
+    let mut desc = String::new();
+    let mut colour = 0xe67e22;
+
+    match (old_channel, new.channel_id) {
+        (None, Some(channel_id)) => {
+            write!(desc, "Joined {}", channel_id.mention())?;
+            colour = 0x2ecc71;
+        }
+        (Some(channel_id), None) => {
+            write!(desc, "Left {}", channel_id.mention())?;
+            colour = 0xe74c3c;
+        }
+        (Some(old_id), Some(new_id)) if old_id != new_id => {
+            write!(
+                desc,
+                "Moved from {} to {}",
+                old_id.mention(),
+                new_id.mention()
+            )?;
+        }
+        _ => {}
+    }
+
+    // Only server mutes and deafens, self mute and deafen are too noisy
+    if let Some(old) = old {
+        if new.channel_id.is_some() && old.mute != new.mute {
+            writeln!(desc)?;
+            write!(
+                desc,
+                "{}",
+                if new.mute {
+                    "Server muted"
+                } else {
+                    "Server unmuted"
+                }
+            )?;
+        }
+
+        if new.channel_id.is_some() && old.deaf != new.deaf {
+            writeln!(desc)?;
+            write!(
+                desc,
+                "{}",
+                if new.deaf {
+                    "Server deafened"
+                } else {
+                    "Server undeafened"
+                }
+            )?;
+        }
+    }
+
+    let desc = desc.trim().to_string();
+
+    if desc.is_empty() {
+        return Ok(());
+    }
+
+    let mut guild_conf = match GuildConfig::from_id(&ctx, &guild_id).await? {
+        Some(c) => c,
+        None => {
+            tracing::error!(
+                ?guild_id,
+                "No guild config found while handling voice_state_update"
+            );
+            return Ok(());
+        }
+    };
+
+    if !guild_conf.log_voice_enabled {
+        return Ok(());
+    }
+
+    let voice_log_channel = match guild_conf.log_voice {
+        Some(id) => ChannelId(id as u64),
+        None => return Ok(()),
+    };
+
+    let user = match new.member {
+        Some(ref m) => m.user.clone(),
+        None => new.user_id.to_user(&ctx).await?,
+    };
+
+    let res = voice_log_channel
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                e.author(|a| {
+                    a.icon_url(user.face());
+                    a.name(format!("{} (ID: {})", user.tag(), user.id));
+
+                    a
+                });
+
+                e.description(desc);
+                e.timestamp(now.format("%Y-%m-%dT%H:%M:%S").to_string());
+                e.color(colour);
+
+                e
+            })
+        })
+        .await;
+
+    handle_log_error(ctx, &mut guild_conf, LogChannel::Voice, &res).await?;
+
+    Ok(())
+}
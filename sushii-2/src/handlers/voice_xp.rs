@@ -0,0 +1,167 @@
+use chrono::Utc;
+use serenity::{model::prelude::*, prelude::*};
+use std::collections::HashMap;
+use sushii_model::keys::SushiiCache;
+
+use crate::error::Result;
+use crate::model::sql::*;
+
+pub async fn guild_create(ctx: &Context, guild: &Guild) {
+    if let Err(e) = _guild_create(&ctx, &guild).await {
+        tracing::error!("Failed to handle voice XP guild_create: {}", e);
+    }
+}
+
+/// Starts tracking members already in voice since they won't have a voice
+/// state update until they change something
+async fn _guild_create(ctx: &Context, guild: &Guild) -> Result<()> {
+    match GuildConfig::from_id(&ctx, &guild.id).await? {
+        Some(conf) if conf.voice_xp_enabled => track_members(&ctx, guild, &conf, None).await,
+        _ => Ok(()),
+    }
+}
+
+pub async fn voice_state_update(ctx: &Context, guild_id: &Option<GuildId>, new: &VoiceState) {
+    if let Err(e) = _voice_state_update(&ctx, &guild_id, &new).await {
+        tracing::error!("Failed to handle voice XP voice_state_update: {}", e);
+    }
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn _voice_state_update(
+    ctx: &Context,
+    guild_id: &Option<GuildId>,
+    new: &VoiceState,
+) -> Result<()> {
+    let guild_id = match guild_id {
+        Some(id) => *id,
+        None => return Ok(()),
+    };
+
+    let guild_conf = match GuildConfig::from_id(&ctx, &guild_id).await? {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+
+    if !guild_conf.voice_xp_enabled {
+        return Ok(());
+    }
+
+    let guild = match guild_id.to_guild_cached(&ctx).await {
+        Some(g) => g,
+        None => return Ok(()),
+    };
+
+    track_members(&ctx, &guild, &guild_conf, Some(new.user_id)).await
+}
+
+/// Starts tracking voice XP for eligible members in voice and stops tracking
+/// the rest. A member that left isn't in voice states anymore, so the member
+/// that changed can be given separately
+pub async fn track_members(
+    ctx: &Context,
+    guild: &Guild,
+    guild_conf: &GuildConfig,
+    changed: Option<UserId>,
+) -> Result<()> {
+    let eligible = eligible_members(guild, guild_conf);
+
+    let sushii_cache = ctx.data.read().await.get::<SushiiCache>().cloned().unwrap();
+    let now = Utc::now().naive_utc();
+
+    // A member's change can affect others in the same channel, e.g. leaving
+    // a channel with one other member, so everyone in voice is updated
+    for user_id in guild.voice_states.keys().chain(changed.iter()) {
+        let key = (guild.id, *user_id);
+
+        if eligible.contains_key(user_id) {
+            // Members already earning XP keep their last credit time
+            sushii_cache.voice_xp.entry(key).or_insert(now);
+        } else {
+            sushii_cache.voice_xp.remove(&key);
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets members that currently earn voice XP with their XP multiplier.
+/// Members earn XP in non-AFK channels while unmuted and with at least one
+/// other unmuted member that isn't a bot
+pub fn eligible_members(guild: &Guild, guild_conf: &GuildConfig) -> HashMap<UserId, f64> {
+    let active = guild.voice_states.values().filter_map(|state| {
+        let channel_id = state.channel_id?;
+
+        if guild.afk_channel_id == Some(channel_id) || state.mute || state.self_mute {
+            return None;
+        }
+
+        // Members missing from the cache are skipped since they could be bots
+        let member = state
+            .member
+            .as_ref()
+            .or_else(|| guild.members.get(&state.user_id))?;
+
+        if member.user.bot {
+            return None;
+        }
+
+        Some((state.user_id, channel_id, member))
+    });
+
+    // Ignored members still count as another member in the channel
+    with_others(active)
+        .into_iter()
+        .filter_map(|(user_id, channel_id, member)| {
+            let category_id = guild.channels.get(&channel_id).and_then(|c| c.category_id);
+
+            if guild_conf.xp_ignored(channel_id, category_id, &member.roles) {
+                return None;
+            }
+
+            let multiplier = guild_conf.xp_multiplier(channel_id, category_id, &member.roles);
+
+            Some((user_id, multiplier))
+        })
+        .collect()
+}
+
+/// Filters active voice members to those with another active member in the
+/// same channel
+fn with_others<T>(
+    active: impl Iterator<Item = (UserId, ChannelId, T)>,
+) -> Vec<(UserId, ChannelId, T)> {
+    let mut channels: HashMap<ChannelId, Vec<(UserId, ChannelId, T)>> = HashMap::new();
+
+    for member in active {
+        channels.entry(member.1).or_default().push(member);
+    }
+
+    channels
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .flat_map(|(_, members)| members)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_other_members() {
+        let active = vec![
+            (UserId(1), ChannelId(10), ()),
+            (UserId(2), ChannelId(10), ()),
+            (UserId(3), ChannelId(20), ()),
+        ];
+
+        let mut users: Vec<UserId> = with_others(active.into_iter())
+            .into_iter()
+            .map(|m| m.0)
+            .collect();
+        users.sort();
+
+        assert_eq!(users, vec![UserId(1), UserId(2)]);
+    }
+}
@@ -135,7 +135,10 @@ async fn main() -> Result<()> {
         .group(&commands::reminders::REMINDERS_GROUP)
         .group(&commands::lastfm::LASTFM_GROUP)
         .group(&commands::users::USERS_GROUP)
+        .group(&commands::levels::LEVELS_GROUP)
+        .group(&commands::xp::XP_GROUP)
         .group(&commands::moderation::MODERATION_GROUP)
+        .group(&commands::ban_list::BANLIST_GROUP)
         .group(&commands::settings::SETTINGS_GROUP)
         .group(&commands::roles::ROLES_GROUP)
         .group(&commands::OWNER_GROUP)
@@ -146,6 +149,8 @@ async fn main() -> Result<()> {
             GatewayIntents::GUILDS
                 | GatewayIntents::GUILD_MEMBERS
                 | GatewayIntents::GUILD_BANS
+                | GatewayIntents::GUILD_EMOJIS
+                | GatewayIntents::GUILD_VOICE_STATES
                 | GatewayIntents::GUILD_MESSAGES
                 | GatewayIntents::GUILD_MESSAGE_REACTIONS
                 | GatewayIntents::DIRECT_MESSAGES
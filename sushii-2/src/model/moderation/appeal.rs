@@ -0,0 +1,282 @@
+use serenity::builder::CreateEmbed;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::error::{Error as SushiiError, Result};
+use crate::model::moderation::{ModActionExecutor, ModActionType};
+use crate::model::sql::{GuildConfig, ModAppeal, ModLogEntry};
+use crate::model::SushiiConfig;
+
+/// How long staff have to respond to an appeal
+pub const APPEAL_TIMEOUT: Duration = Duration::from_secs(60 * 60 * 24 * 3);
+
+const ACCEPT_EMOJI: &str = "✅";
+const DENY_EMOJI: &str = "❌";
+
+/// Gets the staff appeal channel if appeals are enabled
+pub fn appeal_channel(guild_conf: &GuildConfig) -> Option<ChannelId> {
+    if !guild_conf.appeal_enabled {
+        return None;
+    }
+
+    guild_conf.appeal_channel.map(|id| ChannelId(id as u64))
+}
+
+/// Gets the appeal instructions to add to ban and mute DMs, None if appeals
+/// are not enabled
+pub async fn appeal_instructions(
+    ctx: &Context,
+    guild_conf: &GuildConfig,
+    case_id: u64,
+) -> Option<String> {
+    appeal_channel(guild_conf)?;
+
+    let sushii_conf = SushiiConfig::get(&ctx).await;
+
+    Some(format!(
+        "You can appeal this by replying here with: `{}appeal {} {} [your appeal]`",
+        sushii_conf.default_prefix, guild_conf.id, case_id
+    ))
+}
+
+fn appeal_embed<'a>(
+    e: &'a mut CreateEmbed,
+    appeal: &ModAppeal,
+    user: &User,
+    entry: &ModLogEntry,
+) -> &'a mut CreateEmbed {
+    e.author(|a| {
+        a.icon_url(user.face());
+        a.name(format!("Appeal from {} (ID: {})", user.tag(), user.id.0));
+
+        a
+    });
+
+    e.field(
+        format!("Case #{} ({})", entry.case_id, entry.action),
+        entry
+            .reason
+            .clone()
+            .unwrap_or_else(|| "No reason given".into()),
+        false,
+    );
+    e.field("Appeal", &appeal.reason, false);
+    e.timestamp(appeal.created_at.format("%Y-%m-%dT%H:%M:%S").to_string());
+
+    e
+}
+
+/// Posts an appeal in the staff channel, staff reactions are handled in the
+/// reaction_add handler
+pub async fn submit(
+    ctx: &Context,
+    appeal: ModAppeal,
+    user: &User,
+    entry: ModLogEntry,
+    channel_id: ChannelId,
+) -> Result<()> {
+    let staff_msg = channel_id
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                appeal_embed(e, &appeal, &user, &entry);
+                e.description(format!(
+                    "React with {} to accept or {} to deny this appeal.",
+                    ACCEPT_EMOJI, DENY_EMOJI
+                ));
+                e.color(0xe67e22);
+
+                e
+            })
+        })
+        .await?;
+
+    appeal.msg_id(staff_msg.id.0).save(&ctx).await?;
+
+    // Add reactions after saving so a failed save doesn't leave a message
+    // that looks like it can be answered
+    for emoji in &[ACCEPT_EMOJI, DENY_EMOJI] {
+        staff_msg
+            .react(ctx, ReactionType::Unicode(emoji.to_string()))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Gets if a reaction accepts or denies an appeal, None if it's neither
+pub fn reaction_decision(emoji: &ReactionType) -> Option<bool> {
+    match emoji {
+        ReactionType::Unicode(s) if s == ACCEPT_EMOJI => Some(true),
+        ReactionType::Unicode(s) if s == DENY_EMOJI => Some(false),
+        _ => None,
+    }
+}
+
+/// Accepts or denies a pending appeal on behalf of a moderator
+pub async fn decide(
+    ctx: &Context,
+    appeal: ModAppeal,
+    accepted: bool,
+    moderator: &User,
+    channel_id: ChannelId,
+) -> Result<()> {
+    let guild_id = GuildId(appeal.guild_id as u64);
+    let status = if accepted { "accepted" } else { "denied" };
+
+    // Claimed before running the action so that multiple reactions can't
+    // decide the same appeal
+    let appeal = match appeal.decide(&ctx, status, moderator.id.0).await? {
+        Some(a) => a,
+        None => return Ok(()),
+    };
+
+    let entry = ModLogEntry::from_case_id(&ctx, appeal.guild_id as u64, appeal.case_id as u64)
+        .await?
+        .ok_or_else(|| SushiiError::Sushii("Appealed case not found".into()))?;
+    let user = UserId(appeal.user_id as u64).to_user(ctx).await?;
+
+    let mut status = status;
+
+    if accepted {
+        let action = if entry.action == "ban" {
+            ModActionType::Unban
+        } else {
+            ModActionType::Unmute
+        };
+
+        let res = ModActionExecutor {
+            action,
+            target_users: vec![user.id.0],
+            exclude_users: HashSet::new(),
+            reason: Some(format!("Appeal accepted for case #{}", entry.case_id)),
+            duration: None,
+        }
+        .execute_as(ctx, &moderator, channel_id, &guild_id)
+        .await;
+
+        match res {
+            Ok(succeeded) if succeeded.contains(&user.id.0) => {}
+            res => {
+                if let Err(e) = res {
+                    tracing::error!(?appeal, "Failed to revert appealed action: {}", e);
+                }
+
+                // The executor response explains why when it fails, staff
+                // need to revert it manually
+                status = "failed";
+            }
+        }
+    }
+
+    let appeal = if status == "failed" {
+        appeal.status(status).save(&ctx).await?
+    } else {
+        let guild_name = guild_id
+            .to_guild_cached(&ctx)
+            .await
+            .map(|g| g.name)
+            .unwrap_or_else(|| format!("Unknown Guild (ID: {})", guild_id.0));
+
+        // Ignore if dm fails, could be disabled or no longer share a guild
+        let _ = user
+            .dm(ctx, |m| {
+                m.content(format!(
+                    "Your appeal for case #{} in {} has been {}.",
+                    entry.case_id, guild_name, status
+                ))
+            })
+            .await;
+
+        appeal
+    };
+
+    let msg_id = match appeal.msg_id {
+        Some(id) => MessageId(id as u64),
+        None => return Ok(()),
+    };
+
+    channel_id
+        .edit_message(ctx, msg_id, |m| {
+            m.embed(|e| {
+                appeal_embed(e, &appeal, &user, &entry);
+
+                if status == "failed" {
+                    e.description(format!(
+                        "Appeal accepted by {} but the {} could not be reverted, \
+                        it needs to be reverted manually.",
+                        moderator.mention(),
+                        entry.action
+                    ));
+                } else {
+                    e.description(format!("Appeal {} by {}", status, moderator.mention()));
+                }
+
+                e.color(match status {
+                    "accepted" => 0x2ecc71,
+                    "failed" => 0xe67e22,
+                    _ => 0xe74c3c,
+                });
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Expires pending appeals that staff didn't respond to in time
+pub async fn expire_appeals(ctx: &Context) -> Result<()> {
+    let timeout = chrono::Duration::from_std(APPEAL_TIMEOUT).unwrap();
+    let appeals = ModAppeal::expire_pending(&ctx, timeout).await?;
+
+    for appeal in appeals {
+        if let Err(e) = edit_expired(ctx, &appeal).await {
+            tracing::warn!(?appeal, "Failed to edit expired appeal message: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn edit_expired(ctx: &Context, appeal: &ModAppeal) -> Result<()> {
+    let msg_id = match appeal.msg_id {
+        Some(id) => MessageId(id as u64),
+        None => return Ok(()),
+    };
+
+    // Appeals are posted in the current appeal channel, if it was changed
+    // since then the message is left as is
+    let channel_id = match GuildConfig::from_id(&ctx, &GuildId(appeal.guild_id as u64))
+        .await?
+        .as_ref()
+        .and_then(appeal_channel)
+    {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let entry = match ModLogEntry::from_case_id(&ctx, appeal.guild_id as u64, appeal.case_id as u64)
+        .await?
+    {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+    let user = UserId(appeal.user_id as u64).to_user(ctx).await?;
+
+    channel_id
+        .edit_message(ctx, msg_id, |m| {
+            m.embed(|e| {
+                appeal_embed(e, appeal, &user, &entry);
+                e.description("No decision was made before the appeal expired.");
+                e.color(0x95a5a6);
+
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use serenity::{model::prelude::*, prelude::*};
+
+use crate::error::Result;
+use crate::model::sql::{ModLogEntry, SharedBan};
+
+/// Unbans a user that was banned from a shared ban list
+pub async fn revert_shared_ban(ctx: &Context, shared_ban: &SharedBan, user: &User) -> Result<()> {
+    let guild_id = GuildId(shared_ban.guild_id as u64);
+    let source_guild_id = GuildId(shared_ban.source_guild_id as u64);
+    let source_name = source_guild_id
+        .to_guild_cached(&ctx)
+        .await
+        .map(|g| g.name)
+        .unwrap_or_else(|| format!("Unknown Guild (ID: {})", source_guild_id.0));
+
+    let entry = ModLogEntry::new("unban", true, guild_id.0, user)
+        .reason(&Some(format!(
+            "Automated Unban: Shared ban from {} was reverted",
+            source_name
+        )))
+        .save(&ctx)
+        .await?;
+
+    if let Err(e) = guild_id.unban(&ctx.http, user).await {
+        entry.delete(&ctx).await?;
+
+        return Err(e.into());
+    }
+
+    // SharedBan row is deleted in the guild_ban_removal handler
+    Ok(())
+}
@@ -1,5 +1,10 @@
+pub mod appeal;
+pub mod ban_list;
 pub mod mod_action;
 pub mod mod_log;
+pub mod staff_reaction;
 
+pub use ban_list::revert_shared_ban;
 pub use mod_action::{ModActionExecutor, ModActionType};
 pub use mod_log::ModLogReporter;
+pub use staff_reaction::staff_member;
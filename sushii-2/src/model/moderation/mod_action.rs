@@ -11,7 +11,7 @@ use std::fmt::Write;
 use std::result::Result as StdResult;
 
 use crate::error::{Error as SushiiError, Result};
-use crate::model::moderation::ModLogReporter;
+use crate::model::moderation::{appeal, ModLogReporter};
 use crate::model::sql::{GuildConfig, ModLogEntry, Mute};
 use sushii_model::utils::duration::{find_duration, parse_duration};
 
@@ -111,7 +111,8 @@ impl ModActionExecutor {
     async fn execute_user(
         &self,
         ctx: &Context,
-        msg: &Message,
+        executor: &User,
+        entry: &ModLogEntry,
         user: &User,
         guild: &Option<Guild>,
         guild_id: &GuildId,
@@ -120,6 +121,31 @@ impl ModActionExecutor {
     ) -> Result<Option<String>> {
         match self.action {
             ModActionType::Ban => {
+                // DM before banning since users can't be messaged after if
+                // they don't share any other guilds
+                if let Some(s) =
+                    appeal::appeal_instructions(ctx, guild_conf, entry.case_id as u64).await
+                {
+                    let guild_name = guild
+                        .as_ref()
+                        .map(|g| g.name.clone())
+                        .unwrap_or_else(|| format!("Unknown Guild (ID: {})", guild_id.0));
+
+                    // Ignore if dm fails, could be disabled
+                    let _ = user
+                        .dm(ctx, |m| {
+                            m.content(format!(
+                                "You have been banned from {}\nReason: {}\n\n{}",
+                                guild_name,
+                                self.reason
+                                    .clone()
+                                    .unwrap_or_else(|| "No reason given".into()),
+                                s
+                            ))
+                        })
+                        .await;
+                }
+
                 if let Some(reason) = &self.reason {
                     guild_id
                         .ban_with_reason(
@@ -128,8 +154,8 @@ impl ModActionExecutor {
                             0u8,
                             format!(
                                 "[Ban by {} (ID: {})] {}",
-                                &msg.author.tag(),
-                                &msg.author.id.0,
+                                &executor.tag(),
+                                &executor.id.0,
                                 &reason
                             ),
                         )
@@ -142,8 +168,8 @@ impl ModActionExecutor {
                             0u8,
                             format!(
                                 "[Ban by {} (ID: {})] No reason provided",
-                                &msg.author.tag(),
-                                &msg.author.id.0,
+                                &executor.tag(),
+                                &executor.id.0,
                             ),
                         )
                         .await?;
@@ -160,8 +186,8 @@ impl ModActionExecutor {
                             user,
                             &format!(
                                 "[Kick by {} (ID: {})] {}",
-                                &msg.author.tag(),
-                                &msg.author.id.0,
+                                &executor.tag(),
+                                &executor.id.0,
                                 &reason
                             ),
                         )
@@ -173,8 +199,8 @@ impl ModActionExecutor {
                             user,
                             &format!(
                                 "[Kick by {} (ID: {})] No reason provided",
-                                &msg.author.tag(),
-                                &msg.author.id.0,
+                                &executor.tag(),
+                                &executor.id.0,
                             ),
                         )
                         .await?;
@@ -249,7 +275,21 @@ impl ModActionExecutor {
         Ok(None)
     }
 
-    pub async fn execute(mut self, ctx: &Context, msg: &Message, guild_id: &GuildId) -> Result<()> {
+    pub async fn execute(self, ctx: &Context, msg: &Message, guild_id: &GuildId) -> Result<()> {
+        self.execute_as(ctx, &msg.author, msg.channel_id, guild_id)
+            .await
+            .map(|_| ())
+    }
+
+    /// Executes the action on behalf of executor and responds in channel_id,
+    /// returns the IDs of users the action was successful on
+    pub async fn execute_as(
+        mut self,
+        ctx: &Context,
+        executor: &User,
+        channel_id: ChannelId,
+        guild_id: &GuildId,
+    ) -> Result<Vec<u64>> {
         let guild_conf = GuildConfig::from_id(&ctx, guild_id)
             .await?
             .ok_or_else(|| SushiiError::Sushii("No guild found".into()))?;
@@ -260,23 +300,23 @@ impl ModActionExecutor {
         let action_past_str = self.action.to_past_tense();
 
         if self.target_users.is_empty() {
-            msg.channel_id
+            channel_id
                 .say(
                     &ctx,
                     "No target users were found, please give valid IDs or mentions",
                 )
                 .await?;
 
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         if let Some(Err(e)) = &self.duration {
             // If there is a duration, check if the duration parsing failed
-            msg.channel_id
+            channel_id
                 .say(&ctx, format!("Invalid duration, {}", e))
                 .await?;
 
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         // Uh... clone then flatten the Option<Result<Duration>> to just
@@ -289,8 +329,7 @@ impl ModActionExecutor {
             .flatten()
             .or_else(|| guild_conf.mute_duration.map(Duration::seconds));
 
-        let mut sent_msg = msg
-            .channel_id
+        let mut sent_msg = channel_id
             .say(
                 &ctx,
                 format!(
@@ -302,6 +341,7 @@ impl ModActionExecutor {
             .await?;
 
         let mut s = String::new();
+        let mut succeeded = Vec::new();
 
         for &id in &self.target_users {
             let user = match UserId(id).to_user(ctx).await {
@@ -326,7 +366,7 @@ impl ModActionExecutor {
 
             let entry = match ModLogEntry::new(&self.action.to_string(), true, guild_id.0, &user)
                 .reason(&self.reason)
-                .executor_id(msg.author.id.0)
+                .executor_id(executor.id.0)
                 .save(&ctx)
                 .await
             {
@@ -344,7 +384,16 @@ impl ModActionExecutor {
             };
 
             let res = self
-                .execute_user(&ctx, &msg, &user, &guild, &guild_id, &guild_conf, &duration)
+                .execute_user(
+                    &ctx,
+                    &executor,
+                    &entry,
+                    &user,
+                    &guild,
+                    &guild_id,
+                    &guild_conf,
+                    &duration,
+                )
                 .await;
 
             match res {
@@ -382,6 +431,7 @@ impl ModActionExecutor {
                     );
                     // add the action to hashset to prevent dupe actions
                     self.exclude_users.insert(id);
+                    succeeded.push(id);
                 }
             }
         }
@@ -419,7 +469,7 @@ impl ModActionExecutor {
             })
             .await?;
 
-        Ok(())
+        Ok(succeeded)
     }
 }
 
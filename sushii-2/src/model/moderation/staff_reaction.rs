@@ -0,0 +1,26 @@
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+
+/// Gets the member that added a reaction if they can ban members. Reactions
+/// from other members are removed so they don't look like a vote
+pub async fn staff_member(
+    ctx: &Context,
+    guild_id: GuildId,
+    reaction: &Reaction,
+) -> Result<Option<Member>> {
+    let user_id = match reaction.user_id {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+
+    let member = guild_id.member(ctx, user_id).await?;
+
+    if !member.permissions(ctx).await?.ban_members() {
+        let _ = reaction.delete(ctx).await;
+        return Ok(None);
+    }
+
+    Ok(Some(member))
+}
@@ -6,18 +6,26 @@ use tokio::{
     time::{self, Duration},
 };
 
+use crate::handlers::ban_list;
+use crate::model::moderation::appeal;
 use crate::model::SushiiConfig;
 
 static START: Once = Once::new();
 
+mod msg_log;
 mod mute;
 mod reminders;
 mod vlive;
+mod voice_activity;
+mod voice_xp;
 
 pub async fn start(ctx: &Context) {
     START.call_once(|| {
         task::spawn(ten_seconds(ctx.clone()));
         task::spawn(thirty_seconds(ctx.clone()));
+        task::spawn(one_minute(ctx.clone()));
+        task::spawn(five_minutes(ctx.clone()));
+        task::spawn(one_hour(ctx.clone()));
     });
 }
 
@@ -74,3 +82,52 @@ async fn thirty_seconds(ctx: Context) {
         }
     }
 }
+
+async fn one_minute(ctx: Context) {
+    let mut interval = time::interval(Duration::from_secs(60));
+
+    loop {
+        interval.tick().await;
+        tracing::debug!("Crediting voice XP...");
+
+        if let Err(e) = voice_xp::credit_voice_xp(&ctx).await {
+            tracing::error!("Failed crediting voice XP: {}", e);
+        }
+    }
+}
+
+async fn five_minutes(ctx: Context) {
+    let mut interval = time::interval(Duration::from_secs(60 * 5));
+
+    loop {
+        interval.tick().await;
+        tracing::debug!("Expiring pending appeals...");
+
+        if let Err(e) = appeal::expire_appeals(&ctx).await {
+            tracing::error!("Failed expiring pending appeals: {}", e);
+        }
+
+        if let Err(e) = ban_list::expire_alerts(&ctx).await {
+            tracing::error!("Failed expiring ban list alerts: {}", e);
+        }
+
+        tracing::debug!("Updating voice heartbeats...");
+
+        if let Err(e) = voice_activity::voice_heartbeat(&ctx).await {
+            tracing::error!("Failed updating voice heartbeats: {}", e);
+        }
+    }
+}
+
+async fn one_hour(ctx: Context) {
+    let mut interval = time::interval(Duration::from_secs(60 * 60));
+
+    loop {
+        interval.tick().await;
+        tracing::debug!("Pruning saved messages...");
+
+        if let Err(e) = msg_log::prune_saved_messages(&ctx).await {
+            tracing::error!("Failed pruning saved messages: {}", e);
+        }
+    }
+}
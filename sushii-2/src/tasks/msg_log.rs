@@ -0,0 +1,31 @@
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::model::sql::*;
+
+pub async fn prune_saved_messages(ctx: &Context) -> Result<()> {
+    let mut count = 0;
+
+    for guild_id in ctx.cache.guilds().await {
+        let guild_conf = match GuildConfig::from_id(&ctx, &guild_id).await? {
+            Some(c) => c,
+            None => continue,
+        };
+
+        match SavedMessage::prune_guild(
+            ctx,
+            guild_id,
+            guild_conf.log_msg_retention_count,
+            guild_conf.log_msg_retention_secs,
+        )
+        .await
+        {
+            Ok(n) => count += n,
+            Err(e) => tracing::error!(?guild_id, "Failed to prune saved messages: {}", e),
+        }
+    }
+
+    tracing::debug!("Pruned {} saved messages", count);
+
+    Ok(())
+}
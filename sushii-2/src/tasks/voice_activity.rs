@@ -0,0 +1,29 @@
+use chrono::offset::Utc;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::handlers::voice_log;
+use crate::model::sql::*;
+
+/// Marks ongoing voice sessions as seen, so sessions that end while offline
+/// only count up to the last heartbeat
+pub async fn voice_heartbeat(ctx: &Context) -> Result<()> {
+    let now = Utc::now().naive_utc();
+
+    for guild_id in ctx.cache.guilds().await {
+        let in_voice = match guild_id
+            .to_guild_cached(&ctx)
+            .await
+            .map(|g| voice_log::tracked_voice_members(&g))
+        {
+            Some(members) if !members.is_empty() => members,
+            _ => continue,
+        };
+
+        if let Err(e) = VoiceActivity::heartbeat(ctx, guild_id, &in_voice, now).await {
+            tracing::error!(?guild_id, "Failed to update voice heartbeat: {}", e);
+        }
+    }
+
+    Ok(())
+}
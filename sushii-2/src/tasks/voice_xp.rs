@@ -0,0 +1,115 @@
+use chrono::{naive::NaiveDateTime, offset::Utc, Duration};
+use serenity::{model::prelude::*, prelude::*};
+use std::collections::HashSet;
+use sushii_model::keys::SushiiCache;
+
+use crate::error::Result;
+use crate::handlers::{user_levels, voice_xp};
+use crate::model::sql::*;
+
+pub async fn credit_voice_xp(ctx: &Context) -> Result<()> {
+    let sushii_cache = ctx.data.read().await.get::<SushiiCache>().cloned().unwrap();
+    let now = Utc::now().naive_utc();
+
+    let guild_ids: HashSet<GuildId> = sushii_cache.voice_xp.iter().map(|e| e.key().0).collect();
+
+    for guild_id in guild_ids {
+        if let Err(e) = credit_guild(ctx, &sushii_cache, guild_id, now).await {
+            tracing::error!(?guild_id, "Failed to credit voice XP: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn credit_guild(
+    ctx: &Context,
+    sushii_cache: &SushiiCache,
+    guild_id: GuildId,
+    now: NaiveDateTime,
+) -> Result<()> {
+    let guild_conf = GuildConfig::from_id(&ctx, &guild_id).await?;
+    let guild = guild_id.to_guild_cached(&ctx).await;
+
+    let (guild_conf, guild) = match (guild_conf, guild) {
+        (Some(conf), Some(guild)) if conf.voice_xp_enabled => (conf, guild),
+        // Voice XP was disabled or the guild isn't available anymore
+        _ => {
+            sushii_cache.voice_xp.retain(|key, _| key.0 != guild_id);
+
+            return Ok(());
+        }
+    };
+
+    // Checked again in case any voice state updates were missed
+    let eligible = voice_xp::eligible_members(&guild, &guild_conf);
+
+    let tracked: Vec<(UserId, NaiveDateTime)> = sushii_cache
+        .voice_xp
+        .iter()
+        .filter(|e| e.key().0 == guild_id)
+        .map(|e| (e.key().1, *e.value()))
+        .collect();
+
+    for (user_id, last_credited) in tracked {
+        let key = (guild_id, user_id);
+
+        let multiplier = match eligible.get(&user_id) {
+            Some(m) => *m,
+            None => {
+                sushii_cache.voice_xp.remove(&key);
+                continue;
+            }
+        };
+
+        let minutes = (now - last_credited).num_minutes();
+        if minutes < 1 {
+            continue;
+        }
+
+        // Only whole minutes are credited, the rest carries over to the next
+        sushii_cache
+            .voice_xp
+            .insert(key, last_credited + Duration::minutes(minutes));
+
+        let xp = (guild_conf.voice_xp_rate() as f64 * minutes as f64 * multiplier).round() as i64;
+        if xp < 1 {
+            continue;
+        }
+
+        let user_level = match UserLevel::from_id(&ctx, user_id, guild_id).await? {
+            Some(lvl) => lvl,
+            None => UserLevel::new(user_id, guild_id).set_xp(0),
+        };
+
+        let old_level = user_level.level();
+        let user_level = user_level.inc_voice(xp).save(&ctx).await?;
+
+        if user_level.level() <= old_level {
+            continue;
+        }
+
+        let member = guild
+            .voice_states
+            .get(&user_id)
+            .and_then(|s| s.member.as_ref())
+            .or_else(|| guild.members.get(&user_id));
+
+        if let Some(member) = member {
+            // No channel to fall back to, only announced if there's a level
+            // up channel or DMs are used
+            user_levels::level_up(
+                ctx,
+                guild_id,
+                &member.user,
+                &member.roles,
+                None,
+                &guild_conf,
+                user_level.level(),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
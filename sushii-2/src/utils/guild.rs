@@ -0,0 +1,99 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::collections::HashSet;
+
+use crate::error::Result;
+use crate::model::sql::GuildConfig;
+
+/// Parses channel, category and role mentions or IDs that exist in the guild
+pub async fn parse_targets(ctx: &Context, msg: &Message, s: &str) -> Option<(Vec<i64>, Vec<i64>)> {
+    let (guild_channels, guild_roles) = msg
+        .guild_field(ctx, |g| {
+            (
+                g.channels.keys().map(|id| id.0).collect::<HashSet<u64>>(),
+                g.roles.keys().map(|id| id.0).collect::<HashSet<u64>>(),
+            )
+        })
+        .await?;
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"(?:<#|<@&|[^#&\d]|^)(\d{17,19})>?").unwrap();
+    }
+
+    let ids: Vec<u64> = RE
+        .captures_iter(s)
+        .filter_map(|caps| caps.get(1).and_then(|m| m.as_str().parse::<u64>().ok()))
+        .collect();
+
+    let channels = ids
+        .iter()
+        .filter(|id| guild_channels.contains(id))
+        .map(|&id| id as i64)
+        .collect();
+
+    let roles = ids
+        .iter()
+        .filter(|id| guild_roles.contains(id))
+        .map(|&id| id as i64)
+        .collect();
+
+    Some((channels, roles))
+}
+
+pub fn fmt_targets(channels: &[i64], roles: &[i64]) -> String {
+    channels
+        .iter()
+        .map(|&id| format!("<#{}>", id as u64))
+        .chain(roles.iter().map(|&id| format!("<@&{}>", id as u64)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Guild log channels configured in the guild config
+#[derive(Clone, Copy, Debug)]
+pub enum LogChannel {
+    Msg,
+    Member,
+    Server,
+    Voice,
+}
+
+/// Handles failing to send a message to a log channel. The channel is unset if
+/// it was deleted and the log is disabled if sushii doesn't have access to it
+pub async fn handle_log_error<T>(
+    ctx: &Context,
+    guild_conf: &mut GuildConfig,
+    log_channel: LogChannel,
+    res: &serenity::Result<T>,
+) -> Result<()> {
+    let e = match res {
+        Err(SerenityError::Http(e)) => match **e {
+            // Box cant be matched
+            HttpError::UnsuccessfulRequest(ref e) => e,
+            _ => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+
+    tracing::warn!(?e, ?log_channel, "HttpError::UnsuccessfulRequest");
+
+    match (e.error.code, log_channel) {
+        // Unknown channel -- deleted channel so just unset
+        (10003, LogChannel::Msg) => guild_conf.log_msg = None,
+        (10003, LogChannel::Member) => guild_conf.log_member = None,
+        (10003, LogChannel::Server) => guild_conf.log_server = None,
+        (10003, LogChannel::Voice) => guild_conf.log_voice = None,
+        // Missing access -- no perms so might as well just disable
+        (50001, LogChannel::Msg) => guild_conf.log_msg_enabled = false,
+        (50001, LogChannel::Member) => guild_conf.log_member_enabled = false,
+        (50001, LogChannel::Server) => guild_conf.log_server_enabled = false,
+        (50001, LogChannel::Voice) => guild_conf.log_voice_enabled = false,
+        _ => return Ok(()),
+    }
+
+    guild_conf.save(ctx).await?;
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
 pub mod duration;
+pub mod guild;
 pub mod text;
 pub mod user;
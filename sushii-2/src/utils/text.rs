@@ -5,3 +5,301 @@ pub fn escape_markdown(s: &str) -> String {
         .replace("~", "\\~")
         .replace("|", "\\|")
 }
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum DiffOp {
+    Equal,
+    Removed,
+    Added,
+}
+
+/// Splits a string into words and the whitespace between them
+fn tokenize(s: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut prev_whitespace = None;
+
+    for (i, c) in s.char_indices() {
+        let is_whitespace = c.is_whitespace();
+
+        if prev_whitespace.map_or(false, |prev| prev != is_whitespace) {
+            tokens.push(&s[start..i]);
+            start = i;
+        }
+
+        prev_whitespace = Some(is_whitespace);
+    }
+
+    if start < s.len() {
+        tokens.push(&s[start..]);
+    }
+
+    tokens
+}
+
+/// Diffs two token lists with the longest common subsequence
+fn diff_tokens<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(DiffOp, &'a str)> {
+    // Skip common prefix and suffix to keep the table small
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<(DiffOp, &str)> = old[..prefix].iter().map(|t| (DiffOp::Equal, *t)).collect();
+
+    // Too large to diff, treat as a full replacement
+    if old_mid.len() * new_mid.len() > 1_000_000 {
+        ops.extend(old_mid.iter().map(|t| (DiffOp::Removed, *t)));
+        ops.extend(new_mid.iter().map(|t| (DiffOp::Added, *t)));
+    } else {
+        let (n, m) = (old_mid.len(), new_mid.len());
+        // lcs[i][j] is the LCS length of old_mid[i..] and new_mid[j..]
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old_mid[i] == new_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+
+        while i < n || j < m {
+            if i < n && j < m && old_mid[i] == new_mid[j] {
+                ops.push((DiffOp::Equal, old_mid[i]));
+                i += 1;
+                j += 1;
+            } else if j >= m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                ops.push((DiffOp::Removed, old_mid[i]));
+                i += 1;
+            } else {
+                ops.push((DiffOp::Added, new_mid[j]));
+                j += 1;
+            }
+        }
+    }
+
+    ops.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|t| (DiffOp::Equal, *t)),
+    );
+
+    ops
+}
+
+/// Word level diff with Discord markdown, removed words are struck through and
+/// added words are bold
+pub fn word_diff(old: &str, new: &str) -> String {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    // Group consecutive tokens with the same op so each change is one span
+    let mut groups: Vec<(DiffOp, String)> = Vec::new();
+
+    for (op, token) in diff_tokens(&old_tokens, &new_tokens) {
+        match groups.last_mut() {
+            Some((last_op, text)) if *last_op == op => text.push_str(token),
+            _ => groups.push((op, token.to_string())),
+        }
+    }
+
+    let mut s = String::new();
+
+    for (op, text) in groups {
+        let text = escape_markdown(&text);
+
+        let marker = match op {
+            DiffOp::Equal => {
+                s.push_str(&text);
+                continue;
+            }
+            DiffOp::Removed => "~~",
+            DiffOp::Added => "**",
+        };
+
+        // Markdown doesn't apply if the markers are next to whitespace
+        let trimmed = text.trim();
+
+        if trimmed.is_empty() {
+            s.push_str(&text);
+            continue;
+        }
+
+        let start = text.find(trimmed).unwrap_or(0);
+        let end = start + trimmed.len();
+
+        s.push_str(&text[..start]);
+        s.push_str(marker);
+        s.push_str(trimmed);
+        s.push_str(marker);
+        s.push_str(&text[end..]);
+    }
+
+    s
+}
+
+/// Markdown markers used for word diffs
+const MARKERS: [&str; 2] = ["~~", "**"];
+
+/// Characters reserved per chunk to close and reopen markers
+const MARKER_RESERVE: usize = 4 * MARKERS.len();
+
+/// If a byte index is between two characters of the same markdown token, an
+/// escape or a marker
+fn inside_token(s: &str, i: usize) -> bool {
+    let prev = s[..i].chars().next_back();
+    let next = s[i..].chars().next();
+
+    match (prev, next) {
+        (Some('\\'), Some(_)) => true,
+        (Some(a), Some(b)) => a == b && (a == '~' || a == '*'),
+        _ => false,
+    }
+}
+
+/// Updates the open markers with the markers in a chunk
+fn update_open_markers(open: &mut Vec<&'static str>, chunk: &str) {
+    let mut rest = chunk;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '\\' {
+            // Skip escaped character
+            let mut chars = rest.chars();
+            chars.next();
+            chars.next();
+            rest = chars.as_str();
+            continue;
+        }
+
+        if let Some(marker) = MARKERS.iter().find(|m| rest.starts_with(**m)) {
+            match open.iter().position(|m| m == marker) {
+                Some(pos) => {
+                    open.remove(pos);
+                }
+                None => open.push(marker),
+            }
+
+            rest = &rest[marker.len()..];
+            continue;
+        }
+
+        rest = &rest[c.len_utf8()..];
+    }
+}
+
+/// Splits markdown into chunks of at most max characters, preferring to split
+/// at whitespace and never inside an escape or marker. Strikethrough and bold
+/// spans cut by a split are closed at the end of the chunk and reopened at the
+/// start of the next one
+pub fn split_chunks(s: &str, max: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut rest = s;
+    let mut open: Vec<&'static str> = Vec::new();
+
+    // Room for markers is only needed if the text is split
+    let max_text = if s.chars().count() > max {
+        max.saturating_sub(MARKER_RESERVE).max(1)
+    } else {
+        max
+    };
+
+    while !rest.is_empty() {
+        let prefix: String = open.concat();
+
+        let chunk_text = if rest.chars().count() > max_text {
+            let max_byte = rest
+                .char_indices()
+                .nth(max_text)
+                .map_or(rest.len(), |(i, _)| i);
+
+            // Whitespace right after the max can be split at too
+            let search_end = rest[max_byte..]
+                .chars()
+                .next()
+                .map_or(max_byte, |c| max_byte + c.len_utf8());
+
+            let mut split_at = rest[..search_end]
+                .rfind(char::is_whitespace)
+                .filter(|&i| i > 0 && i <= max_byte)
+                .unwrap_or(max_byte);
+
+            while split_at > 1 && inside_token(rest, split_at) {
+                split_at = rest[..split_at]
+                    .char_indices()
+                    .next_back()
+                    .map_or(0, |(i, _)| i);
+            }
+
+            let chunk_text = &rest[..split_at];
+            rest = rest[split_at..].trim_start();
+
+            chunk_text
+        } else {
+            let chunk_text = rest;
+            rest = "";
+
+            chunk_text
+        };
+
+        update_open_markers(&mut open, chunk_text);
+
+        let suffix: String = open.iter().rev().copied().collect();
+        chunks.push(format!("{}{}{}", prefix, chunk_text, suffix));
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_words() {
+        assert_eq!(
+            word_diff("the quick brown fox", "the slow brown fox jumps"),
+            "the ~~quick~~**slow** brown fox **jumps**"
+        );
+        assert_eq!(word_diff("same text", "same text"), "same text");
+        assert_eq!(word_diff("a *b*", "a c"), "a ~~\\*b\\*~~**c**");
+    }
+
+    #[test]
+    fn splits_chunks() {
+        assert_eq!(split_chunks("aaa bbb ccc", 11), vec!["aaa bbb ccc"]);
+        assert_eq!(split_chunks("aaa bbb ccc", 15), vec!["aaa bbb ccc"]);
+        assert_eq!(
+            split_chunks("aaa bbb ccc ddd eee", 15),
+            vec!["aaa bbb", "ccc ddd", "eee"]
+        );
+        assert!(split_chunks("", 3).is_empty());
+    }
+
+    #[test]
+    fn splits_chunks_in_markdown() {
+        let chunks = split_chunks("aaa ~~bbb ccc ddd~~ **eee**", 17);
+        assert_eq!(chunks, vec!["aaa ~~bbb~~", "~~ccc ddd~~", "**eee**"]);
+
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 17);
+        }
+
+        // Escapes and markers aren't split
+        assert_eq!(split_chunks(&"\\*".repeat(8), 13), vec!["\\*\\*"; 4]);
+        assert_eq!(
+            split_chunks("aaa~~bbbbbbbb~~", 12),
+            vec!["aaa", "~~bb~~", "~~bbbb~~", "~~bb~~"]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use serenity::utils::parse_mention;
 use serenity::{model::prelude::*, prelude::Context};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 
 pub async fn get_user(ctx: &Context, id: u64) -> Option<User> {
     // First check cache then try http
@@ -18,6 +19,80 @@ pub fn parse_id<S: AsRef<str>>(s: S) -> Option<u64> {
     parse_mention(s.as_ref()).or_else(|| s.as_ref().parse::<u64>().ok())
 }
 
+/// Max consecutive combining marks that are kept. Accents and vowel signs in
+/// scripts like Devanagari or Thai don't stack more than this, longer runs are
+/// treated as zalgo
+const MAX_COMBINING_MARKS: usize = 2;
+
+/// Removes runs of combining marks longer than MAX_COMBINING_MARKS
+fn strip_stacked_marks(chars: impl Iterator<Item = char>) -> String {
+    let mut s = String::new();
+    let mut marks = Vec::new();
+
+    for c in chars {
+        if is_combining_mark(c) {
+            marks.push(c);
+            continue;
+        }
+
+        if marks.len() <= MAX_COMBINING_MARKS {
+            s.extend(marks.iter());
+        }
+
+        marks.clear();
+        s.push(c);
+    }
+
+    if marks.len() <= MAX_COMBINING_MARKS {
+        s.extend(marks.iter());
+    }
+
+    s
+}
+
+/// Normalizes a display name by converting fancy unicode to plain characters,
+/// removing zalgo and invisible characters, and removing leading hoisting
+/// characters. Returns the fallback if there is nothing left
+pub fn normalize_name(name: &str, fallback: &str) -> String {
+    // Decompose so fancy letters and accents are separate from the base char
+    let decomposed = name
+        .nfkd()
+        .filter(|c| !c.is_control())
+        // Zero width and other format characters
+        .filter(|c| !matches!(c, '\u{200b}'..='\u{200f}' | '\u{2060}'..='\u{206f}' | '\u{feff}'));
+
+    // Recompose remaining accents with their base char
+    let normalized: String = strip_stacked_marks(decomposed).nfc().collect();
+
+    let dehoisted = normalized
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .trim_end();
+
+    if dehoisted.is_empty() {
+        return fallback.to_string();
+    }
+
+    // Nicknames are max 32 characters
+    dehoisted.chars().take(32).collect()
+}
+
+/// Nickname used if a guild doesn't have a fallback set
+pub const DEFAULT_DEHOIST_FALLBACK: &str = "dehoisted";
+
+/// Gets the normalized nickname for a member, None if it doesn't need to change
+pub fn dehoisted_name(member: &Member, fallback: &str) -> Option<String> {
+    let display_name = member.display_name();
+    let new_name = normalize_name(&display_name, fallback);
+
+    // Fallback is skipped too in case it isn't normalized itself, which would
+    // cause it to be changed again on the resulting member update
+    if new_name == *display_name || display_name.as_str() == fallback {
+        return None;
+    }
+
+    Some(new_name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,4 +108,44 @@ mod tests {
             assert_eq!(id, exp);
         }
     }
+
+    #[test]
+    fn normalizes_names() {
+        let names = vec![
+            ("!!!cool name", "cool name"),
+            ("\u{1d4ec}\u{1d4f8}\u{1d4f8}\u{1d4f5}", "cool"),
+            (
+                "z\u{0351}\u{0308}\u{0300}a\u{0300}\u{0358}\u{0351}lgo",
+                "zalgo",
+            ),
+            ("\u{200b}.name", "name"),
+            ("normal name", "normal name"),
+            ("!!!", "fallback"),
+        ];
+
+        for (name, exp) in names {
+            assert_eq!(normalize_name(name, "fallback"), exp);
+        }
+    }
+
+    #[test]
+    fn keeps_non_latin_names() {
+        let names = vec![
+            // Accents
+            "José",
+            "Nguyễn",
+            "Zoë",
+            // Decomposes to a base kana and a combining mark
+            "がんばる",
+            "山田太郎",
+            "안녕",
+            // Devanagari and Thai vowel signs are combining marks
+            "नमस्ते",
+            "สวัสดี",
+        ];
+
+        for name in names {
+            assert_eq!(normalize_name(name, "fallback"), name);
+        }
+    }
 }
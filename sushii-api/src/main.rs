@@ -1,5 +1,5 @@
 use actix_cors::Cors;
-use actix_web::{middleware, web, App, Error, HttpResponse, HttpServer};
+use actix_web::{http::header, middleware, web, App, Error, HttpRequest, HttpResponse, HttpServer};
 use juniper::{EmptyMutation, EmptySubscription, RootNode};
 use juniper_actix::{
     graphiql_handler as gqli_handler, graphql_handler, playground_handler as play_handler,
@@ -16,6 +16,30 @@ use model::Query;
 
 type Schema = RootNode<'static, Query, EmptyMutation<Context>, EmptySubscription<Context>>;
 
+/// Token required for guild-private queries, sent as `Authorization: Bearer
+/// <token>`. Guild-private queries are disabled if it isn't set
+struct ApiToken(Option<String>);
+
+impl ApiToken {
+    fn authorizes(&self, req: &HttpRequest) -> bool {
+        let token = match &self.0 {
+            Some(t) => t,
+            None => return false,
+        };
+
+        req.headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map_or(false, |v| constant_time_eq(v.as_bytes(), token.as_bytes()))
+    }
+}
+
+/// Compares all bytes so the token can't be guessed from response times
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 fn schema() -> Schema {
     Schema::new(
         Query,
@@ -33,12 +57,13 @@ async fn playground_handler() -> Result<HttpResponse, Error> {
 }
 
 async fn graphql(
-    req: actix_web::HttpRequest,
+    req: HttpRequest,
     payload: actix_web::web::Payload,
     schema: web::Data<Schema>,
     pool: web::Data<sqlx::PgPool>,
+    api_token: web::Data<ApiToken>,
 ) -> Result<HttpResponse, Error> {
-    let ctx = Context::new((*pool).clone());
+    let ctx = Context::new((*pool).clone(), api_token.authorizes(&req));
 
     graphql_handler(&schema, &ctx, req, payload).await
 }
@@ -57,6 +82,11 @@ async fn main() -> std::io::Result<()> {
         "127.0.0.1:8080".into()
     });
 
+    let api_token = env::var("API_TOKEN").ok().filter(|t| !t.is_empty());
+    if api_token.is_none() {
+        tracing::warn!("API_TOKEN not in environment, guild-private queries are disabled");
+    }
+
     let db_url = env::var("DATABASE_URL").expect("Missing DATABASE_URL in environment");
 
     let pool = PgPoolOptions::new()
@@ -72,6 +102,7 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .data(schema())
             .data(pool.clone())
+            .data(ApiToken(api_token.clone()))
             .wrap(middleware::Compress::default())
             .wrap(middleware::Logger::default())
             .wrap(
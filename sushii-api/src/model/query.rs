@@ -1,10 +1,13 @@
+use chrono::{Duration, Utc};
 use juniper::{graphql_object, FieldResult};
 use sushii_model::{
     cursor::encode_cursor,
     model::{
         juniper::Context,
-        sql::{CachedGuild, UserLevel, UserLevelRanked, UserXP},
-        user::TimeFrame,
+        sql::{
+            CachedGuild, ModStats, UserLevel, UserLevelRanked, UserXP, XpSeason, XpSeasonStanding,
+        },
+        user::{TimeFrame, XpSource},
         BigInt,
     },
     Error,
@@ -47,10 +50,41 @@ impl Query {
             .map_err(Into::into)
     }
 
+    /// Get mod log case counts in the last number of days, and the same
+    /// number of days before that. Optionally filtered by a moderator.
+    /// Requires the API token, callers are trusted to check that the user can
+    /// moderate the guild
+    async fn mod_stats(
+        ctx: &Context,
+        guild_id: BigInt,
+        executor_id: Option<BigInt>,
+        days: i32,
+    ) -> FieldResult<ModStats> {
+        ctx.require_auth()?;
+
+        if !(1..=365).contains(&days) {
+            return Err(Error::Sushii("days must be between 1 and 365".into()).into());
+        }
+
+        ModStats::from_period(
+            &ctx.pool,
+            guild_id,
+            executor_id,
+            Utc::now().naive_utc(),
+            Duration::days(days.into()),
+        )
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Get a guild or global XP leaderboard. Guild leaderboards can be only
+    /// text or voice XP, which defaults to combined. Text and voice XP are
+    /// only counted all time, so they can't be used with other timeframes
     async fn user_xp_leaderboard_connection(
         ctx: &Context,
         guild_id: Option<BigInt>,
         timeframe: TimeFrame,
+        source: Option<XpSource>,
         first: BigInt,
         after: Option<String>,
     ) -> FieldResult<UserXPConnection> {
@@ -58,8 +92,22 @@ impl Query {
         let first_with_peek = BigInt(first.0 + 1);
 
         // If None, global ranks
+        let source = source.unwrap_or(XpSource::Combined);
+
         let (total_count, users) = if let Some(guild_id) = guild_id {
-            UserXP::guild_top(&ctx.pool, guild_id, timeframe, first_with_peek, after).await?
+            UserXP::guild_top(
+                &ctx.pool,
+                guild_id,
+                timeframe,
+                source,
+                first_with_peek,
+                after,
+            )
+            .await?
+        } else if !matches!(source, XpSource::Combined) {
+            return Err(
+                Error::Sushii("Global XP leaderboards can only be combined XP".into()).into(),
+            );
         } else {
             UserXP::global_top(&ctx.pool, timeframe, first_with_peek, after).await?
         };
@@ -100,6 +148,66 @@ impl Query {
             page_info,
         })
     }
+
+    /// Get a guild's ended XP seasons, newest first
+    async fn xp_seasons(ctx: &Context, guild_id: BigInt) -> FieldResult<Vec<XpSeason>> {
+        XpSeason::from_guild(&ctx.pool, guild_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Get the leaderboard of an ended XP season
+    async fn xp_season_leaderboard_connection(
+        ctx: &Context,
+        season_id: BigInt,
+        first: BigInt,
+        after: Option<String>,
+    ) -> FieldResult<XpSeasonStandingConnection> {
+        // Fetch 1 extra to see if theres a next page truncated later
+        let first_with_peek = BigInt(first.0 + 1);
+
+        let (total_count, standings) =
+            XpSeasonStanding::season_top(&ctx.pool, season_id, first_with_peek, after).await?;
+
+        let standings_with_peek_len = standings.len();
+
+        let edges: Vec<XpSeasonStandingEdge> = standings
+            .into_iter()
+            .enumerate()
+            // Remove the last one if there is an extra peek element
+            .filter(|(i, _)| *i != first_with_peek.0 as usize - 1)
+            .map(|(_, node)| {
+                let cursor = encode_cursor(node.xp.0, node.user_id.0);
+
+                XpSeasonStandingEdge { node, cursor }
+            })
+            .collect();
+
+        let page_info = PageInfo {
+            has_previous_page: false,
+            has_next_page: first_with_peek.0 as usize == standings_with_peek_len,
+            start_cursor: edges
+                .first()
+                .map(|e| e.cursor.clone())
+                .ok_or_else(|| Error::Sushii("No data was returned".into()))?,
+            end_cursor: edges
+                .last()
+                .map(|e| e.cursor.clone())
+                .ok_or_else(|| Error::Sushii("No data was returned".into()))?,
+        };
+
+        Ok(XpSeasonStandingConnection {
+            total_count,
+            edges,
+            page_info,
+        })
+    }
 }
 
 relay_connection!(UserXPConnection, UserXPEdge, UserXP, Context);
+relay_connection!(
+    XpSeasonStandingConnection,
+    XpSeasonStandingEdge,
+    XpSeasonStanding,
+    Context
+);
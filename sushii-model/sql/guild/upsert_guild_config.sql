@@ -24,7 +24,39 @@ INSERT INTO app_public.guild_configs (
     mute_dm_text,
     mute_dm_enabled,
     max_mention,
-    disabled_channels
+    disabled_channels,
+    appeal_channel,
+    appeal_enabled,
+    age_gate_duration,
+    age_gate_quarantine,
+    age_gate_dm_text,
+    age_gate_dm_enabled,
+    age_gate_allowlist,
+    dehoist_enabled,
+    dehoist_fallback,
+    log_msg_retention_count,
+    log_msg_retention_secs,
+    log_server,
+    log_server_enabled,
+    log_voice,
+    log_voice_enabled,
+    log_msg_ignore_channels,
+    log_msg_ignore_roles,
+    snipe_enabled,
+    level_role_stack,
+    level_up_msg,
+    level_up_enabled,
+    level_up_channel,
+    level_up_dm,
+    xp_min,
+    xp_max,
+    xp_cooldown_secs,
+    xp_ignore_channels,
+    xp_ignore_roles,
+    xp_channel_multipliers,
+    xp_role_multipliers,
+    voice_xp_enabled,
+    voice_xp_per_minute
   )
 VALUES (
     $1,
@@ -52,7 +84,39 @@ VALUES (
     $23,
     $24,
     $25,
-    $26
+    $26,
+    $27,
+    $28,
+    $29,
+    $30,
+    $31,
+    $32,
+    $33,
+    $34,
+    $35,
+    $36,
+    $37,
+    $38,
+    $39,
+    $40,
+    $41,
+    $42,
+    $43,
+    $44,
+    $45,
+    $46,
+    $47,
+    $48,
+    $49,
+    $50,
+    $51,
+    $52,
+    $53,
+    $54,
+    $55,
+    $56,
+    $57,
+    $58
   ) ON CONFLICT (id) DO
 UPDATE
 SET -- id = $1, Don't need to update ID 
@@ -80,4 +144,36 @@ SET -- id = $1, Don't need to update ID
   mute_dm_text = $23,
   mute_dm_enabled = $24,
   max_mention = $25,
-  disabled_channels = $26
+  disabled_channels = $26,
+  appeal_channel = $27,
+  appeal_enabled = $28,
+  age_gate_duration = $29,
+  age_gate_quarantine = $30,
+  age_gate_dm_text = $31,
+  age_gate_dm_enabled = $32,
+  age_gate_allowlist = $33,
+  dehoist_enabled = $34,
+  dehoist_fallback = $35,
+  log_msg_retention_count = $36,
+  log_msg_retention_secs = $37,
+  log_server = $38,
+  log_server_enabled = $39,
+  log_voice = $40,
+  log_voice_enabled = $41,
+  log_msg_ignore_channels = $42,
+  log_msg_ignore_roles = $43,
+  snipe_enabled = $44,
+  level_role_stack = $45,
+  level_up_msg = $46,
+  level_up_enabled = $47,
+  level_up_channel = $48,
+  level_up_dm = $49,
+  xp_min = $50,
+  xp_max = $51,
+  xp_cooldown_secs = $52,
+  xp_ignore_channels = $53,
+  xp_ignore_roles = $54,
+  xp_channel_multipliers = $55,
+  xp_role_multipliers = $56,
+  voice_xp_enabled = $57,
+  voice_xp_per_minute = $58
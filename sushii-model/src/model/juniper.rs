@@ -1,3 +1,4 @@
+use crate::error::{Error, Result};
 use crate::model::sql::user::cached_user::{CachedUserBatcher, CachedUserLoader};
 use std::sync::Arc;
 
@@ -5,14 +6,29 @@ use std::sync::Arc;
 pub struct Context {
     pub pool: Arc<sqlx::PgPool>,
     pub cached_user_loader: CachedUserLoader,
+    /// If the request has the API token, required for guild-private data
+    pub authorized: bool,
 }
 
 impl Context {
-    pub fn new(pool: Arc<sqlx::PgPool>) -> Self {
+    pub fn new(pool: Arc<sqlx::PgPool>, authorized: bool) -> Self {
         Self {
             pool: pool.clone(),
             cached_user_loader: CachedUserLoader::new(CachedUserBatcher::new(pool.clone()))
                 .with_yield_count(100),
+            authorized,
+        }
+    }
+
+    /// Errors if the request doesn't have the API token, for guild-private
+    /// data such as moderation stats
+    pub fn require_auth(&self) -> Result<()> {
+        if self.authorized {
+            Ok(())
+        } else {
+            Err(Error::Sushii(
+                "Unauthorized, this requires an API token".into(),
+            ))
         }
     }
 }
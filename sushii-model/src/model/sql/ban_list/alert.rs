@@ -0,0 +1,116 @@
+use chrono::{naive::NaiveDateTime, offset::Utc, Duration};
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::keys::DbPool;
+
+/// A ban alert sent to a subscribed guild that is waiting for staff to ban
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct BanListAlert {
+    pub msg_id: i64,
+    /// Subscribed guild the alert was sent to
+    pub guild_id: i64,
+    pub channel_id: i64,
+    pub user_id: i64,
+    pub source_guild_id: i64,
+    /// Mod log case in the source guild
+    pub source_case_id: i64,
+    pub created_at: NaiveDateTime,
+}
+
+impl BanListAlert {
+    pub fn new(
+        msg_id: MessageId,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        user_id: UserId,
+        source_guild_id: GuildId,
+        source_case_id: i64,
+    ) -> Self {
+        Self {
+            msg_id: msg_id.0 as i64,
+            guild_id: guild_id.0 as i64,
+            channel_id: channel_id.0 as i64,
+            user_id: user_id.0 as i64,
+            source_guild_id: source_guild_id.0 as i64,
+            source_case_id,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    pub async fn from_msg_id(ctx: &Context, msg_id: MessageId) -> Result<Option<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            BanListAlert,
+            r#"
+            SELECT *
+              FROM app_public.ban_list_alerts
+             WHERE msg_id = $1
+            "#,
+            msg_id.0 as i64,
+        )
+        .fetch_optional(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Deletes alerts older than the timeout
+    pub async fn delete_expired(ctx: &Context, timeout: Duration) -> Result<()> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query!(
+            r#"
+            DELETE FROM app_public.ban_list_alerts
+                  WHERE created_at < $1
+            "#,
+            Utc::now().naive_utc() - timeout,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn save(self, ctx: &Context) -> Result<Self> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO app_public.ban_list_alerts
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            self.msg_id,
+            self.guild_id,
+            self.channel_id,
+            self.user_id,
+            self.source_guild_id,
+            self.source_case_id,
+            self.created_at,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(self)
+    }
+
+    /// Deletes the alert, returns false if it was already deleted. This
+    /// prevents multiple staff reactions from handling the same alert
+    pub async fn delete(&self, ctx: &Context) -> Result<bool> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        let res = sqlx::query!(
+            r#"
+            DELETE FROM app_public.ban_list_alerts
+                  WHERE msg_id = $1
+            "#,
+            self.msg_id,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(res.rows_affected() > 0)
+    }
+}
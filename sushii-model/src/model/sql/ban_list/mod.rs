@@ -0,0 +1,9 @@
+pub mod alert;
+pub mod shared_ban;
+pub mod subscription;
+pub mod trust;
+
+pub use self::{
+    alert::BanListAlert, shared_ban::SharedBan, subscription::BanListSubscription,
+    trust::BanListTrust,
+};
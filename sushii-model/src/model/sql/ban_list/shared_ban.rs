@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::keys::DbPool;
+
+/// A ban that was added from another guild's ban list
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct SharedBan {
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub source_guild_id: i64,
+    /// Mod log case in the source guild
+    pub source_case_id: i64,
+    /// Mod log case in this guild
+    pub case_id: Option<i64>,
+}
+
+impl SharedBan {
+    pub fn new(
+        guild_id: GuildId,
+        user_id: UserId,
+        source_guild_id: GuildId,
+        source_case_id: i64,
+    ) -> Self {
+        Self {
+            guild_id: guild_id.0 as i64,
+            user_id: user_id.0 as i64,
+            source_guild_id: source_guild_id.0 as i64,
+            source_case_id,
+            case_id: None,
+        }
+    }
+
+    pub fn case_id(mut self, case_id: i64) -> Self {
+        self.case_id.replace(case_id);
+        self
+    }
+
+    pub async fn from_id(
+        ctx: &Context,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            SharedBan,
+            r#"
+            SELECT *
+              FROM app_public.shared_bans
+             WHERE guild_id = $1
+               AND user_id = $2
+            "#,
+            guild_id.0 as i64,
+            user_id.0 as i64,
+        )
+        .fetch_optional(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Gets all bans in a guild that originated from a source guild
+    pub async fn from_source(
+        ctx: &Context,
+        guild_id: GuildId,
+        source_guild_id: GuildId,
+    ) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            SharedBan,
+            r#"
+            SELECT *
+              FROM app_public.shared_bans
+             WHERE guild_id = $1
+               AND source_guild_id = $2
+            "#,
+            guild_id.0 as i64,
+            source_guild_id.0 as i64,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Gets all bans of a user in any guild that originated from a source guild
+    pub async fn from_source_user(
+        ctx: &Context,
+        source_guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            SharedBan,
+            r#"
+            SELECT *
+              FROM app_public.shared_bans
+             WHERE source_guild_id = $1
+               AND user_id = $2
+            "#,
+            source_guild_id.0 as i64,
+            user_id.0 as i64,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    pub async fn save(self, ctx: &Context) -> Result<Self> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO app_public.shared_bans
+                 VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (guild_id, user_id)
+              DO UPDATE
+                    SET source_guild_id = $3,
+                        source_case_id = $4,
+                        case_id = $5
+            "#,
+            self.guild_id,
+            self.user_id,
+            self.source_guild_id,
+            self.source_case_id,
+            self.case_id,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(self)
+    }
+
+    pub async fn delete(self, ctx: &Context) -> Result<()> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query!(
+            r#"
+            DELETE FROM app_public.shared_bans
+                  WHERE guild_id = $1
+                    AND user_id = $2
+            "#,
+            self.guild_id,
+            self.user_id,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::keys::DbPool;
+
+/// A guild subscribed to another guild's bans
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct BanListSubscription {
+    pub guild_id: i64,
+    pub source_guild_id: i64,
+    /// Ban automatically, otherwise only send an alert to channel_id
+    pub auto_ban: bool,
+    pub channel_id: i64,
+}
+
+impl BanListSubscription {
+    pub fn new(
+        guild_id: GuildId,
+        source_guild_id: GuildId,
+        auto_ban: bool,
+        channel_id: ChannelId,
+    ) -> Self {
+        Self {
+            guild_id: guild_id.0 as i64,
+            source_guild_id: source_guild_id.0 as i64,
+            auto_ban,
+            channel_id: channel_id.0 as i64,
+        }
+    }
+
+    pub async fn from_id(
+        ctx: &Context,
+        guild_id: GuildId,
+        source_guild_id: GuildId,
+    ) -> Result<Option<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            BanListSubscription,
+            r#"
+            SELECT *
+              FROM app_public.ban_list_subscriptions
+             WHERE guild_id = $1
+               AND source_guild_id = $2
+            "#,
+            guild_id.0 as i64,
+            source_guild_id.0 as i64,
+        )
+        .fetch_optional(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Gets all subscriptions of a guild
+    pub async fn from_guild_id(ctx: &Context, guild_id: GuildId) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            BanListSubscription,
+            r#"
+            SELECT *
+              FROM app_public.ban_list_subscriptions
+             WHERE guild_id = $1
+            "#,
+            guild_id.0 as i64,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Gets all subscribers of a source guild
+    pub async fn from_source_guild_id(
+        ctx: &Context,
+        source_guild_id: GuildId,
+    ) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            BanListSubscription,
+            r#"
+            SELECT *
+              FROM app_public.ban_list_subscriptions
+             WHERE source_guild_id = $1
+            "#,
+            source_guild_id.0 as i64,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    pub async fn save(self, ctx: &Context) -> Result<Self> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO app_public.ban_list_subscriptions
+                 VALUES ($1, $2, $3, $4)
+            ON CONFLICT (guild_id, source_guild_id)
+              DO UPDATE
+                    SET auto_ban = $3,
+                        channel_id = $4
+            "#,
+            self.guild_id,
+            self.source_guild_id,
+            self.auto_ban,
+            self.channel_id,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(self)
+    }
+
+    pub async fn delete(self, ctx: &Context) -> Result<()> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query!(
+            r#"
+            DELETE FROM app_public.ban_list_subscriptions
+                  WHERE guild_id = $1
+                    AND source_guild_id = $2
+            "#,
+            self.guild_id,
+            self.source_guild_id,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
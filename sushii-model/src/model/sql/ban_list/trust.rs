@@ -0,0 +1,104 @@
+use chrono::{naive::NaiveDateTime, offset::Utc};
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::keys::DbPool;
+
+/// A guild allowing another guild to subscribe to its bans
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct BanListTrust {
+    pub guild_id: i64,
+    pub trusted_guild_id: i64,
+    pub created_at: NaiveDateTime,
+}
+
+impl BanListTrust {
+    pub fn new(guild_id: GuildId, trusted_guild_id: GuildId) -> Self {
+        Self {
+            guild_id: guild_id.0 as i64,
+            trusted_guild_id: trusted_guild_id.0 as i64,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    pub async fn from_id(
+        ctx: &Context,
+        guild_id: GuildId,
+        trusted_guild_id: GuildId,
+    ) -> Result<Option<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            BanListTrust,
+            r#"
+            SELECT *
+              FROM app_public.ban_list_trusts
+             WHERE guild_id = $1
+               AND trusted_guild_id = $2
+            "#,
+            guild_id.0 as i64,
+            trusted_guild_id.0 as i64,
+        )
+        .fetch_optional(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Gets all guilds trusted by a guild
+    pub async fn from_guild_id(ctx: &Context, guild_id: GuildId) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            BanListTrust,
+            r#"
+            SELECT *
+              FROM app_public.ban_list_trusts
+             WHERE guild_id = $1
+            "#,
+            guild_id.0 as i64,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    pub async fn save(self, ctx: &Context) -> Result<Self> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO app_public.ban_list_trusts
+                 VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+            self.guild_id,
+            self.trusted_guild_id,
+            self.created_at,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(self)
+    }
+
+    /// Deletes the trust, this also removes the trusted guild's subscription
+    pub async fn delete(self, ctx: &Context) -> Result<()> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query!(
+            r#"
+            DELETE FROM app_public.ban_list_trusts
+                  WHERE guild_id = $1
+                    AND trusted_guild_id = $2
+            "#,
+            self.guild_id,
+            self.trusted_guild_id,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
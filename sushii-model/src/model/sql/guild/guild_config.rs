@@ -1,7 +1,9 @@
+use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 use serenity::utils::parse_channel;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt;
 use std::time::Duration;
@@ -9,6 +11,13 @@ use std::time::Duration;
 use crate::model::sql::GuildSetting;
 use crate::prelude::*;
 
+/// XP given per message if no range is set
+pub const DEFAULT_XP: i32 = 5;
+/// Seconds between messages that earn XP if no cooldown is set
+pub const DEFAULT_XP_COOLDOWN_SECS: i64 = 60;
+/// XP given per minute in voice channels if no rate is set
+pub const DEFAULT_VOICE_XP_PER_MINUTE: i32 = 2;
+
 #[derive(Deserialize, Default, Serialize, sqlx::FromRow, Clone, Debug)]
 pub struct GuildConfig {
     pub id: i64,
@@ -66,6 +75,76 @@ pub struct GuildConfig {
 
     /// Channels where commands are ignored
     pub disabled_channels: Option<Vec<i64>>,
+
+    /// Staff channel where ban / mute appeals are sent
+    pub appeal_channel: Option<i64>,
+    pub appeal_enabled: bool,
+
+    /// Minimum account age in seconds to join, newer accounts are kicked
+    /// or quarantined
+    pub age_gate_duration: Option<i64>,
+    /// Add the mute role instead of kicking
+    pub age_gate_quarantine: bool,
+    pub age_gate_dm_text: Option<String>,
+    pub age_gate_dm_enabled: bool,
+    /// Users that bypass the account age gate
+    pub age_gate_allowlist: Option<Vec<i64>>,
+
+    /// Normalize member display names, opt-in since it changes nicknames
+    pub dehoist_enabled: bool,
+    /// Nickname used when nothing is left after normalizing
+    pub dehoist_fallback: Option<String>,
+
+    /// Max number of saved messages per channel for the message log
+    pub log_msg_retention_count: Option<i32>,
+    /// Max age in seconds of saved messages for the message log
+    pub log_msg_retention_secs: Option<i64>,
+
+    /// Channel, role, emoji and guild setting change log
+    pub log_server: Option<i64>,
+    pub log_server_enabled: bool,
+
+    /// Voice channel join, leave and move log
+    pub log_voice: Option<i64>,
+    pub log_voice_enabled: bool,
+
+    /// Channels and categories ignored by the message log
+    pub log_msg_ignore_channels: Option<Vec<i64>>,
+    /// Members with these roles are ignored by the message log
+    pub log_msg_ignore_roles: Option<Vec<i64>>,
+
+    /// Allows snipe and editsnipe, opt-in for privacy
+    pub snipe_enabled: bool,
+
+    /// Keep lower level role rewards instead of replacing them
+    pub level_role_stack: bool,
+
+    /// Level up announcement text
+    pub level_up_msg: Option<String>,
+    pub level_up_enabled: bool,
+    /// Channel for level up announcements, same channel as the message if not set
+    pub level_up_channel: Option<i64>,
+    /// Send level up announcements in DMs instead
+    pub level_up_dm: bool,
+
+    /// XP given per message, a random amount between min and max
+    pub xp_min: Option<i32>,
+    pub xp_max: Option<i32>,
+    /// Seconds between messages that earn XP
+    pub xp_cooldown_secs: Option<i64>,
+    /// Channels and categories where messages earn no XP
+    pub xp_ignore_channels: Option<Vec<i64>>,
+    /// Members with these roles earn no XP
+    pub xp_ignore_roles: Option<Vec<i64>>,
+    /// Channel or category ID to XP multiplier
+    pub xp_channel_multipliers: Option<serde_json::Value>,
+    /// Role ID to XP multiplier
+    pub xp_role_multipliers: Option<serde_json::Value>,
+
+    /// Members earn XP for time in voice channels
+    pub voice_xp_enabled: bool,
+    /// XP given per minute in a voice channel
+    pub voice_xp_per_minute: Option<i32>,
 }
 
 impl GuildConfig {
@@ -82,6 +161,11 @@ impl GuildConfig {
             log_member_enabled: true,
             mute_dm_enabled: true,
             warn_dm_enabled: true,
+            appeal_enabled: true,
+            age_gate_dm_enabled: true,
+            log_server_enabled: true,
+            log_voice_enabled: true,
+            level_role_stack: true,
             ..Default::default()
         }
     }
@@ -126,12 +210,58 @@ impl GuildConfig {
                         as i64,
                 );
             }
+            GuildSetting::ServerLog => {
+                self.log_server.replace(
+                    parse_channel(val).ok_or_else(|| Error::Sushii("invalid channel".into()))?
+                        as i64,
+                );
+            }
+            GuildSetting::VoiceLog => {
+                self.log_voice.replace(
+                    parse_channel(val).ok_or_else(|| Error::Sushii("invalid channel".into()))?
+                        as i64,
+                );
+            }
             GuildSetting::MuteDm => {
                 self.mute_dm_text.replace(val.into());
             }
             GuildSetting::WarnDm => {
                 self.mute_dm_text.replace(val.into());
             }
+            GuildSetting::Appeal => {
+                self.appeal_channel.replace(
+                    parse_channel(val).ok_or_else(|| Error::Sushii("invalid channel".into()))?
+                        as i64,
+                );
+            }
+            GuildSetting::Dehoist => {
+                self.dehoist_fallback.replace(val.into());
+            }
+            GuildSetting::Snipe => {
+                return Err(Error::Sushii(
+                    "this setting can only be enabled/disabled".into(),
+                ));
+            }
+            GuildSetting::LevelUpMsg => {
+                self.level_up_msg.replace(val.into());
+            }
+            GuildSetting::LevelUpChannel => match val.trim() {
+                "dm" => {
+                    self.level_up_dm = true;
+                    self.level_up_channel = None;
+                }
+                "here" => {
+                    self.level_up_dm = false;
+                    self.level_up_channel = None;
+                }
+                val => {
+                    self.level_up_channel
+                        .replace(parse_channel(val).ok_or_else(|| {
+                            Error::Sushii("invalid channel, use a channel, `here` or `dm`".into())
+                        })? as i64);
+                    self.level_up_dm = false;
+                }
+            },
         }
 
         Ok(())
@@ -177,6 +307,20 @@ impl GuildConfig {
 
                 self.log_member_enabled = new_value;
             }
+            GuildSetting::ServerLog => {
+                if self.log_server_enabled == new_value {
+                    return Ok(false);
+                }
+
+                self.log_server_enabled = new_value;
+            }
+            GuildSetting::VoiceLog => {
+                if self.log_voice_enabled == new_value {
+                    return Ok(false);
+                }
+
+                self.log_voice_enabled = new_value;
+            }
             GuildSetting::MuteDm => {
                 if self.mute_dm_enabled == new_value {
                     return Ok(false);
@@ -191,7 +335,35 @@ impl GuildConfig {
 
                 self.warn_dm_enabled = new_value;
             }
-            GuildSetting::JoinReact | GuildSetting::MsgChannel => {
+            GuildSetting::Appeal => {
+                if self.appeal_enabled == new_value {
+                    return Ok(false);
+                }
+
+                self.appeal_enabled = new_value;
+            }
+            GuildSetting::Dehoist => {
+                if self.dehoist_enabled == new_value {
+                    return Ok(false);
+                }
+
+                self.dehoist_enabled = new_value;
+            }
+            GuildSetting::Snipe => {
+                if self.snipe_enabled == new_value {
+                    return Ok(false);
+                }
+
+                self.snipe_enabled = new_value;
+            }
+            GuildSetting::LevelUpMsg => {
+                if self.level_up_enabled == new_value {
+                    return Ok(false);
+                }
+
+                self.level_up_enabled = new_value;
+            }
+            GuildSetting::JoinReact | GuildSetting::MsgChannel | GuildSetting::LevelUpChannel => {
                 return Err(Error::Sushii(
                     "this setting cannot be enabled/disabled".into(),
                 ));
@@ -232,6 +404,14 @@ impl GuildConfig {
                 self.log_member_enabled = !self.log_member_enabled;
                 self.log_member_enabled
             }
+            GuildSetting::ServerLog => {
+                self.log_server_enabled = !self.log_server_enabled;
+                self.log_server_enabled
+            }
+            GuildSetting::VoiceLog => {
+                self.log_voice_enabled = !self.log_voice_enabled;
+                self.log_voice_enabled
+            }
             GuildSetting::MuteDm => {
                 self.mute_dm_enabled = !self.mute_dm_enabled;
                 self.mute_dm_enabled
@@ -240,7 +420,23 @@ impl GuildConfig {
                 self.warn_dm_enabled = !self.warn_dm_enabled;
                 self.warn_dm_enabled
             }
-            GuildSetting::JoinReact | GuildSetting::MsgChannel => {
+            GuildSetting::Appeal => {
+                self.appeal_enabled = !self.appeal_enabled;
+                self.appeal_enabled
+            }
+            GuildSetting::Dehoist => {
+                self.dehoist_enabled = !self.dehoist_enabled;
+                self.dehoist_enabled
+            }
+            GuildSetting::Snipe => {
+                self.snipe_enabled = !self.snipe_enabled;
+                self.snipe_enabled
+            }
+            GuildSetting::LevelUpMsg => {
+                self.level_up_enabled = !self.level_up_enabled;
+                self.level_up_enabled
+            }
+            GuildSetting::JoinReact | GuildSetting::MsgChannel | GuildSetting::LevelUpChannel => {
                 return Err(Error::Sushii(
                     "this setting cannot be enabled/disabled".into(),
                 ));
@@ -264,6 +460,14 @@ impl GuildConfig {
             ),
             GuildSetting::MuteDm => (self.mute_dm_text.clone(), Some(self.mute_dm_enabled)),
             GuildSetting::WarnDm => (self.warn_dm_text.clone(), Some(self.warn_dm_enabled)),
+            GuildSetting::Appeal => (
+                self.appeal_channel.map(|id| format!("<#{}>", id as u64)),
+                Some(self.appeal_enabled),
+            ),
+            GuildSetting::Dehoist => (self.dehoist_fallback.clone(), Some(self.dehoist_enabled)),
+            GuildSetting::Snipe => (None, Some(self.snipe_enabled)),
+            GuildSetting::LevelUpMsg => (self.level_up_msg.clone(), Some(self.level_up_enabled)),
+            GuildSetting::LevelUpChannel => (Some(self.level_up_destination()), None),
             GuildSetting::JoinReact => (self.join_react.clone(), None),
             GuildSetting::MsgChannel => {
                 (self.msg_channel.map(|id| format!("<#{}>", id as u64)), None)
@@ -271,7 +475,180 @@ impl GuildConfig {
             GuildSetting::MemberLog => {
                 (self.log_member.map(|id| format!("<#{}>", id as u64)), None)
             }
+            GuildSetting::ServerLog => (
+                self.log_server.map(|id| format!("<#{}>", id as u64)),
+                Some(self.log_server_enabled),
+            ),
+            GuildSetting::VoiceLog => (
+                self.log_voice.map(|id| format!("<#{}>", id as u64)),
+                Some(self.log_voice_enabled),
+            ),
+        }
+    }
+
+    /// Where level up announcements are sent, for displaying
+    pub fn level_up_destination(&self) -> String {
+        if self.level_up_dm {
+            return "DMs".into();
+        }
+
+        match self.level_up_channel {
+            Some(id) => format!("<#{}>", id as u64),
+            None => "Same channel".into(),
+        }
+    }
+
+    /// If messages in a channel, channel category, or from members with any of
+    /// the given roles should not be logged by the message log
+    pub fn msg_log_ignored(
+        &self,
+        channel_id: ChannelId,
+        category_id: Option<ChannelId>,
+        roles: &[RoleId],
+    ) -> bool {
+        // Role channel is always ignored
+        if self.role_channel == Some(channel_id.0 as i64) {
+            return true;
+        }
+
+        if let Some(ref channels) = self.log_msg_ignore_channels {
+            let in_channel = channels.contains(&(channel_id.0 as i64));
+            let in_category = category_id.map_or(false, |id| channels.contains(&(id.0 as i64)));
+
+            if in_channel || in_category {
+                return true;
+            }
+        }
+
+        if let Some(ref ignore_roles) = self.log_msg_ignore_roles {
+            if roles.iter().any(|id| ignore_roles.contains(&(id.0 as i64))) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Min and max XP given per message
+    pub fn xp_range(&self) -> (i64, i64) {
+        let min = i64::from(self.xp_min.unwrap_or(DEFAULT_XP));
+        let max = self.xp_max.map_or(min, i64::from).max(min);
+
+        (min, max)
+    }
+
+    /// Seconds between messages that earn XP
+    pub fn xp_cooldown(&self) -> i64 {
+        self.xp_cooldown_secs.unwrap_or(DEFAULT_XP_COOLDOWN_SECS)
+    }
+
+    /// If messages in a channel, channel category, or from members with any of
+    /// the given roles should not earn XP
+    pub fn xp_ignored(
+        &self,
+        channel_id: ChannelId,
+        category_id: Option<ChannelId>,
+        roles: &[RoleId],
+    ) -> bool {
+        if let Some(ref channels) = self.xp_ignore_channels {
+            let in_channel = channels.contains(&(channel_id.0 as i64));
+            let in_category = category_id.map_or(false, |id| channels.contains(&(id.0 as i64)));
+
+            if in_channel || in_category {
+                return true;
+            }
+        }
+
+        if let Some(ref ignore_roles) = self.xp_ignore_roles {
+            if roles.iter().any(|id| ignore_roles.contains(&(id.0 as i64))) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// XP given per minute in voice channels
+    pub fn voice_xp_rate(&self) -> i64 {
+        i64::from(
+            self.voice_xp_per_minute
+                .unwrap_or(DEFAULT_VOICE_XP_PER_MINUTE),
+        )
+    }
+
+    pub fn xp_channel_multipliers(&self) -> HashMap<i64, f64> {
+        parse_multipliers(&self.xp_channel_multipliers)
+    }
+
+    pub fn xp_role_multipliers(&self) -> HashMap<i64, f64> {
+        parse_multipliers(&self.xp_role_multipliers)
+    }
+
+    /// Sets the multiplier for IDs in a multiplier map, a multiplier of 1
+    /// removes them
+    pub fn set_xp_multipliers(value: &mut Option<serde_json::Value>, ids: &[i64], multiplier: f64) {
+        let mut multipliers = parse_multipliers(value);
+
+        for id in ids {
+            if (multiplier - 1.0).abs() < f64::EPSILON {
+                multipliers.remove(id);
+            } else {
+                multipliers.insert(*id, multiplier);
+            }
         }
+
+        *value = if multipliers.is_empty() {
+            None
+        } else {
+            serde_json::to_value(multipliers).ok()
+        };
+    }
+
+    /// XP multiplier for a message. The channel multiplier is used over the
+    /// category multiplier, and is multiplied by the highest role multiplier
+    /// of the member
+    pub fn xp_multiplier(
+        &self,
+        channel_id: ChannelId,
+        category_id: Option<ChannelId>,
+        roles: &[RoleId],
+    ) -> f64 {
+        let channel_multipliers = self.xp_channel_multipliers();
+        let role_multipliers = self.xp_role_multipliers();
+
+        let channel_multiplier = channel_multipliers
+            .get(&(channel_id.0 as i64))
+            .or_else(|| category_id.and_then(|id| channel_multipliers.get(&(id.0 as i64))))
+            .copied()
+            .unwrap_or(1.0);
+
+        let role_multiplier = roles
+            .iter()
+            .filter_map(|id| role_multipliers.get(&(id.0 as i64)))
+            .copied()
+            .fold(None, |max: Option<f64>, m| {
+                Some(max.map_or(m, |max| max.max(m)))
+            })
+            .unwrap_or(1.0);
+
+        channel_multiplier * role_multiplier
+    }
+
+    /// Random XP earned for a message, None if the message doesn't earn any
+    pub fn xp_for_message(
+        &self,
+        channel_id: ChannelId,
+        category_id: Option<ChannelId>,
+        roles: &[RoleId],
+    ) -> Option<i64> {
+        if self.xp_ignored(channel_id, category_id, roles) {
+            return None;
+        }
+
+        let (min, max) = self.xp_range();
+        let xp = thread_rng().gen_range(min, max + 1) as f64;
+
+        Some((xp * self.xp_multiplier(channel_id, category_id, roles)).round() as i64)
     }
 
     /// Gets a GuildConfig from a given message
@@ -386,6 +763,13 @@ impl GuildConfig {
     }
 }
 
+fn parse_multipliers(value: &Option<serde_json::Value>) -> HashMap<i64, f64> {
+    value
+        .clone()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
 fn fmt_channel(id: Option<i64>) -> Option<String> {
     id.map(|id| format!("<#{}>", id))
 }
@@ -459,6 +843,68 @@ impl fmt::Display for GuildConfig {
             ),
             ("Invite Guard", None, Some(self.invite_guard)),
             ("Max Mentions", Some(fmt_num(self.max_mention)), None),
+            (
+                "Appeal Channel",
+                Some(fmt_channel(self.appeal_channel)),
+                Some(self.appeal_enabled),
+            ),
+            (
+                "Account Age Gate",
+                Some(fmt_duration(self.age_gate_duration)),
+                None,
+            ),
+            (
+                "Account Age Gate DM",
+                Some(self.age_gate_dm_text.clone()),
+                Some(self.age_gate_dm_enabled),
+            ),
+            (
+                "Dehoist Fallback Name",
+                Some(self.dehoist_fallback.clone()),
+                Some(self.dehoist_enabled),
+            ),
+            (
+                "Message Log Retention Count",
+                Some(fmt_num(self.log_msg_retention_count)),
+                None,
+            ),
+            (
+                "Message Log Retention Age",
+                Some(fmt_duration(self.log_msg_retention_secs)),
+                None,
+            ),
+            (
+                "Server Log",
+                Some(fmt_channel(self.log_server)),
+                Some(self.log_server_enabled),
+            ),
+            (
+                "Voice Log",
+                Some(fmt_channel(self.log_voice)),
+                Some(self.log_voice_enabled),
+            ),
+            ("Snipe", None, Some(self.snipe_enabled)),
+            ("Stack Level Roles", None, Some(self.level_role_stack)),
+            (
+                "Level Up Message",
+                Some(self.level_up_msg.clone()),
+                Some(self.level_up_enabled),
+            ),
+            (
+                "Level Up Channel",
+                Some(Some(self.level_up_destination())),
+                None,
+            ),
+            (
+                "XP Cooldown",
+                Some(fmt_duration(self.xp_cooldown_secs)),
+                None,
+            ),
+            (
+                "Voice XP",
+                Some(Some(format!("{} per minute", self.voice_xp_rate()))),
+                Some(self.voice_xp_enabled),
+            ),
             // role_config: Option<serde_json::Value>,
         ];
 
@@ -535,9 +981,122 @@ async fn upsert_config_query(conf: &GuildConfig, pool: &sqlx::PgPool) -> Result<
         conf.mute_dm_enabled,
         conf.max_mention,
         conf.disabled_channels.as_deref(),
+        conf.appeal_channel,
+        conf.appeal_enabled,
+        conf.age_gate_duration,
+        conf.age_gate_quarantine,
+        conf.age_gate_dm_text,
+        conf.age_gate_dm_enabled,
+        conf.age_gate_allowlist.as_deref(),
+        conf.dehoist_enabled,
+        conf.dehoist_fallback,
+        conf.log_msg_retention_count,
+        conf.log_msg_retention_secs,
+        conf.log_server,
+        conf.log_server_enabled,
+        conf.log_voice,
+        conf.log_voice_enabled,
+        conf.log_msg_ignore_channels.as_deref(),
+        conf.log_msg_ignore_roles.as_deref(),
+        conf.snipe_enabled,
+        conf.level_role_stack,
+        conf.level_up_msg.as_deref(),
+        conf.level_up_enabled,
+        conf.level_up_channel,
+        conf.level_up_dm,
+        conf.xp_min,
+        conf.xp_max,
+        conf.xp_cooldown_secs,
+        conf.xp_ignore_channels.as_deref(),
+        conf.xp_ignore_roles.as_deref(),
+        conf.xp_channel_multipliers,
+        conf.xp_role_multipliers,
+        conf.voice_xp_enabled,
+        conf.voice_xp_per_minute,
     )
     .execute(pool)
     .await
     .map(|_| ())
     .map_err(Into::into)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_channels_categories_and_roles() {
+        let mut conf = GuildConfig::new(1);
+        conf.role_channel = Some(2);
+        conf.log_msg_ignore_channels = Some(vec![3, 4]);
+        conf.log_msg_ignore_roles = Some(vec![5]);
+
+        assert!(conf.msg_log_ignored(ChannelId(2), None, &[]));
+        assert!(conf.msg_log_ignored(ChannelId(3), None, &[]));
+        // Channel in an ignored category
+        assert!(conf.msg_log_ignored(ChannelId(10), Some(ChannelId(4)), &[]));
+        assert!(conf.msg_log_ignored(ChannelId(10), None, &[RoleId(6), RoleId(5)]));
+
+        assert!(!conf.msg_log_ignored(ChannelId(10), Some(ChannelId(11)), &[RoleId(6)]));
+    }
+
+    #[test]
+    fn gets_xp_range() {
+        let mut conf = GuildConfig::new(1);
+        assert_eq!(conf.xp_range(), (5, 5));
+
+        conf.xp_min = Some(10);
+        assert_eq!(conf.xp_range(), (10, 10));
+
+        conf.xp_max = Some(20);
+        assert_eq!(conf.xp_range(), (10, 20));
+    }
+
+    #[test]
+    fn gets_xp_multiplier() {
+        let mut conf = GuildConfig::new(1);
+        GuildConfig::set_xp_multipliers(&mut conf.xp_channel_multipliers, &[2], 2.0);
+        GuildConfig::set_xp_multipliers(&mut conf.xp_channel_multipliers, &[3], 0.5);
+        GuildConfig::set_xp_multipliers(&mut conf.xp_role_multipliers, &[4, 5], 1.5);
+        GuildConfig::set_xp_multipliers(&mut conf.xp_role_multipliers, &[5], 3.0);
+
+        assert_eq!(conf.xp_multiplier(ChannelId(10), None, &[]), 1.0);
+        assert_eq!(
+            conf.xp_multiplier(ChannelId(2), Some(ChannelId(3)), &[]),
+            2.0
+        );
+        // Channel in a category with a multiplier
+        assert_eq!(
+            conf.xp_multiplier(ChannelId(10), Some(ChannelId(3)), &[]),
+            0.5
+        );
+        // Highest role multiplier is used
+        assert_eq!(
+            conf.xp_multiplier(ChannelId(2), None, &[RoleId(4), RoleId(5)]),
+            6.0
+        );
+
+        GuildConfig::set_xp_multipliers(&mut conf.xp_role_multipliers, &[4, 5], 1.0);
+        assert!(conf.xp_role_multipliers.is_none());
+    }
+
+    #[test]
+    fn ignores_xp_channels_and_roles() {
+        let mut conf = GuildConfig::new(1);
+        conf.xp_ignore_channels = Some(vec![2]);
+        conf.xp_ignore_roles = Some(vec![3]);
+
+        assert!(conf.xp_for_message(ChannelId(2), None, &[]).is_none());
+        assert!(conf
+            .xp_for_message(ChannelId(10), Some(ChannelId(2)), &[])
+            .is_none());
+        assert!(conf
+            .xp_for_message(ChannelId(10), None, &[RoleId(3)])
+            .is_none());
+
+        assert_eq!(
+            conf.xp_for_message(ChannelId(10), None, &[RoleId(4)]),
+            Some(5)
+        );
+    }
+}
@@ -1,5 +1,31 @@
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use serenity::model::{channel::ReactionType, guild::Role, id::RoleId};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::{self, Write};
+
+/// Conditions a member has to meet to add a role, for either a whole group or a
+/// single role
+#[derive(Deserialize, Default, Serialize, Clone, Debug)]
+pub struct RoleRequirements {
+    /// Role the member needs to have, e.g. a verified role
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_role: Option<u64>,
+
+    /// Names of other groups the member can't have roles in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_groups: Vec<String>,
+
+    /// Minimum level in the guild
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_level: Option<i64>,
+}
+
+impl RoleRequirements {
+    pub fn excludes(&self, group_name: &str) -> bool {
+        self.excluded_groups.iter().any(|g| g == group_name)
+    }
+}
 
 #[derive(Deserialize, Default, Serialize, Clone, Debug)]
 pub struct GuildRole {
@@ -12,6 +38,102 @@ pub struct GuildRole {
     /// Secondary role that is lower in priority than **all** other primary roles
     #[serde(skip_serializing_if = "Option::is_none")]
     pub secondary_id: Option<u64>,
+
+    /// Emoji to react with in role menus, either a unicode emoji or a custom
+    /// emoji like `<:name:id>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+
+    #[serde(flatten)]
+    pub requirements: RoleRequirements,
+}
+
+impl GuildRole {
+    /// Role menu reaction for this role if it has a valid emoji
+    pub fn reaction_type(&self) -> Option<ReactionType> {
+        self.emoji
+            .as_deref()
+            .and_then(|e| ReactionType::try_from(e.trim()).ok())
+            .filter(|r| match r {
+                ReactionType::Unicode(s) => is_unicode_emoji(s),
+                _ => true,
+            })
+    }
+
+    /// If a reaction is this role's emoji
+    pub fn matches_reaction(&self, reaction: &ReactionType) -> bool {
+        match (self.reaction_type(), reaction) {
+            (Some(ReactionType::Custom { id: a, .. }), ReactionType::Custom { id: b, .. }) => {
+                a == *b
+            }
+            // Variation selectors may or may not be included
+            (Some(ReactionType::Unicode(a)), ReactionType::Unicode(b)) => {
+                a.trim_end_matches('\u{fe0f}') == b.trim_end_matches('\u{fe0f}')
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Rough check that a string is a single unicode emoji, since `ReactionType`
+/// accepts any string as unicode
+fn is_unicode_emoji(s: &str) -> bool {
+    let chars: Vec<char> = s.chars().collect();
+
+    match chars.as_slice() {
+        // Keycaps like 1️⃣
+        [base, rest @ ..]
+            if (base.is_ascii_digit() || *base == '#' || *base == '*')
+                && (rest == ['\u{20e3}'] || rest == ['\u{fe0f}', '\u{20e3}']) =>
+        {
+            return true
+        }
+        // Flags are a pair of regional indicators
+        [a, b] if is_regional_indicator(*a) && is_regional_indicator(*b) => return true,
+        _ => {}
+    }
+
+    // Everything else is pictographs joined by zero width joiners, each
+    // optionally followed by variation selectors, skin tones or tags
+    let mut expect_base = true;
+
+    for c in chars {
+        if expect_base {
+            if !is_pictographic(c) {
+                return false;
+            }
+
+            expect_base = false;
+        } else if c == '\u{200d}' {
+            expect_base = true;
+        } else if !is_emoji_modifier(c) {
+            return false;
+        }
+    }
+
+    !expect_base
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&c)
+}
+
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c, '\u{fe0e}' | '\u{fe0f}' | '\u{1f3fb}'..='\u{1f3ff}' | '\u{e0020}'..='\u{e007f}')
+}
+
+/// Code points with emoji presentation, regional indicators are excluded
+/// since they're only valid in pairs
+fn is_pictographic(c: char) -> bool {
+    !is_regional_indicator(c)
+        && matches!(c,
+            '\u{a9}' | '\u{ae}' | '\u{203c}' | '\u{2049}' | '\u{2122}' | '\u{2139}'
+            | '\u{2194}'..='\u{2199}' | '\u{21a9}'..='\u{21aa}' | '\u{231a}'..='\u{231b}'
+            | '\u{2328}' | '\u{23cf}' | '\u{23e9}'..='\u{23f3}' | '\u{23f8}'..='\u{23fa}'
+            | '\u{24c2}' | '\u{25aa}'..='\u{25ab}' | '\u{25b6}' | '\u{25c0}'
+            | '\u{25fb}'..='\u{25fe}' | '\u{2600}'..='\u{27bf}' | '\u{2934}'..='\u{2935}'
+            | '\u{2b05}'..='\u{2b07}' | '\u{2b1b}'..='\u{2b1c}' | '\u{2b50}' | '\u{2b55}'
+            | '\u{3030}' | '\u{303d}' | '\u{3297}' | '\u{3299}' | '\u{1f000}'..='\u{1faff}')
 }
 
 #[derive(Deserialize, Default, Serialize, Clone, Debug)]
@@ -25,6 +147,10 @@ pub struct GuildGroup {
 
     /// List of roles in this group
     pub roles: Vec<GuildRole>,
+
+    /// Requirements for all roles in this group
+    #[serde(flatten)]
+    pub requirements: RoleRequirements,
 }
 
 #[derive(Deserialize, Default, Serialize, Clone, Debug)]
@@ -65,7 +191,179 @@ impl fmt::Display for GuildRoles {
     }
 }
 
+impl GuildGroup {
+    /// If any role in this group has an emoji for role menus
+    pub fn has_menu(&self) -> bool {
+        self.roles.iter().any(|r| r.reaction_type().is_some())
+    }
+
+    /// Role menu description listing each role's emoji, roles named in skip
+    /// are left out
+    pub fn menu_string(&self, skip: &[&str]) -> String {
+        let mut s = String::new();
+
+        for role in &self.roles {
+            if role.reaction_type().is_none() || skip.contains(&role.name.as_str()) {
+                continue;
+            }
+
+            if let Some(emoji) = &role.emoji {
+                let _ = writeln!(s, "{} {}", emoji.trim(), role.name);
+            }
+        }
+
+        if self.limit > 0 {
+            let _ = write!(s, "\nYou can pick up to `{}` roles", self.limit);
+        }
+
+        s
+    }
+}
+
+/// A problem with a role config that would prevent roles from being assigned
+#[derive(Clone, Debug, PartialEq)]
+pub enum RoleConfigIssue {
+    /// Role ID doesn't exist in the guild
+    MissingRole { name: String, id: u64 },
+    /// Role is at or above the bot's highest role
+    RoleTooHigh { name: String, role_name: String },
+    /// Role is managed by an integration or is @everyone
+    ManagedRole { name: String, role_name: String },
+    /// Excluded group name doesn't exist in the config
+    UnknownGroup { name: String, group_name: String },
+    /// Emoji isn't a valid unicode or custom emoji
+    InvalidEmoji { name: String, emoji: String },
+}
+
+impl RoleConfigIssue {
+    /// Errors are always broken, other issues can be fixed without changing
+    /// the config
+    pub fn is_error(&self) -> bool {
+        !matches!(self, RoleConfigIssue::RoleTooHigh { .. })
+    }
+}
+
+impl fmt::Display for RoleConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoleConfigIssue::MissingRole { name, id } => write!(
+                f,
+                "`{}`: Role ID `{}` doesn't exist, use `roles listids` to find the correct ID",
+                name, id
+            ),
+            RoleConfigIssue::RoleTooHigh { name, role_name } => write!(
+                f,
+                "`{}`: Role `{}` is not below my highest role, move my role above it",
+                name, role_name
+            ),
+            RoleConfigIssue::ManagedRole { name, role_name } => write!(
+                f,
+                "`{}`: Role `{}` can't be assigned to members, use a different role",
+                name, role_name
+            ),
+            RoleConfigIssue::UnknownGroup { name, group_name } => write!(
+                f,
+                "`{}`: Excluded group `{}` doesn't exist, check the group name",
+                name, group_name
+            ),
+            RoleConfigIssue::InvalidEmoji { name, emoji } => write!(
+                f,
+                "`{}`: Emoji `{}` is invalid, use a unicode emoji or a custom emoji like `<:name:id>`",
+                name, emoji
+            ),
+        }
+    }
+}
+
 impl GuildRoles {
+    /// Checks that all roles exist and can be assigned by the bot, given the
+    /// guild's roles and the position of the bot's highest role
+    pub fn validate(
+        &self,
+        guild_roles: &HashMap<RoleId, Role>,
+        bot_position: i64,
+    ) -> Vec<RoleConfigIssue> {
+        let mut issues = Vec::new();
+
+        let mut check_role = |name: &str, id: u64| match guild_roles.get(&RoleId(id)) {
+            None => issues.push(RoleConfigIssue::MissingRole {
+                name: name.to_string(),
+                id,
+            }),
+            // @everyone has the same ID as the guild
+            Some(role) if role.managed || role.id.0 == role.guild_id.0 => {
+                issues.push(RoleConfigIssue::ManagedRole {
+                    name: name.to_string(),
+                    role_name: role.name.clone(),
+                })
+            }
+            Some(role) if role.position >= bot_position => {
+                issues.push(RoleConfigIssue::RoleTooHigh {
+                    name: name.to_string(),
+                    role_name: role.name.clone(),
+                })
+            }
+            _ => {}
+        };
+
+        for group in &self.groups {
+            for role in &group.roles {
+                check_role(&role.name, role.primary_id);
+
+                if let Some(id) = role.secondary_id {
+                    check_role(&role.name, id);
+                }
+            }
+        }
+
+        for group in &self.groups {
+            let requirements = std::iter::once((&group.name, &group.requirements))
+                .chain(group.roles.iter().map(|r| (&r.name, &r.requirements)));
+
+            for (name, req) in requirements {
+                if let Some(id) = req.required_role {
+                    if !guild_roles.contains_key(&RoleId(id)) {
+                        issues.push(RoleConfigIssue::MissingRole {
+                            name: name.clone(),
+                            id,
+                        });
+                    }
+                }
+
+                for group_name in &req.excluded_groups {
+                    if !self.groups.iter().any(|g| &g.name == group_name) {
+                        issues.push(RoleConfigIssue::UnknownGroup {
+                            name: name.clone(),
+                            group_name: group_name.clone(),
+                        });
+                    }
+                }
+            }
+
+            for role in &group.roles {
+                if let Some(emoji) = &role.emoji {
+                    if role.reaction_type().is_none() {
+                        issues.push(RoleConfigIssue::InvalidEmoji {
+                            name: role.name.clone(),
+                            emoji: emoji.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// If any group or role has a minimum level, to skip fetching levels
+    /// when it isn't needed
+    pub fn uses_levels(&self) -> bool {
+        self.groups.iter().any(|g| {
+            g.requirements.min_level.is_some()
+                || g.roles.iter().any(|r| r.requirements.min_level.is_some())
+        })
+    }
+
     pub fn get_examples_string(&self) -> String {
         let roles: Vec<&str> = self
             .groups
@@ -90,3 +388,205 @@ impl GuildRoles {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::model::id::EmojiId;
+
+    const GUILD_ID: u64 = 100;
+
+    fn guild_role(id: u64, position: i64, managed: bool) -> (RoleId, Role) {
+        let role = serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "guild_id": GUILD_ID.to_string(),
+            "name": format!("role {}", id),
+            "color": 0,
+            "hoist": false,
+            "managed": managed,
+            "mentionable": false,
+            "permissions": "0",
+            "position": position,
+        }))
+        .unwrap();
+
+        (RoleId(id), role)
+    }
+
+    fn config_role(name: &str, primary_id: u64) -> GuildRole {
+        GuildRole {
+            name: name.into(),
+            primary_id,
+            ..Default::default()
+        }
+    }
+
+    fn config(roles: Vec<GuildRole>) -> GuildRoles {
+        GuildRoles {
+            groups: vec![GuildGroup {
+                name: "colours".into(),
+                roles,
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn matches_reactions() {
+        let role = |emoji: &str| GuildRole {
+            emoji: Some(emoji.into()),
+            ..Default::default()
+        };
+
+        assert!(role("❤️").matches_reaction(&ReactionType::Unicode("❤".into())));
+        assert!(!role("❤️").matches_reaction(&ReactionType::Unicode("💙".into())));
+        assert!(
+            role("<:sushii:123>").matches_reaction(&ReactionType::Custom {
+                animated: false,
+                id: EmojiId(123),
+                name: Some("renamed".into()),
+            })
+        );
+        assert!(!GuildRole::default().matches_reaction(&ReactionType::Unicode("❤".into())));
+    }
+
+    #[test]
+    fn validates_unicode_emojis() {
+        let role = |emoji: &str| GuildRole {
+            emoji: Some(emoji.into()),
+            ..Default::default()
+        };
+
+        for emoji in &["❤️", "❤", "👍🏽", "🇺🇸", "👨‍👩‍👧", "1️⃣", "🏴󠁧󠁢󠁳󠁣󠁴󠁿", "<:sushii:123>"]
+        {
+            assert!(role(emoji).reaction_type().is_some(), "{}", emoji);
+        }
+
+        for emoji in &["banana", "a", "1", "❤️x", "👍👍", "🇺", ""] {
+            assert!(role(emoji).reaction_type().is_none(), "{}", emoji);
+        }
+    }
+
+    #[test]
+    fn validates_valid_config() {
+        let guild_roles = vec![guild_role(1, 1, false)].into_iter().collect();
+        let mut role = config_role("red", 1);
+        role.emoji = Some("🔴".into());
+
+        assert!(config(vec![role]).validate(&guild_roles, 5).is_empty());
+    }
+
+    #[test]
+    fn validates_missing_roles() {
+        let guild_roles = vec![guild_role(1, 1, false)].into_iter().collect();
+        let mut role = config_role("red", 1);
+        role.secondary_id = Some(2);
+        role.requirements.required_role = Some(3);
+
+        assert_eq!(
+            config(vec![role]).validate(&guild_roles, 5),
+            vec![
+                RoleConfigIssue::MissingRole {
+                    name: "red".into(),
+                    id: 2,
+                },
+                RoleConfigIssue::MissingRole {
+                    name: "red".into(),
+                    id: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validates_managed_roles() {
+        let guild_roles = vec![guild_role(1, 1, true), guild_role(GUILD_ID, 0, false)]
+            .into_iter()
+            .collect();
+
+        let issues = config(vec![
+            config_role("bot", 1),
+            config_role("everyone", GUILD_ID),
+        ])
+        .validate(&guild_roles, 5);
+
+        assert_eq!(
+            issues,
+            vec![
+                RoleConfigIssue::ManagedRole {
+                    name: "bot".into(),
+                    role_name: "role 1".into(),
+                },
+                RoleConfigIssue::ManagedRole {
+                    name: "everyone".into(),
+                    role_name: format!("role {}", GUILD_ID),
+                },
+            ]
+        );
+        assert!(issues.iter().all(RoleConfigIssue::is_error));
+    }
+
+    #[test]
+    fn validates_roles_too_high() {
+        let guild_roles = vec![guild_role(1, 5, false), guild_role(2, 6, false)]
+            .into_iter()
+            .collect();
+
+        let issues =
+            config(vec![config_role("red", 1), config_role("blue", 2)]).validate(&guild_roles, 5);
+
+        assert_eq!(
+            issues,
+            vec![
+                RoleConfigIssue::RoleTooHigh {
+                    name: "red".into(),
+                    role_name: "role 1".into(),
+                },
+                RoleConfigIssue::RoleTooHigh {
+                    name: "blue".into(),
+                    role_name: "role 2".into(),
+                },
+            ]
+        );
+        assert!(!issues.iter().any(RoleConfigIssue::is_error));
+    }
+
+    #[test]
+    fn validates_unknown_groups() {
+        let guild_roles = vec![guild_role(1, 1, false)].into_iter().collect();
+        let mut role = config_role("red", 1);
+        role.requirements.excluded_groups = vec!["colours".into(), "pronouns".into()];
+
+        let mut roles = config(vec![role]);
+        roles.groups[0].requirements.excluded_groups = vec!["regions".into()];
+
+        assert_eq!(
+            roles.validate(&guild_roles, 5),
+            vec![
+                RoleConfigIssue::UnknownGroup {
+                    name: "colours".into(),
+                    group_name: "regions".into(),
+                },
+                RoleConfigIssue::UnknownGroup {
+                    name: "red".into(),
+                    group_name: "pronouns".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validates_invalid_emojis() {
+        let guild_roles = vec![guild_role(1, 1, false)].into_iter().collect();
+        let mut role = config_role("red", 1);
+        role.emoji = Some("banana".into());
+
+        assert_eq!(
+            config(vec![role]).validate(&guild_roles, 5),
+            vec![RoleConfigIssue::InvalidEmoji {
+                name: "red".into(),
+                emoji: "banana".into(),
+            }]
+        );
+    }
+}
@@ -11,8 +11,15 @@ pub enum GuildSetting {
     MsgLog,
     ModLog,
     MemberLog,
+    ServerLog,
+    VoiceLog,
     MuteDm,
     WarnDm,
+    Appeal,
+    Dehoist,
+    Snipe,
+    LevelUpMsg,
+    LevelUpChannel,
 }
 
 impl fmt::Display for GuildSetting {
@@ -28,8 +35,15 @@ impl fmt::Display for GuildSetting {
                 GuildSetting::MsgLog => "message log",
                 GuildSetting::ModLog => "mod log",
                 GuildSetting::MemberLog => "member log",
+                GuildSetting::ServerLog => "server log",
+                GuildSetting::VoiceLog => "voice log",
                 GuildSetting::MuteDm => "mute DMs",
                 GuildSetting::WarnDm => "warn DMs",
+                GuildSetting::Appeal => "appeals",
+                GuildSetting::Dehoist => "dehoist fallback name",
+                GuildSetting::Snipe => "snipe",
+                GuildSetting::LevelUpMsg => "level up message",
+                GuildSetting::LevelUpChannel => "level up channel",
             }
         )
     }
@@ -47,8 +61,15 @@ impl FromStr for GuildSetting {
             "msglog" => Self::MsgLog,
             "modlog" => Self::ModLog,
             "memberlog" => Self::MemberLog,
+            "serverlog" => Self::ServerLog,
+            "voicelog" => Self::VoiceLog,
             "mutedm" => Self::MuteDm,
             "warndm" => Self::WarnDm,
+            "appeal" => Self::Appeal,
+            "dehoist" => Self::Dehoist,
+            "snipe" => Self::Snipe,
+            "levelupmsg" => Self::LevelUpMsg,
+            "levelupchannel" => Self::LevelUpChannel,
             _ => return Err(Error::Sushii("Invalid guild setting".into())),
         };
 
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::keys::DbPool;
+
+/// A role given to members when they reach a level
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug, PartialEq)]
+pub struct LevelRole {
+    pub guild_id: i64,
+    pub role_id: i64,
+    pub level: i64,
+}
+
+impl LevelRole {
+    pub fn new(guild_id: GuildId, role_id: RoleId, level: i64) -> Self {
+        LevelRole {
+            guild_id: guild_id.0 as i64,
+            role_id: role_id.0 as i64,
+            level,
+        }
+    }
+
+    /// Gets roles that should be added and removed for a member at a level.
+    /// Rewards above the level are never removed so roles given manually are
+    /// kept, lower rewards are only removed if rewards don't stack
+    pub fn rewards_for_level(
+        rewards: &[LevelRole],
+        level: i64,
+        stack: bool,
+    ) -> (Vec<RoleId>, Vec<RoleId>) {
+        let earned = rewards.iter().filter(|r| r.level <= level);
+
+        let highest_level = match earned.clone().map(|r| r.level).max() {
+            Some(l) => l,
+            None => return (Vec::new(), Vec::new()),
+        };
+
+        let (add, remove): (Vec<&LevelRole>, Vec<&LevelRole>) =
+            earned.partition(|r| stack || r.level == highest_level);
+
+        let to_role_ids =
+            |roles: Vec<&LevelRole>| roles.iter().map(|r| RoleId(r.role_id as u64)).collect();
+
+        (to_role_ids(add), to_role_ids(remove))
+    }
+
+    /// Gets all level roles in a guild, lowest level first
+    pub async fn from_guild(ctx: &Context, guild_id: GuildId) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            LevelRole,
+            r#"
+                  SELECT *
+                    FROM app_public.level_roles
+                   WHERE guild_id = $1
+                ORDER BY level ASC
+            "#,
+            guild_id.0 as i64,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    pub async fn save(&self, ctx: &Context) -> Result<Self> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            LevelRole,
+            r#"
+                INSERT INTO app_public.level_roles (guild_id, role_id, level)
+                     VALUES ($1, $2, $3)
+                ON CONFLICT (guild_id, role_id)
+                  DO UPDATE
+                        SET level = $3
+                  RETURNING *
+            "#,
+            self.guild_id,
+            self.role_id,
+            self.level,
+        )
+        .fetch_one(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Deletes a level role, returns the deleted level role if it existed
+    pub async fn delete(ctx: &Context, guild_id: GuildId, role_id: RoleId) -> Result<Option<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            LevelRole,
+            r#"
+                DELETE FROM app_public.level_roles
+                      WHERE guild_id = $1
+                            AND role_id = $2
+                  RETURNING *
+            "#,
+            guild_id.0 as i64,
+            role_id.0 as i64,
+        )
+        .fetch_optional(&pool)
+        .await
+        .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewards() -> Vec<LevelRole> {
+        vec![
+            LevelRole::new(GuildId(1), RoleId(5), 5),
+            LevelRole::new(GuildId(1), RoleId(10), 10),
+            LevelRole::new(GuildId(1), RoleId(11), 10),
+            LevelRole::new(GuildId(1), RoleId(20), 20),
+        ]
+    }
+
+    #[test]
+    fn gets_stacked_rewards() {
+        let (add, remove) = LevelRole::rewards_for_level(&rewards(), 12, true);
+
+        assert_eq!(add, vec![RoleId(5), RoleId(10), RoleId(11)]);
+        assert!(remove.is_empty());
+    }
+
+    #[test]
+    fn gets_replaced_rewards() {
+        let (add, remove) = LevelRole::rewards_for_level(&rewards(), 12, false);
+
+        assert_eq!(add, vec![RoleId(10), RoleId(11)]);
+        assert_eq!(remove, vec![RoleId(5)]);
+    }
+
+    #[test]
+    fn gets_no_rewards_below_lowest() {
+        let (add, remove) = LevelRole::rewards_for_level(&rewards(), 4, false);
+
+        assert!(add.is_empty());
+        assert!(remove.is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::naive::NaiveDateTime;
+use chrono::{naive::NaiveDateTime, offset::Utc, Duration};
 use serde::{Deserialize, Serialize};
 use serenity::model::prelude::*;
 use serenity::prelude::*;
@@ -16,9 +16,22 @@ pub struct SavedMessage {
     pub created: NaiveDateTime,
     pub content: String,
     pub msg: Json<Message>,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub edited_at: Option<NaiveDateTime>,
+    /// Content before the latest edit
+    pub prev_content: Option<String>,
 }
 
 impl SavedMessage {
+    /// Saved messages per channel if a guild doesn't set a retention count
+    pub const DEFAULT_RETENTION_COUNT: i32 = 100;
+
+    /// Max retention count guilds can set
+    pub const MAX_RETENTION_COUNT: i32 = 1000;
+
+    /// Max retention age in seconds guilds can set, 1 year
+    pub const MAX_RETENTION_SECS: i64 = 365 * 24 * 60 * 60;
+
     pub fn from_msg(msg: &Message) -> Option<Self> {
         Some(Self {
             message_id: i64::from(msg.id),
@@ -28,6 +41,9 @@ impl SavedMessage {
             created: msg.timestamp.naive_utc(),
             content: msg.content.clone(),
             msg: Json(msg.clone()),
+            deleted_at: None,
+            edited_at: None,
+            prev_content: None,
         })
     }
 
@@ -44,8 +60,11 @@ impl SavedMessage {
                             guild_id,
                             created,
                             content,
-                            msg)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                            msg,
+                            deleted_at,
+                            edited_at,
+                            prev_content)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             ON CONFLICT (message_id)
               DO UPDATE
                     SET author_id = $2,
@@ -53,7 +72,10 @@ impl SavedMessage {
                         guild_id = $4,
                         created = $5,
                         content = $6,
-                        msg = $7
+                        msg = $7,
+                        deleted_at = $8,
+                        edited_at = $9,
+                        prev_content = $10
             "#,
             self.message_id,
             self.author_id,
@@ -62,6 +84,9 @@ impl SavedMessage {
             self.created,
             self.content,
             self.msg as _, // Converts to serde_json::Value I think
+            self.deleted_at,
+            self.edited_at,
+            self.prev_content,
         )
         .execute(&pool)
         .await?;
@@ -82,7 +107,10 @@ impl SavedMessage {
                        guild_id,
                        created,
                        content,
-                       msg as "msg: Json<Message>"
+                       msg as "msg: Json<Message>",
+                       deleted_at,
+                       edited_at,
+                       prev_content
                   FROM app_public.messages
                  WHERE message_id = $1
             "#,
@@ -93,26 +121,383 @@ impl SavedMessage {
         .map_err(Into::into)
     }
 
-    pub async fn prune_old(ctx: &Context, channel_id: ChannelId) -> Result<()> {
+    /// Marks messages as deleted
+    pub async fn mark_deleted(ctx: &Context, message_ids: &[MessageId]) -> Result<()> {
         let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
 
-        // Only keep last 100 messages
+        let ids: Vec<i64> = message_ids.iter().map(|id| i64::from(*id)).collect();
+
         sqlx::query!(
+            r#"
+                UPDATE app_public.messages
+                   SET deleted_at = $2
+                 WHERE message_id = ANY($1)
+            "#,
+            &ids,
+            Utc::now().naive_utc(),
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Gets the most recently deleted messages in a channel deleted after a
+    /// given time, newest first
+    pub async fn recently_deleted(
+        ctx: &Context,
+        channel_id: ChannelId,
+        since: NaiveDateTime,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            SavedMessage,
+            r#"
+                  SELECT message_id,
+                         author_id,
+                         channel_id,
+                         guild_id,
+                         created,
+                         content,
+                         msg as "msg: Json<Message>",
+                         deleted_at,
+                         edited_at,
+                         prev_content
+                    FROM app_public.messages
+                   WHERE channel_id = $1
+                         AND deleted_at > $2
+                ORDER BY deleted_at DESC
+                   LIMIT $3
+            "#,
+            i64::from(channel_id),
+            since,
+            limit,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Gets the most recently edited messages in a channel edited after a
+    /// given time, newest first
+    pub async fn recently_edited(
+        ctx: &Context,
+        channel_id: ChannelId,
+        since: NaiveDateTime,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            SavedMessage,
+            r#"
+                  SELECT message_id,
+                         author_id,
+                         channel_id,
+                         guild_id,
+                         created,
+                         content,
+                         msg as "msg: Json<Message>",
+                         deleted_at,
+                         edited_at,
+                         prev_content
+                    FROM app_public.messages
+                   WHERE channel_id = $1
+                         AND edited_at > $2
+                         AND deleted_at IS NULL
+                ORDER BY edited_at DESC
+                   LIMIT $3
+            "#,
+            i64::from(channel_id),
+            since,
+            limit,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Fetches saved messages from a list of IDs, oldest first. Messages that
+    /// were not saved are skipped
+    pub async fn from_ids(ctx: &Context, message_ids: &[MessageId]) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        let ids: Vec<i64> = message_ids.iter().map(|id| i64::from(*id)).collect();
+
+        sqlx::query_as!(
+            SavedMessage,
+            r#"
+                SELECT message_id,
+                       author_id,
+                       channel_id,
+                       guild_id,
+                       created,
+                       content,
+                       msg as "msg: Json<Message>",
+                       deleted_at,
+                       edited_at,
+                       prev_content
+                  FROM app_public.messages
+                 WHERE message_id = ANY($1)
+              ORDER BY created ASC
+            "#,
+            &ids,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Deletes saved messages in a guild past the retention count per channel
+    /// or older than the retention age. Returns the number of deleted messages
+    pub async fn prune_guild(
+        ctx: &Context,
+        guild_id: GuildId,
+        retention_count: Option<i32>,
+        retention_secs: Option<i64>,
+    ) -> Result<u64> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        let mut deleted = 0;
+
+        if let Some(secs) = retention_secs {
+            // Duration::seconds panics on huge values, so go through std
+            let cutoff =
+                match Duration::from_std(std::time::Duration::from_secs(secs.max(0) as u64))
+                    .ok()
+                    .and_then(|d| Utc::now().naive_utc().checked_sub_signed(d))
+                {
+                    Some(c) => c,
+                    None => {
+                        tracing::warn!(
+                            ?guild_id,
+                            secs,
+                            "Message retention age is too long, skipping"
+                        );
+                        return Ok(0);
+                    }
+                };
+
+            deleted += sqlx::query!(
+                r#"
+                    DELETE FROM app_public.messages
+                          WHERE guild_id = $1
+                                AND created < $2
+                "#,
+                i64::from(guild_id),
+                cutoff,
+            )
+            .execute(&pool)
+            .await?
+            .rows_affected();
+        }
+
+        // Each channel's newest messages are found with the channel index
+        // instead of numbering every message
+        deleted += sqlx::query!(
             r#"
                 DELETE FROM app_public.messages
-                      WHERE channel_id = $1
-                            AND ctid NOT IN (
-                                  SELECT ctid
-                                    FROM app_public.messages
-                                ORDER BY created DESC
-                                   LIMIT 100
+                      WHERE message_id IN (
+                                SELECT old.message_id
+                                  FROM (
+                                         SELECT DISTINCT channel_id
+                                           FROM app_public.messages
+                                          WHERE guild_id = $1
+                                       ) c
+                            CROSS JOIN LATERAL (
+                                         SELECT message_id
+                                           FROM app_public.messages
+                                          WHERE channel_id = c.channel_id
+                                       ORDER BY created DESC
+                                         OFFSET $2
+                                       ) old
                             )
             "#,
-            i64::from(channel_id),
+            i64::from(guild_id),
+            i64::from(retention_count.unwrap_or(Self::DEFAULT_RETENTION_COUNT)),
         )
         .execute(&pool)
+        .await?
+        .rows_affected();
+
+        Ok(deleted)
+    }
+}
+
+/// Number and size of saved messages in a guild
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct MessageStorage {
+    pub guild_id: i64,
+    pub channel_count: i64,
+    pub message_count: i64,
+    /// Total size of stored rows in bytes
+    pub size: i64,
+}
+
+impl MessageStorage {
+    /// Gets message storage for all guilds, largest first
+    pub async fn all(ctx: &Context) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            MessageStorage,
+            r#"
+                  SELECT guild_id,
+                         COUNT(DISTINCT channel_id) as "channel_count!",
+                         COUNT(*) as "message_count!",
+                         SUM(pg_column_size(messages.*))::BIGINT as "size!"
+                    FROM app_public.messages
+                GROUP BY guild_id
+                ORDER BY "size!" DESC
+            "#,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+}
+
+/// Limits how long a message search can run since regexes are given by
+/// moderators. SET doesn't support parameters
+const SET_SEARCH_TIMEOUT: &str = "SET LOCAL statement_timeout = '5s'";
+
+/// Filters for searching saved messages, all given filters must match
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct MessageSearch {
+    pub author_id: Option<i64>,
+    pub channel_id: Option<i64>,
+    /// Case insensitive substring
+    pub content: Option<String>,
+    /// Case insensitive POSIX regex
+    pub regex: Option<String>,
+    pub after: Option<NaiveDateTime>,
+    pub before: Option<NaiveDateTime>,
+}
+
+impl MessageSearch {
+    /// Content as a LIKE pattern with wildcards escaped
+    fn like_pattern(&self) -> Option<String> {
+        self.content.as_ref().map(|c| {
+            format!(
+                "%{}%",
+                c.replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            )
+        })
+    }
+
+    /// Gets the number of saved messages in a guild matching the search, only
+    /// messages in the given channels are searched
+    pub async fn count(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        channel_ids: &[i64],
+    ) -> Result<i64> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        let mut tx = pool.begin().await?;
+        set_search_timeout(&mut tx).await?;
+
+        let count = sqlx::query!(
+            r#"
+                SELECT COUNT(*) as "count!"
+                  FROM app_public.messages
+                 WHERE guild_id = $1
+                       AND ($2::BIGINT IS NULL OR author_id = $2)
+                       AND ($3::BIGINT IS NULL OR channel_id = $3)
+                       AND ($4::TEXT IS NULL OR content ILIKE $4)
+                       AND ($5::TEXT IS NULL OR content ~* $5)
+                       AND ($6::TIMESTAMP IS NULL OR created > $6)
+                       AND ($7::TIMESTAMP IS NULL OR created < $7)
+                       AND channel_id = ANY($8)
+            "#,
+            i64::from(guild_id),
+            self.author_id,
+            self.channel_id,
+            self.like_pattern(),
+            self.regex,
+            self.after,
+            self.before,
+            channel_ids,
+        )
+        .fetch_one(&mut tx)
+        .await?
+        .count;
+
+        tx.commit().await?;
+
+        Ok(count)
+    }
+
+    /// Gets a page of saved messages matching the search in the given
+    /// channels, newest first. The offset is the last message ID of the
+    /// previous page
+    pub async fn get_page(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        channel_ids: &[i64],
+        count: i64,
+        offset: Option<i64>,
+    ) -> Result<Vec<SavedMessage>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        let mut tx = pool.begin().await?;
+        set_search_timeout(&mut tx).await?;
+
+        let msgs = sqlx::query_as!(
+            SavedMessage,
+            r#"
+                  SELECT message_id,
+                         author_id,
+                         channel_id,
+                         guild_id,
+                         created,
+                         content,
+                         msg as "msg: Json<Message>",
+                         deleted_at,
+                         edited_at,
+                         prev_content
+                    FROM app_public.messages
+                   WHERE guild_id = $1
+                         AND ($2::BIGINT IS NULL OR author_id = $2)
+                         AND ($3::BIGINT IS NULL OR channel_id = $3)
+                         AND ($4::TEXT IS NULL OR content ILIKE $4)
+                         AND ($5::TEXT IS NULL OR content ~* $5)
+                         AND ($6::TIMESTAMP IS NULL OR created > $6)
+                         AND ($7::TIMESTAMP IS NULL OR created < $7)
+                         AND ($8::BIGINT IS NULL OR message_id < $8)
+                         AND channel_id = ANY($10)
+                ORDER BY message_id DESC
+                   LIMIT $9
+            "#,
+            i64::from(guild_id),
+            self.author_id,
+            self.channel_id,
+            self.like_pattern(),
+            self.regex,
+            self.after,
+            self.before,
+            offset,
+            count,
+            channel_ids,
+        )
+        .fetch_all(&mut tx)
         .await?;
 
-        Ok(())
+        tx.commit().await?;
+
+        Ok(msgs)
     }
 }
+
+/// Limits how long queries in a search transaction can run
+async fn set_search_timeout(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<()> {
+    sqlx::query(SET_SEARCH_TIMEOUT).execute(tx).await?;
+
+    Ok(())
+}
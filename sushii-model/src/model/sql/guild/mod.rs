@@ -2,5 +2,8 @@ pub mod cached_guild;
 pub mod guild_config;
 pub mod guild_roles;
 pub mod guild_setting;
+pub mod level_role;
 pub mod messages;
+pub mod role_menu;
 pub mod tags;
+pub mod xp_season;
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::keys::DbPool;
+
+/// A reaction role menu message for a group in a guild's role config
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct RoleMenu {
+    pub message_id: i64,
+    pub guild_id: i64,
+    pub channel_id: i64,
+    pub group_name: String,
+}
+
+impl RoleMenu {
+    pub fn new(message: &Message, guild_id: GuildId, group_name: &str) -> Self {
+        RoleMenu {
+            message_id: message.id.0 as i64,
+            guild_id: guild_id.0 as i64,
+            channel_id: message.channel_id.0 as i64,
+            group_name: group_name.to_string(),
+        }
+    }
+
+    /// Gets the role menu of a message, if it is one
+    pub async fn from_message_id(ctx: &Context, message_id: MessageId) -> Result<Option<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            RoleMenu,
+            r#"
+                SELECT *
+                  FROM app_public.role_menus
+                 WHERE message_id = $1
+            "#,
+            message_id.0 as i64,
+        )
+        .fetch_optional(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    pub async fn save(&self, ctx: &Context) -> Result<Self> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            RoleMenu,
+            r#"
+                INSERT INTO app_public.role_menus (message_id, guild_id, channel_id, group_name)
+                     VALUES ($1, $2, $3, $4)
+                ON CONFLICT (message_id)
+                  DO UPDATE
+                        SET group_name = $4
+                  RETURNING *
+            "#,
+            self.message_id,
+            self.guild_id,
+            self.channel_id,
+            self.group_name,
+        )
+        .fetch_one(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Deletes all role menus in a guild, returns the deleted menus
+    pub async fn delete_guild(ctx: &Context, guild_id: GuildId) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            RoleMenu,
+            r#"
+                DELETE FROM app_public.role_menus
+                      WHERE guild_id = $1
+                  RETURNING *
+            "#,
+            guild_id.0 as i64,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+}
@@ -0,0 +1,248 @@
+use chrono::naive::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "graphql"))]
+use crate::keys::DbPool;
+#[cfg(not(feature = "graphql"))]
+use crate::model::sql::XpAdjustment;
+#[cfg(not(feature = "graphql"))]
+use chrono::offset::Utc;
+#[cfg(not(feature = "graphql"))]
+use serenity::{model::prelude::*, prelude::*};
+
+#[cfg(feature = "graphql")]
+use juniper::{graphql_object, GraphQLObject};
+
+#[cfg(feature = "graphql")]
+use crate::{
+    cursor::decode_cursor,
+    model::{juniper::Context, sql::CachedUser},
+};
+
+use crate::error::Result;
+use crate::model::BigInt;
+
+/// An ended XP season of a guild
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+#[cfg_attr(
+    feature = "graphql",
+    graphql(description = "An ended XP season of a guild"),
+    derive(GraphQLObject)
+)]
+pub struct XpSeason {
+    pub id: BigInt,
+    pub guild_id: BigInt,
+    pub name: String,
+    pub ended_at: NaiveDateTime,
+}
+
+/// A user's standing on the leaderboard when a season ended
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct XpSeasonStanding {
+    pub season_id: BigInt,
+    pub user_id: BigInt,
+    pub rank: BigInt,
+    pub xp: BigInt,
+}
+
+impl XpSeason {
+    /// Ends the current season, saving the leaderboard and removing a
+    /// percentage of every member's XP, 100 resets all XP. Returns the new
+    /// season and the number of members saved
+    #[cfg(not(feature = "graphql"))]
+    pub async fn end(
+        ctx: &Context,
+        guild_id: GuildId,
+        moderator_id: UserId,
+        name: &str,
+        decay_percent: i64,
+    ) -> Result<(Self, u64)> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+        let mut tx = pool.begin().await?;
+
+        let season = sqlx::query_as!(
+            XpSeason,
+            r#"
+                INSERT INTO app_public.xp_seasons (guild_id, name, ended_at)
+                     VALUES ($1, $2, $3)
+                  RETURNING id as "id: BigInt",
+                            guild_id as "guild_id: BigInt",
+                            name,
+                            ended_at
+            "#,
+            i64::from(guild_id),
+            name,
+            Utc::now().naive_utc(),
+        )
+        .fetch_one(&mut tx)
+        .await?;
+
+        // Same order as the all time leaderboard
+        let user_count = sqlx::query!(
+            r#"
+                INSERT INTO app_public.xp_season_standings (season_id, user_id, rank, xp)
+                     SELECT $1,
+                            user_id,
+                            ROW_NUMBER() OVER (ORDER BY msg_all_time DESC, user_id DESC),
+                            msg_all_time
+                       FROM app_public.user_levels
+                      WHERE guild_id = $2
+                        AND msg_all_time > 0
+            "#,
+            season.id.0,
+            i64::from(guild_id),
+        )
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+
+        if decay_percent >= 100 {
+            sqlx::query!(
+                r#"
+                    DELETE FROM app_public.user_levels
+                          WHERE guild_id = $1
+                "#,
+                i64::from(guild_id),
+            )
+            .execute(&mut tx)
+            .await?;
+        } else {
+            // Interval XP can't be more than the decayed all time XP
+            sqlx::query!(
+                r#"
+                    UPDATE app_public.user_levels
+                       SET msg_all_time = msg_all_time * (100 - $2::BIGINT) / 100,
+                           msg_month = LEAST(msg_month, msg_all_time * (100 - $2::BIGINT) / 100),
+                           msg_week = LEAST(msg_week, msg_all_time * (100 - $2::BIGINT) / 100),
+                           msg_day = LEAST(msg_day, msg_all_time * (100 - $2::BIGINT) / 100),
+                           voice_xp = voice_xp * (100 - $2::BIGINT) / 100
+                     WHERE guild_id = $1
+                "#,
+                i64::from(guild_id),
+                decay_percent,
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        XpAdjustment::new(guild_id, moderator_id, "season")
+            .save_with(&mut tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok((season, user_count))
+    }
+
+    #[cfg(feature = "graphql")]
+    pub async fn from_guild(pool: &sqlx::PgPool, guild_id: BigInt) -> Result<Vec<Self>> {
+        from_guild_query(pool, guild_id.0).await
+    }
+
+    /// Gets all ended seasons in a guild, newest first
+    #[cfg(not(feature = "graphql"))]
+    pub async fn from_guild(ctx: &Context, guild_id: GuildId) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        from_guild_query(&pool, i64::from(guild_id)).await
+    }
+}
+
+impl XpSeasonStanding {
+    /// Gets a season's leaderboard, highest XP first
+    #[cfg(feature = "graphql")]
+    pub async fn season_top(
+        pool: &sqlx::PgPool,
+        season_id: BigInt,
+        first: BigInt,
+        after: Option<String>,
+    ) -> Result<(BigInt, Vec<Self>)> {
+        let after = if let Some(s) = after {
+            Some(decode_cursor(&s)?)
+        } else {
+            None
+        };
+
+        let total_count = sqlx::query!(
+            r#"
+                SELECT COUNT(*) as "total!: BigInt"
+                  FROM app_public.xp_season_standings
+                 WHERE season_id = $1
+            "#,
+            season_id.0,
+        )
+        .fetch_one(pool)
+        .await?
+        .total;
+
+        let standings = sqlx::query_as!(
+            XpSeasonStanding,
+            r#"
+                  SELECT season_id as "season_id: BigInt",
+                         user_id as "user_id: BigInt",
+                         rank as "rank: BigInt",
+                         xp as "xp: BigInt"
+                    FROM app_public.xp_season_standings
+                   WHERE season_id = $1
+                     AND ((xp, user_id) < ($2, $3) OR $2 IS NULL OR $3 IS NULL)
+                ORDER BY xp DESC,
+                         user_id DESC
+                   LIMIT $4
+            "#,
+            season_id.0,
+            after.map(|a| a.0), // xp
+            after.map(|a| a.1), // user id
+            first.0,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok((total_count, standings))
+    }
+}
+
+#[cfg(feature = "graphql")]
+#[graphql_object(
+    context = Context,
+    description = "A user's standing on the leaderboard when a season ended"
+)]
+impl XpSeasonStanding {
+    fn season_id(&self) -> BigInt {
+        self.season_id
+    }
+
+    fn user_id(&self) -> BigInt {
+        self.user_id
+    }
+
+    fn rank(&self) -> BigInt {
+        self.rank
+    }
+
+    fn xp(&self) -> BigInt {
+        self.xp
+    }
+
+    async fn user(ctx: &Context) -> Option<CachedUser> {
+        ctx.cached_user_loader.load(self.user_id.0).await
+    }
+}
+
+async fn from_guild_query(pool: &sqlx::PgPool, guild_id: i64) -> Result<Vec<XpSeason>> {
+    sqlx::query_as!(
+        XpSeason,
+        r#"
+              SELECT id as "id: BigInt",
+                     guild_id as "guild_id: BigInt",
+                     name,
+                     ended_at
+                FROM app_public.xp_seasons
+               WHERE guild_id = $1
+            ORDER BY ended_at DESC
+        "#,
+        guild_id,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(Into::into)
+}
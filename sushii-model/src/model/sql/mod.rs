@@ -1,26 +1,43 @@
+pub mod ban_list;
 pub mod failure;
 pub mod feeds;
 pub mod guild;
+pub mod mod_appeal;
 pub mod mod_log;
+pub mod mod_stats;
 pub mod mute;
 pub mod user;
 
 pub use self::{
+    ban_list::{BanListAlert, BanListSubscription, BanListTrust, SharedBan},
     failure::Failure,
     feeds::{Feed, FeedItem, FeedMetadata, FeedSubscription},
     guild::{
         cached_guild::CachedGuild,
         guild_config::GuildConfig,
-        guild_roles::{GuildGroup, GuildRole, GuildRoles},
+        guild_roles::{GuildGroup, GuildRole, GuildRoles, RoleConfigIssue, RoleRequirements},
         guild_setting::{GuildSetting, GuildSettingAction},
-        messages::SavedMessage,
+        level_role::LevelRole,
+        messages::{MessageSearch, MessageStorage, SavedMessage},
+        role_menu::RoleMenu,
         tags::Tag,
+        xp_season::{XpSeason, XpSeasonStanding},
     },
+    mod_appeal::ModAppeal,
     mod_log::ModLogEntry,
+    mod_stats::{ModActionCount, ModStats},
     mute::{delete_mute, Mute},
     user::{
-        cached_user::CachedUser, notification::Notification, reminder::Reminder,
-        user_data::UserData, user_level::UserLevel, user_level_global::UserLevelGlobal,
-        user_level_ranked::UserLevelRanked, user_xp::UserXP,
+        cached_user::CachedUser,
+        name_history::NameHistory,
+        notification::Notification,
+        reminder::Reminder,
+        user_data::UserData,
+        user_level::{UserLevel, XpImportMode},
+        user_level_global::UserLevelGlobal,
+        user_level_ranked::UserLevelRanked,
+        user_xp::UserXP,
+        voice_activity::VoiceActivity,
+        xp_adjustment::XpAdjustment,
     },
 };
@@ -0,0 +1,236 @@
+use chrono::{naive::NaiveDateTime, offset::Utc, Duration};
+use serde::{Deserialize, Serialize};
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::keys::DbPool;
+
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct ModAppeal {
+    /// (guild_id, case_id) foreign key to the appealed mod action
+    pub guild_id: i64,
+    pub case_id: i64,
+    pub user_id: i64,
+    pub reason: String,
+    /// pending, accepted, denied, expired, or failed if accepted but the
+    /// action could not be reverted
+    pub status: String,
+    pub created_at: NaiveDateTime,
+    /// Message ID of the appeal in the staff appeal channel
+    pub msg_id: Option<i64>,
+    /// Moderator that accepted or denied the appeal
+    pub decided_by: Option<i64>,
+}
+
+impl ModAppeal {
+    pub fn new(guild_id: u64, case_id: u64, user_id: u64, reason: &str) -> Self {
+        ModAppeal {
+            guild_id: guild_id as i64,
+            case_id: case_id as i64,
+            user_id: user_id as i64,
+            reason: reason.into(),
+            status: "pending".into(),
+            created_at: Utc::now().naive_utc(),
+            msg_id: None,
+            decided_by: None,
+        }
+    }
+
+    pub fn status(mut self, status: &str) -> Self {
+        self.status = status.into();
+        self
+    }
+
+    pub fn msg_id(mut self, msg_id: u64) -> Self {
+        self.msg_id.replace(msg_id as i64);
+        self
+    }
+
+    pub fn decided_by(mut self, user_id: u64) -> Self {
+        self.decided_by.replace(user_id as i64);
+        self
+    }
+
+    /// If this appeal still blocks new appeals for the same case. Pending
+    /// appeals older than the timeout can be re-submitted even if they haven't
+    /// been marked as expired yet
+    pub fn is_active(&self, timeout: Duration) -> bool {
+        match self.status.as_str() {
+            "pending" => Utc::now().naive_utc() - self.created_at < timeout,
+            "expired" => false,
+            _ => true,
+        }
+    }
+
+    /// Gets the appeal for a mod log case
+    pub async fn from_case_id(
+        ctx: &Context,
+        guild_id: u64,
+        case_id: u64,
+    ) -> Result<Option<ModAppeal>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        from_case_id_query(&pool, guild_id, case_id).await
+    }
+
+    /// Gets the appeal posted in the staff channel with the given message ID
+    pub async fn from_msg_id(ctx: &Context, msg_id: u64) -> Result<Option<ModAppeal>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        from_msg_id_query(&pool, msg_id).await
+    }
+
+    /// Sets the status of a pending appeal, returns None if it was already
+    /// decided. This prevents multiple staff reactions from deciding the same
+    /// appeal
+    pub async fn decide(
+        &self,
+        ctx: &Context,
+        status: &str,
+        decided_by: u64,
+    ) -> Result<Option<ModAppeal>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        decide_query(&pool, self, status, decided_by).await
+    }
+
+    /// Marks pending appeals older than the timeout as expired and returns them
+    pub async fn expire_pending(ctx: &Context, timeout: Duration) -> Result<Vec<ModAppeal>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        expire_pending_query(&pool, Utc::now().naive_utc() - timeout).await
+    }
+
+    /// Saves appeal to the database
+    pub async fn save(&self, ctx: &Context) -> Result<ModAppeal> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        upsert_query(&pool, self).await
+    }
+}
+
+async fn from_case_id_query(
+    pool: &sqlx::PgPool,
+    guild_id: u64,
+    case_id: u64,
+) -> Result<Option<ModAppeal>> {
+    sqlx::query_as!(
+        ModAppeal,
+        r#"
+            SELECT *
+              FROM app_public.mod_appeals
+             WHERE guild_id = $1
+               AND case_id = $2
+        "#,
+        guild_id as i64,
+        case_id as i64,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(Into::into)
+}
+
+async fn from_msg_id_query(pool: &sqlx::PgPool, msg_id: u64) -> Result<Option<ModAppeal>> {
+    sqlx::query_as!(
+        ModAppeal,
+        r#"
+            SELECT *
+              FROM app_public.mod_appeals
+             WHERE msg_id = $1
+        "#,
+        msg_id as i64,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(Into::into)
+}
+
+async fn decide_query(
+    pool: &sqlx::PgPool,
+    appeal: &ModAppeal,
+    status: &str,
+    decided_by: u64,
+) -> Result<Option<ModAppeal>> {
+    sqlx::query_as!(
+        ModAppeal,
+        r#"
+               UPDATE app_public.mod_appeals
+                  SET status = $3,
+                      decided_by = $4
+                WHERE guild_id = $1
+                  AND case_id = $2
+                  AND status = 'pending'
+            RETURNING *
+        "#,
+        appeal.guild_id,
+        appeal.case_id,
+        status,
+        decided_by as i64,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(Into::into)
+}
+
+async fn expire_pending_query(
+    pool: &sqlx::PgPool,
+    created_before: NaiveDateTime,
+) -> Result<Vec<ModAppeal>> {
+    sqlx::query_as!(
+        ModAppeal,
+        r#"
+               UPDATE app_public.mod_appeals
+                  SET status = 'expired'
+                WHERE status = 'pending'
+                  AND created_at < $1
+            RETURNING *
+        "#,
+        created_before,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(Into::into)
+}
+
+async fn upsert_query(pool: &sqlx::PgPool, appeal: &ModAppeal) -> Result<ModAppeal> {
+    sqlx::query_as!(
+        ModAppeal,
+        r#"
+            INSERT INTO app_public.mod_appeals
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (guild_id, case_id)
+              DO UPDATE
+                    SET user_id = $3,
+                        reason = $4,
+                        status = $5,
+                        created_at = $6,
+                        msg_id = $7,
+                        decided_by = $8
+              RETURNING *
+        "#,
+        appeal.guild_id,
+        appeal.case_id,
+        appeal.user_id,
+        appeal.reason,
+        appeal.status,
+        appeal.created_at,
+        appeal.msg_id,
+        appeal.decided_by,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(Into::into)
+}
+
+#[test]
+fn expired_pending_appeal_is_inactive() {
+    let appeal = ModAppeal::new(1, 2, 3, "reason");
+    assert!(appeal.is_active(Duration::days(1)));
+
+    let mut old_appeal = appeal.clone();
+    old_appeal.created_at = old_appeal.created_at - Duration::days(2);
+    assert!(!old_appeal.is_active(Duration::days(1)));
+
+    assert!(appeal.clone().status("denied").is_active(Duration::days(1)));
+    assert!(!appeal.status("expired").is_active(Duration::days(1)));
+}
@@ -0,0 +1,248 @@
+use chrono::naive::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[cfg(not(feature = "graphql"))]
+use crate::keys::DbPool;
+#[cfg(not(feature = "graphql"))]
+use serenity::{model::prelude::*, prelude::*};
+
+#[cfg(feature = "graphql")]
+use juniper::GraphQLObject;
+
+use crate::error::{Error, Result};
+use crate::model::BigInt;
+
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+#[cfg_attr(
+    feature = "graphql",
+    graphql(description = "Number of mod log cases of a single action by a single moderator"),
+    derive(GraphQLObject)
+)]
+pub struct ModActionCount {
+    /// Moderator ID, None for cases without a responsible moderator
+    pub executor_id: Option<BigInt>,
+    pub action: String,
+    pub count: BigInt,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[cfg_attr(
+    feature = "graphql",
+    graphql(description = "Mod log case counts in a time period and the period before it"),
+    derive(GraphQLObject)
+)]
+pub struct ModStats {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    /// Counts between start and end
+    pub current: Vec<ModActionCount>,
+    /// Counts in the same length period right before start
+    pub previous: Vec<ModActionCount>,
+}
+
+/// Start of the period ending at end and the start of the period before it
+fn period_starts(
+    end: NaiveDateTime,
+    period: chrono::Duration,
+) -> Result<(NaiveDateTime, NaiveDateTime)> {
+    end.checked_sub_signed(period)
+        .and_then(|start| Some((start, start.checked_sub_signed(period)?)))
+        .ok_or_else(|| Error::Sushii("Period is too long".into()))
+}
+
+/// Sums counts grouped by a key, sorted by highest count first
+fn sum_by<K, F>(counts: &[ModActionCount], key: F) -> Vec<(K, i64)>
+where
+    K: std::hash::Hash + Eq + Ord + Clone,
+    F: Fn(&ModActionCount) -> K,
+{
+    let map = counts.iter().fold(HashMap::new(), |mut acc, c| {
+        *acc.entry(key(c)).or_insert(0i64) += c.count.0;
+
+        acc
+    });
+
+    let mut totals: Vec<(K, i64)> = map.into_iter().collect();
+    // Sort by count, then by key so ties are in a stable order
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    totals
+}
+
+impl ModActionCount {
+    /// Total cases per action, sorted by highest count first
+    pub fn action_totals(counts: &[Self]) -> Vec<(String, i64)> {
+        sum_by(counts, |c| c.action.clone())
+    }
+
+    /// Total cases per moderator, sorted by highest count first
+    pub fn executor_totals(counts: &[Self]) -> Vec<(Option<i64>, i64)> {
+        sum_by(counts, |c| c.executor_id.map(|id| id.0))
+    }
+
+    /// Total number of cases
+    pub fn total(counts: &[Self]) -> i64 {
+        counts.iter().map(|c| c.count.0).sum()
+    }
+
+    #[cfg(feature = "graphql")]
+    pub async fn from_range(
+        pool: &sqlx::PgPool,
+        guild_id: BigInt,
+        executor_id: Option<BigInt>,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<Self>> {
+        from_range_query(pool, guild_id.0, executor_id.map(|id| id.0), start, end).await
+    }
+
+    /// Gets case counts grouped by moderator and action between start and end
+    #[cfg(not(feature = "graphql"))]
+    pub async fn from_range(
+        ctx: &Context,
+        guild_id: GuildId,
+        executor_id: Option<UserId>,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        from_range_query(
+            &pool,
+            guild_id.0 as i64,
+            executor_id.map(|id| id.0 as i64),
+            start,
+            end,
+        )
+        .await
+    }
+}
+
+impl ModStats {
+    #[cfg(feature = "graphql")]
+    pub async fn from_period(
+        pool: &sqlx::PgPool,
+        guild_id: BigInt,
+        executor_id: Option<BigInt>,
+        end: NaiveDateTime,
+        period: chrono::Duration,
+    ) -> Result<Self> {
+        let (start, previous_start) = period_starts(end, period)?;
+        let (current, previous) = tokio::join!(
+            ModActionCount::from_range(pool, guild_id, executor_id, start, end),
+            ModActionCount::from_range(pool, guild_id, executor_id, previous_start, start),
+        );
+
+        Ok(Self {
+            start,
+            end,
+            current: current?,
+            previous: previous?,
+        })
+    }
+
+    /// Gets case counts for the period ending at end, and the period before it
+    #[cfg(not(feature = "graphql"))]
+    pub async fn from_period(
+        ctx: &Context,
+        guild_id: GuildId,
+        executor_id: Option<UserId>,
+        end: NaiveDateTime,
+        period: chrono::Duration,
+    ) -> Result<Self> {
+        let (start, previous_start) = period_starts(end, period)?;
+        let (current, previous) = tokio::join!(
+            ModActionCount::from_range(ctx, guild_id, executor_id, start, end),
+            ModActionCount::from_range(ctx, guild_id, executor_id, previous_start, start),
+        );
+
+        Ok(Self {
+            start,
+            end,
+            current: current?,
+            previous: previous?,
+        })
+    }
+}
+
+async fn from_range_query(
+    pool: &sqlx::PgPool,
+    guild_id: i64,
+    executor_id: Option<i64>,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Result<Vec<ModActionCount>> {
+    sqlx::query_as!(
+        ModActionCount,
+        r#"
+              SELECT executor_id as "executor_id: BigInt",
+                     action,
+                     COUNT(*) as "count!: BigInt"
+                FROM app_public.mod_logs
+               WHERE guild_id = $1
+                 AND (executor_id = $2 OR $2 IS NULL)
+                 AND action_time >= $3
+                 AND action_time < $4
+            GROUP BY executor_id, action
+        "#,
+        guild_id,
+        executor_id,
+        start,
+        end,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(executor_id: Option<i64>, action: &str, count: i64) -> ModActionCount {
+        ModActionCount {
+            executor_id: executor_id.map(BigInt),
+            action: action.into(),
+            count: BigInt(count),
+        }
+    }
+
+    #[test]
+    fn limits_periods() {
+        let end = chrono::NaiveDate::from_ymd(2021, 3, 1).and_hms(0, 0, 0);
+
+        assert_eq!(
+            period_starts(end, chrono::Duration::days(1)).unwrap(),
+            (
+                chrono::NaiveDate::from_ymd(2021, 2, 28).and_hms(0, 0, 0),
+                chrono::NaiveDate::from_ymd(2021, 2, 27).and_hms(0, 0, 0),
+            )
+        );
+        assert!(period_starts(end, chrono::Duration::days(365 * 1_000_000)).is_err());
+    }
+
+    #[test]
+    fn sums_totals() {
+        let counts = vec![
+            count(Some(1), "ban", 3),
+            count(Some(1), "mute", 1),
+            count(Some(2), "ban", 2),
+            count(None, "unmute", 4),
+        ];
+
+        assert_eq!(
+            ModActionCount::action_totals(&counts),
+            vec![
+                ("ban".to_string(), 5),
+                ("unmute".to_string(), 4),
+                ("mute".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            ModActionCount::executor_totals(&counts),
+            vec![(None, 4), (Some(1), 4), (Some(2), 2)]
+        );
+        assert_eq!(ModActionCount::total(&counts), 10);
+    }
+}
@@ -18,6 +18,9 @@ pub struct Mute {
     pub pending: bool,
     pub start_time: NaiveDateTime,
     pub end_time: Option<NaiveDateTime>,
+
+    /// User was already notified elsewhere, so no mute DM is sent
+    pub skip_dm: bool,
 }
 
 impl Mute {
@@ -31,6 +34,7 @@ impl Mute {
             pending: false,
             start_time: now,
             end_time: duration.map(|d| now + d),
+            skip_dm: false,
         }
     }
 
@@ -44,6 +48,11 @@ impl Mute {
         self
     }
 
+    pub fn skip_dm(mut self, skip_dm: bool) -> Self {
+        self.skip_dm = skip_dm;
+        self
+    }
+
     /// Gets total mute duration
     pub fn get_duration(&self) -> Option<Duration> {
         self.end_time
@@ -217,13 +226,14 @@ async fn upsert_query(pool: &sqlx::PgPool, mute: &Mute) -> Result<Mute> {
     sqlx::query_as!(
         Mute,
         r#"
-        INSERT INTO app_public.mutes (guild_id, user_id, start_time, end_time, pending, case_id)
-             VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO app_public.mutes (guild_id, user_id, start_time, end_time, pending, case_id, skip_dm)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
         ON CONFLICT (guild_id, user_id)
           DO UPDATE
                 SET start_time = $3,
                     end_time = $4,
-                    pending = $5
+                    pending = $5,
+                    skip_dm = $7
             RETURNING *
         "#,
         // Not in the order of the struct fields, but in the order of columns or it make error :(
@@ -234,6 +244,7 @@ async fn upsert_query(pool: &sqlx::PgPool, mute: &Mute) -> Result<Mute> {
         mute.end_time,
         mute.pending,
         mute.case_id,
+        mute.skip_dm,
     )
     .fetch_one(pool)
     .await
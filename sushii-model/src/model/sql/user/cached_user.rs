@@ -46,9 +46,10 @@ impl CachedUser {
         from_ids_query(pool, user_ids).await
     }
 
-    /// Updates user
+    /// Updates user, returns the previously cached user if the name,
+    /// discriminator or avatar changed
     #[cfg(not(feature = "graphql"))]
-    pub async fn update(ctx: &Context, user: &User) -> Result<()> {
+    pub async fn update(ctx: &Context, user: &User) -> Result<Option<CachedUser>> {
         let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
 
         update_query(&pool, user).await
@@ -95,15 +96,24 @@ async fn from_ids_query(pool: &sqlx::PgPool, user_ids: &[i64]) -> Result<Vec<Cac
 }
 
 #[cfg(not(feature = "graphql"))]
-async fn update_query(pool: &sqlx::PgPool, user: &User) -> Result<()> {
+async fn update_query(pool: &sqlx::PgPool, user: &User) -> Result<Option<CachedUser>> {
     let cached_user = from_id_query(pool, user.id.0 as i64).await?;
 
-    if let Some(cached_user) = cached_user {
+    let changed_user = cached_user
+        .as_ref()
+        .filter(|c| {
+            c.name != user.name
+                || c.discriminator != user.discriminator as i32
+                || c.avatar_url != user.face()
+        })
+        .cloned();
+
+    if let Some(ref cached_user) = cached_user {
         let now = Utc::now().naive_utc();
 
-        // If not yet 1 day since last check, skip
-        if now < (cached_user.last_checked + Duration::days(1)) {
-            return Ok(());
+        // If not yet 1 day since last check, skip unless changed
+        if changed_user.is_none() && now < (cached_user.last_checked + Duration::days(1)) {
+            return Ok(None);
         }
     }
 
@@ -127,7 +137,7 @@ async fn update_query(pool: &sqlx::PgPool, user: &User) -> Result<()> {
     .execute(pool)
     .await?;
 
-    Ok(())
+    Ok(changed_user)
 }
 
 #[cfg(feature = "graphql")]
@@ -1,4 +1,5 @@
 pub mod cached_user;
+pub mod name_history;
 pub mod notification;
 pub mod reminder;
 pub mod user_data;
@@ -6,3 +7,5 @@ pub mod user_level;
 pub mod user_level_global;
 pub mod user_level_ranked;
 pub mod user_xp;
+pub mod voice_activity;
+pub mod xp_adjustment;
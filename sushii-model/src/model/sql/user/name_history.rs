@@ -0,0 +1,92 @@
+use chrono::{naive::NaiveDateTime, offset::Utc};
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::keys::DbPool;
+
+/// A past username or nickname of a user
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct NameHistory {
+    pub id: i64,
+    pub user_id: i64,
+    /// Guild the nickname was used in, None for usernames
+    pub guild_id: Option<i64>,
+    /// Full tag for usernames, display name for nicknames
+    pub name: String,
+    pub changed_at: NaiveDateTime,
+}
+
+impl NameHistory {
+    /// Saves a new username
+    pub async fn save_username(ctx: &Context, user: &User) -> Result<Self> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        insert_query(&pool, user.id, None, &user.tag()).await
+    }
+
+    /// Saves a new nickname in a guild, should be the username if the nickname
+    /// was removed
+    pub async fn save_nickname(
+        ctx: &Context,
+        guild_id: GuildId,
+        user_id: UserId,
+        name: &str,
+    ) -> Result<Self> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        insert_query(&pool, user_id, Some(guild_id), name).await
+    }
+
+    /// Gets past usernames and nicknames in a guild, newest first
+    pub async fn from_user(
+        ctx: &Context,
+        guild_id: GuildId,
+        user_id: UserId,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            NameHistory,
+            r#"
+                  SELECT *
+                    FROM app_public.name_history
+                   WHERE user_id = $1
+                         AND (guild_id = $2 OR guild_id IS NULL)
+                ORDER BY changed_at DESC
+                   LIMIT $3
+            "#,
+            user_id.0 as i64,
+            guild_id.0 as i64,
+            limit,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
+}
+
+async fn insert_query(
+    pool: &sqlx::PgPool,
+    user_id: UserId,
+    guild_id: Option<GuildId>,
+    name: &str,
+) -> Result<NameHistory> {
+    sqlx::query_as!(
+        NameHistory,
+        r#"
+            INSERT INTO app_public.name_history (user_id, guild_id, name, changed_at)
+                 VALUES ($1, $2, $3, $4)
+              RETURNING *
+        "#,
+        user_id.0 as i64,
+        guild_id.map(|id| id.0 as i64),
+        name,
+        Utc::now().naive_utc(),
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(Into::into)
+}
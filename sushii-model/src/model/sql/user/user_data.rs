@@ -20,6 +20,8 @@ pub struct UserData {
     pub last_fishies: Option<NaiveDateTime>,
     pub profile_data: Option<serde_json::Value>,
     pub lastfm_username: Option<String>,
+    /// Don't send level up announcements for this user
+    pub level_up_opt_out: bool,
 }
 
 fn eligible(last_time: Option<NaiveDateTime>, cooldown: Duration) -> bool {
@@ -165,8 +167,8 @@ async fn upsert_query(pool: &sqlx::PgPool, user_data: &UserData) -> Result<UserD
     sqlx::query_as!(
         UserData,
         r#"
-        INSERT INTO app_public.users (id, is_patron, patron_emoji, rep, fishies, last_rep, last_fishies, profile_data, lastfm_username)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        INSERT INTO app_public.users (id, is_patron, patron_emoji, rep, fishies, last_rep, last_fishies, profile_data, lastfm_username, level_up_opt_out)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
         ON CONFLICT (id)
           DO UPDATE
                 SET is_patron = $2,
@@ -176,7 +178,8 @@ async fn upsert_query(pool: &sqlx::PgPool, user_data: &UserData) -> Result<UserD
                     last_rep = $6,
                     last_fishies = $7,
                     profile_data = $8,
-                    lastfm_username = $9
+                    lastfm_username = $9,
+                    level_up_opt_out = $10
           RETURNING *
         "#,
         user_data.id,
@@ -188,6 +191,7 @@ async fn upsert_query(pool: &sqlx::PgPool, user_data: &UserData) -> Result<UserD
         user_data.last_fishies,
         user_data.profile_data,
         user_data.lastfm_username,
+        user_data.level_up_opt_out,
     )
     .fetch_one(pool)
     .await
@@ -2,14 +2,50 @@ use chrono::{naive::NaiveDateTime, offset::Utc, Datelike, Duration};
 use serde::{Deserialize, Serialize};
 use serenity::model::prelude::*;
 use serenity::prelude::*;
+use std::str::FromStr;
 
 #[cfg(feature = "graphql")]
 use juniper::GraphQLObject;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::keys::DbPool;
+use crate::model::user::user_level::{get_level, next_level};
 use crate::model::BigInt;
 
+/// Max number of users upserted per query when importing XP
+const IMPORT_BATCH_SIZE: usize = 1000;
+
+/// How imported XP is combined with a user's existing XP
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum XpImportMode {
+    Overwrite,
+    Add,
+    Max,
+}
+
+impl XpImportMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            XpImportMode::Overwrite => "overwrite",
+            XpImportMode::Add => "add",
+            XpImportMode::Max => "max",
+        }
+    }
+}
+
+impl FromStr for XpImportMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "overwrite" => Ok(XpImportMode::Overwrite),
+            "add" => Ok(XpImportMode::Add),
+            "max" => Ok(XpImportMode::Max),
+            _ => Err(Error::Sushii("Invalid XP import mode".into())),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
 #[cfg_attr(
     feature = "graphql",
@@ -23,11 +59,17 @@ pub struct UserLevel {
     pub msg_month: BigInt,
     pub msg_week: BigInt,
     pub msg_day: BigInt,
+    /// Part of all time XP earned in voice channels
+    pub voice_xp: BigInt,
     pub last_msg: NaiveDateTime,
+    /// When day, week and month XP were last reset
+    pub interval_reset: NaiveDateTime,
 }
 
 impl UserLevel {
     pub fn new(user_id: UserId, guild_id: GuildId) -> Self {
+        let now = Utc::now().naive_utc();
+
         Self {
             user_id: user_id.into(),
             guild_id: guild_id.into(),
@@ -36,57 +78,105 @@ impl UserLevel {
             msg_month: 5u64.into(),
             msg_week: 5u64.into(),
             msg_day: 5u64.into(),
-            last_msg: Utc::now().naive_utc(),
+            voice_xp: 0u64.into(),
+            last_msg: now,
+            interval_reset: now,
         }
     }
 
-    /// Checks if user is eligible for increment, limited to once per minute
-    pub fn eligible(&self) -> bool {
+    /// Current level from all time XP
+    pub fn level(&self) -> i64 {
+        get_level(self.msg_all_time.0)
+    }
+
+    /// All time XP earned from messages
+    pub fn text_xp(&self) -> i64 {
+        self.msg_all_time.0 - self.voice_xp.0
+    }
+
+    /// Checks if user is eligible for increment, limited to once per cooldown
+    pub fn eligible(&self, cooldown: Duration) -> bool {
         let now = Utc::now().naive_utc();
 
-        // Now is past (last message + 1 minute)
-        now > (self.last_msg + Duration::minutes(1))
+        // Now is past (last message + cooldown)
+        now > (self.last_msg + cooldown)
     }
 
-    /// Increments values with the time intervals reset accordingly
-    pub fn inc(mut self) -> Self {
-        self.reset_intervals().inc_fields();
+    /// Increments values by the given XP with the time intervals reset accordingly
+    pub fn inc(mut self, xp: i64) -> Self {
+        self.reset_intervals().inc_fields(xp);
 
-        // Set last_message to now, so that next XP inc is minimum 1 minute later
-        // Must be set AFTER intervals are reset, otherwise they will never be reset
+        // Set last_message to now, so that next XP inc is minimum 1 cooldown later
         self.last_msg = Utc::now().naive_utc();
 
         self
     }
 
-    /// Resets intervals that have expired
+    /// Increments values by XP earned in voice channels. This doesn't start a
+    /// message cooldown, so members can still earn XP from messages while in
+    /// a voice channel
+    pub fn inc_voice(mut self, xp: i64) -> Self {
+        self.reset_intervals().inc_fields(xp);
+        self.voice_xp.0 += xp;
+
+        self
+    }
+
+    /// Adds or removes XP with the time intervals reset accordingly, so that
+    /// timeframe XP stays consistent with all time XP. XP never goes below 0.
+    /// This doesn't start a message cooldown
+    pub fn adjust(mut self, xp: i64) -> Self {
+        self.reset_intervals();
+
+        self.msg_all_time.0 = (self.msg_all_time.0 + xp).max(0);
+
+        // Interval XP can't be more than all time XP
+        let all_time = self.msg_all_time.0;
+        for interval in &mut [&mut self.msg_month, &mut self.msg_week, &mut self.msg_day] {
+            interval.0 = (interval.0 + xp).max(0).min(all_time);
+        }
+
+        // Removed XP is taken from text XP first
+        self.voice_xp.0 = self.voice_xp.0.min(all_time);
+
+        self
+    }
+
+    /// Sets all time XP, with the difference applied to the time intervals
+    pub fn set_xp(self, xp: i64) -> Self {
+        let diff = xp - self.msg_all_time.0;
+
+        self.adjust(diff)
+    }
+
+    /// Resets intervals that have expired, intervals are then current until
+    /// the end of the day
     fn reset_intervals(&mut self) -> &mut Self {
         let now = Utc::now().naive_utc();
 
-        if now.ordinal() != self.last_msg.ordinal() {
+        if now.ordinal() != self.interval_reset.ordinal() {
             self.msg_day = 0u64.into();
         }
 
-        if now.iso_week() != self.last_msg.iso_week() {
+        if now.iso_week() != self.interval_reset.iso_week() {
             self.msg_week = 0u64.into();
         }
 
-        if now.month() != self.last_msg.month() {
+        if now.month() != self.interval_reset.month() {
             self.msg_month = 0u64.into();
         }
 
+        self.interval_reset = now;
+
         self
     }
 
-    /// Increment all fields by 5
-    fn inc_fields(&mut self) -> &mut Self {
-        // Add enough to be rounded to 5
-        let to_add = 5 - self.msg_all_time.0 % 5;
-
-        self.msg_all_time.0 += to_add;
-        self.msg_month.0 += to_add;
-        self.msg_week.0 += to_add;
-        self.msg_day.0 += to_add;
+    /// Increment all fields by the given XP
+    fn inc_fields(&mut self, xp: i64) -> &mut Self {
+        self.msg_all_time.0 += xp;
+        self.msg_month.0 += xp;
+        self.msg_week.0 += xp;
+        self.msg_day.0 += xp;
 
         self
     }
@@ -116,6 +206,151 @@ impl UserLevel {
 
         upsert_query(&pool, &self).await
     }
+
+    /// Deletes a user's level in a guild, returns the deleted level if it existed
+    pub async fn delete(
+        ctx: &Context,
+        user_id: UserId,
+        guild_id: GuildId,
+    ) -> Result<Option<UserLevel>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            UserLevel,
+            r#"
+                DELETE FROM app_public.user_levels
+                      WHERE user_id = $1
+                            AND guild_id = $2
+                  RETURNING user_id as "user_id: BigInt",
+                            guild_id as "guild_id: BigInt",
+                            msg_all_time as "msg_all_time: BigInt",
+                            msg_month as "msg_month: BigInt",
+                            msg_week as "msg_week: BigInt",
+                            msg_day as "msg_day: BigInt",
+                            voice_xp as "voice_xp: BigInt",
+                            last_msg,
+                            interval_reset
+            "#,
+            i64::from(user_id),
+            i64::from(guild_id),
+        )
+        .fetch_optional(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Deletes all user levels in a guild, returns the number deleted
+    pub async fn delete_guild(ctx: &Context, guild_id: GuildId) -> Result<u64> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query!(
+            r#"
+                DELETE FROM app_public.user_levels
+                      WHERE guild_id = $1
+            "#,
+            i64::from(guild_id),
+        )
+        .execute(&pool)
+        .await
+        .map(|r| r.rows_affected())
+        .map_err(Into::into)
+    }
+
+    /// Imports all time XP for users in a guild as (user ID, XP) pairs,
+    /// returns the number of users updated. Imported XP isn't counted in the
+    /// day, week and month intervals since it was earned before
+    pub async fn import(
+        ctx: &Context,
+        guild_id: GuildId,
+        users: &[(i64, i64)],
+        mode: XpImportMode,
+    ) -> Result<u64> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+        let now = Utc::now().naive_utc();
+
+        let mut tx = pool.begin().await?;
+        let mut count = 0;
+
+        for batch in users.chunks(IMPORT_BATCH_SIZE) {
+            let (user_ids, xp): (Vec<i64>, Vec<i64>) = batch.iter().copied().unzip();
+
+            count += sqlx::query!(
+                r#"
+                    INSERT INTO app_public.user_levels (user_id, guild_id, msg_all_time, msg_month, msg_week, msg_day, last_msg, interval_reset)
+                         SELECT user_id, $2, xp, 0, 0, 0, $5, $5
+                           FROM UNNEST($1::BIGINT[], $3::BIGINT[]) AS t (user_id, xp)
+                    ON CONFLICT (user_id, guild_id)
+                      DO UPDATE
+                            SET msg_all_time = CASE $4::TEXT
+                                    WHEN 'add' THEN user_levels.msg_all_time + EXCLUDED.msg_all_time
+                                    WHEN 'max' THEN GREATEST(user_levels.msg_all_time, EXCLUDED.msg_all_time)
+                                    ELSE EXCLUDED.msg_all_time
+                                END,
+                                -- Overwriting can lower all time XP below interval XP
+                                msg_month = CASE WHEN $4 = 'overwrite'
+                                    THEN LEAST(user_levels.msg_month, EXCLUDED.msg_all_time)
+                                    ELSE user_levels.msg_month
+                                END,
+                                msg_week = CASE WHEN $4 = 'overwrite'
+                                    THEN LEAST(user_levels.msg_week, EXCLUDED.msg_all_time)
+                                    ELSE user_levels.msg_week
+                                END,
+                                msg_day = CASE WHEN $4 = 'overwrite'
+                                    THEN LEAST(user_levels.msg_day, EXCLUDED.msg_all_time)
+                                    ELSE user_levels.msg_day
+                                END,
+                                voice_xp = CASE WHEN $4 = 'overwrite'
+                                    THEN LEAST(user_levels.voice_xp, EXCLUDED.msg_all_time)
+                                    ELSE user_levels.voice_xp
+                                END
+                "#,
+                &user_ids,
+                i64::from(guild_id),
+                &xp,
+                mode.as_str(),
+                now,
+            )
+            .execute(&mut tx)
+            .await?
+            .rows_affected();
+        }
+
+        tx.commit().await?;
+
+        Ok(count)
+    }
+
+    /// Gets all user levels in a guild that are at least a given level
+    pub async fn from_guild_min_level(
+        ctx: &Context,
+        guild_id: GuildId,
+        min_level: i64,
+    ) -> Result<Vec<UserLevel>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            UserLevel,
+            r#"
+                SELECT user_id as "user_id: BigInt",
+                       guild_id as "guild_id: BigInt",
+                       msg_all_time as "msg_all_time: BigInt",
+                       msg_month as "msg_month: BigInt",
+                       msg_week as "msg_week: BigInt",
+                       msg_day as "msg_day: BigInt",
+                       voice_xp as "voice_xp: BigInt",
+                       last_msg,
+                       interval_reset
+                  FROM app_public.user_levels
+                 WHERE guild_id = $1
+                   AND msg_all_time >= $2
+            "#,
+            i64::from(guild_id),
+            next_level(min_level),
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(Into::into)
+    }
 }
 
 async fn from_id_query(
@@ -132,7 +367,9 @@ async fn from_id_query(
                    msg_month as "msg_month: BigInt",
                    msg_week as "msg_week: BigInt",
                    msg_day as "msg_day: BigInt",
-                   last_msg
+                   voice_xp as "voice_xp: BigInt",
+                   last_msg,
+                   interval_reset
               FROM app_public.user_levels
              WHERE user_id = $1
                AND guild_id = $2
@@ -149,22 +386,26 @@ async fn upsert_query(pool: &sqlx::PgPool, user_level: &UserLevel) -> Result<Use
     sqlx::query_as!(
         UserLevel,
         r#"
-        INSERT INTO app_public.user_levels (user_id, guild_id, msg_all_time, msg_month, msg_week, msg_day, last_msg)
-             VALUES ($1, $2, $3, $4, $5, $6, $7)
+        INSERT INTO app_public.user_levels (user_id, guild_id, msg_all_time, msg_month, msg_week, msg_day, voice_xp, last_msg, interval_reset)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
         ON CONFLICT (user_id, guild_id)
           DO UPDATE
                 SET msg_all_time = $3,
                     msg_month = $4,
                     msg_week = $5,
                     msg_day = $6,
-                    last_msg = $7
+                    voice_xp = $7,
+                    last_msg = $8,
+                    interval_reset = $9
           RETURNING user_id as "user_id: BigInt",
                     guild_id as "guild_id: BigInt",
                     msg_all_time as "msg_all_time: BigInt",
                     msg_month as "msg_month: BigInt",
                     msg_week as "msg_week: BigInt",
                     msg_day as "msg_day: BigInt",
-                    last_msg
+                    voice_xp as "voice_xp: BigInt",
+                    last_msg,
+                    interval_reset
         "#,
         user_level.user_id.0,
         user_level.guild_id.0,
@@ -172,9 +413,87 @@ async fn upsert_query(pool: &sqlx::PgPool, user_level: &UserLevel) -> Result<Use
         user_level.msg_month.0,
         user_level.msg_week.0,
         user_level.msg_day.0,
+        user_level.voice_xp.0,
         user_level.last_msg,
+        user_level.interval_reset,
     )
     .fetch_one(pool)
     .await
     .map_err(Into::into)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjusts_xp_with_intervals() {
+        let mut user_level = UserLevel::new(UserId(1), GuildId(2));
+        user_level.msg_all_time = 100u64.into();
+        user_level.msg_month = 50u64.into();
+        user_level.msg_week = 20u64.into();
+        user_level.msg_day = 10u64.into();
+
+        let user_level = user_level.adjust(15);
+        assert_eq!(user_level.msg_all_time.0, 115);
+        assert_eq!(user_level.msg_month.0, 65);
+        assert_eq!(user_level.msg_day.0, 25);
+
+        let user_level = user_level.adjust(-30);
+        assert_eq!(user_level.msg_all_time.0, 85);
+        assert_eq!(user_level.msg_week.0, 5);
+        assert_eq!(user_level.msg_day.0, 0);
+
+        let user_level = user_level.set_xp(100);
+        assert_eq!(user_level.msg_all_time.0, 100);
+        assert_eq!(user_level.msg_month.0, 50);
+        assert_eq!(user_level.msg_week.0, 20);
+
+        let user_level = user_level.set_xp(0);
+        assert_eq!(user_level.msg_all_time.0, 0);
+        assert_eq!(user_level.msg_month.0, 0);
+    }
+
+    #[test]
+    fn adjusts_xp_without_cooldown() {
+        let mut user_level = UserLevel::new(UserId(1), GuildId(2));
+        let last_msg = Utc::now().naive_utc() - Duration::days(40);
+        user_level.last_msg = last_msg;
+        user_level.interval_reset = last_msg;
+
+        // Old intervals are reset without changing last_msg
+        let user_level = user_level.adjust(10);
+        assert_eq!(user_level.msg_all_time.0, 15);
+        assert_eq!(user_level.msg_month.0, 10);
+        assert_eq!(user_level.last_msg, last_msg);
+        assert!(user_level.eligible(Duration::minutes(1)));
+
+        // Intervals aren't reset again
+        let user_level = user_level.adjust(10);
+        assert_eq!(user_level.msg_day.0, 20);
+    }
+
+    #[test]
+    fn adds_voice_xp_without_cooldown() {
+        let mut user_level = UserLevel::new(UserId(1), GuildId(2));
+        let last_msg = Utc::now().naive_utc() - Duration::days(40);
+        user_level.last_msg = last_msg;
+        user_level.interval_reset = last_msg;
+
+        let user_level = user_level.inc_voice(10);
+        assert_eq!(user_level.msg_all_time.0, 15);
+        assert_eq!(user_level.msg_month.0, 10);
+        assert_eq!(user_level.msg_day.0, 10);
+        assert_eq!(user_level.voice_xp.0, 10);
+        assert_eq!(user_level.text_xp(), 5);
+        assert_eq!(user_level.last_msg, last_msg);
+
+        // Intervals aren't reset again on the same day
+        let user_level = user_level.inc_voice(10);
+        assert_eq!(user_level.msg_day.0, 20);
+
+        let user_level = user_level.adjust(-20);
+        assert_eq!(user_level.msg_all_time.0, 5);
+        assert_eq!(user_level.voice_xp.0, 5);
+    }
+}
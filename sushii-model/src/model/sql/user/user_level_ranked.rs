@@ -26,6 +26,8 @@ pub struct UserLevelRanked {
     pub msg_week: BigInt,
     pub msg_day: BigInt,
     pub last_msg: NaiveDateTime,
+    /// When day, week and month XP were last reset
+    pub interval_reset: NaiveDateTime,
 
     // Rank (row #) / Total in category
     pub msg_all_time_rank: Option<BigInt>,
@@ -61,19 +63,19 @@ fn fmt_rank(rank: Option<BigInt>, total: Option<BigInt>) -> String {
 impl UserLevelRanked {
     // Ranks would be for user's last message timeframes, so if user sent a
     // message on 12/02/2020, daily rank would ONLY be for those users who have
-    // last_msg in that day So if it is the next day, 12/03 it would be stale
+    // interval_reset in that day So if it is the next day, 12/03 it would be stale
     fn reset_stale_ranks(mut self) -> Self {
         let now = Utc::now().naive_utc();
 
-        if now.ordinal() != self.last_msg.ordinal() {
+        if now.ordinal() != self.interval_reset.ordinal() {
             self.msg_day_rank = None;
         }
 
-        if now.iso_week() != self.last_msg.iso_week() {
+        if now.iso_week() != self.interval_reset.iso_week() {
             self.msg_week_rank = None;
         }
 
-        if now.month() != self.last_msg.month() {
+        if now.month() != self.interval_reset.month() {
             self.msg_month_rank = None;
         }
 
@@ -156,6 +158,7 @@ async fn ranked_from_id_query(
                    msg_week as "msg_week: BigInt",
                    msg_day as "msg_day: BigInt",
                    last_msg,
+                   interval_reset,
                    msg_all_time_rank as "msg_all_time_rank: BigInt",
                    msg_all_time_total as "msg_all_time_total: BigInt",
                    msg_month_rank as "msg_month_rank: BigInt",
@@ -166,14 +169,14 @@ async fn ranked_from_id_query(
                    msg_day_total as "msg_day_total: BigInt"
                 FROM (
                     SELECT *,
-                        ROW_NUMBER() OVER(PARTITION BY EXTRACT(DOY FROM last_msg) ORDER BY msg_day DESC) AS msg_day_rank,
-                        COUNT(*) OVER(PARTITION BY EXTRACT(DOY FROM last_msg)) AS msg_day_total,
+                        ROW_NUMBER() OVER(PARTITION BY EXTRACT(DOY FROM interval_reset) ORDER BY msg_day DESC) AS msg_day_rank,
+                        COUNT(*) OVER(PARTITION BY EXTRACT(DOY FROM interval_reset)) AS msg_day_total,
 
-                        ROW_NUMBER() OVER(PARTITION BY EXTRACT(WEEK FROM last_msg) ORDER BY msg_week DESC) AS msg_week_rank,
-                        COUNT(*) OVER(PARTITION BY EXTRACT(WEEK FROM last_msg)) AS msg_week_total,
+                        ROW_NUMBER() OVER(PARTITION BY EXTRACT(WEEK FROM interval_reset) ORDER BY msg_week DESC) AS msg_week_rank,
+                        COUNT(*) OVER(PARTITION BY EXTRACT(WEEK FROM interval_reset)) AS msg_week_total,
 
-                        ROW_NUMBER() OVER(PARTITION BY EXTRACT(MONTH FROM last_msg) ORDER BY msg_month DESC) AS msg_month_rank,
-                        COUNT(*) OVER(PARTITION BY EXTRACT(MONTH FROM last_msg)) AS msg_month_total,
+                        ROW_NUMBER() OVER(PARTITION BY EXTRACT(MONTH FROM interval_reset) ORDER BY msg_month DESC) AS msg_month_rank,
+                        COUNT(*) OVER(PARTITION BY EXTRACT(MONTH FROM interval_reset)) AS msg_month_total,
 
                         ROW_NUMBER() OVER(ORDER BY msg_all_time DESC) AS msg_all_time_rank,
                         COUNT(*) OVER() AS msg_all_time_total
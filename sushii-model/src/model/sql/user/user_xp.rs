@@ -11,7 +11,7 @@ use juniper::graphql_object;
 use sqlx::types::Decimal;
 
 #[cfg(feature = "graphql")]
-use crate::model::user::{TimeFrame, UserLevelProgress};
+use crate::model::user::{TimeFrame, UserLevelProgress, XpSource};
 #[cfg(feature = "graphql")]
 use crate::{
     cursor::decode_cursor,
@@ -19,7 +19,7 @@ use crate::{
 };
 
 #[cfg(feature = "graphql")]
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::model::BigInt;
 
 #[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
@@ -34,12 +34,13 @@ pub struct UserXP {
 }
 
 impl UserXP {
-    /// Get guild all time ranks
+    /// Get guild all time ranks. Text and voice XP are only counted all time
     #[cfg(feature = "graphql")]
     pub async fn guild_top(
         pool: &sqlx::PgPool,
         guild_id: BigInt,
         timeframe: TimeFrame,
+        source: XpSource,
         first: BigInt,
         after: Option<String>,
     ) -> Result<(BigInt, Vec<UserXP>)> {
@@ -49,7 +50,17 @@ impl UserXP {
             None
         };
 
-        guild_top_query(pool, guild_id.0, timeframe, first.0, after).await
+        match (source, timeframe) {
+            (XpSource::Combined, _) => {
+                guild_top_query(pool, guild_id.0, timeframe, first.0, after).await
+            }
+            (_, TimeFrame::AllTime) => {
+                guild_source_top_query(pool, guild_id.0, source, first.0, after).await
+            }
+            _ => Err(Error::Sushii(
+                "Text and voice XP leaderboards are only available for all time".into(),
+            )),
+        }
     }
 
     /// Get global all time ranks
@@ -138,8 +149,8 @@ async fn guild_timeframe_user_count(
                 SELECT COUNT(*) as "total!: BigInt"
                   FROM app_public.user_levels
                  WHERE guild_id = $1
-                   AND EXTRACT(DOY  FROM last_msg) = EXTRACT(DOY  FROM NOW())
-                   AND EXTRACT(YEAR FROM last_msg) = EXTRACT(YEAR FROM NOW())
+                   AND EXTRACT(DOY  FROM interval_reset) = EXTRACT(DOY  FROM NOW())
+                   AND EXTRACT(YEAR FROM interval_reset) = EXTRACT(YEAR FROM NOW())
                 "#,
             guild_id,
         )
@@ -151,8 +162,8 @@ async fn guild_timeframe_user_count(
                 SELECT COUNT(*) as "total!: BigInt"
                   FROM app_public.user_levels
                  WHERE guild_id = $1
-                   AND EXTRACT(WEEK FROM last_msg) = EXTRACT(WEEK FROM NOW())
-                   AND EXTRACT(YEAR FROM last_msg) = EXTRACT(YEAR FROM NOW())
+                   AND EXTRACT(WEEK FROM interval_reset) = EXTRACT(WEEK FROM NOW())
+                   AND EXTRACT(YEAR FROM interval_reset) = EXTRACT(YEAR FROM NOW())
                 "#,
             guild_id,
         )
@@ -164,8 +175,8 @@ async fn guild_timeframe_user_count(
                 SELECT COUNT(*) as "total!: BigInt"
                   FROM app_public.user_levels
                  WHERE guild_id = $1
-                   AND EXTRACT(MONTH FROM last_msg) = EXTRACT(MONTH FROM NOW())
-                   AND EXTRACT(YEAR  FROM last_msg) = EXTRACT(YEAR  FROM NOW())
+                   AND EXTRACT(MONTH FROM interval_reset) = EXTRACT(MONTH FROM NOW())
+                   AND EXTRACT(YEAR  FROM interval_reset) = EXTRACT(YEAR  FROM NOW())
                 "#,
             guild_id,
         )
@@ -220,8 +231,8 @@ async fn guild_timeframe_users(
                       FROM app_public.user_levels
                      WHERE guild_id = $1
                        AND ((msg_day, user_id) < ($2, $3) OR $2 IS NULL OR $3 IS NULL)
-                       AND EXTRACT(DOY  FROM last_msg) = EXTRACT(DOY  FROM NOW())
-                       AND EXTRACT(YEAR FROM last_msg) = EXTRACT(YEAR FROM NOW())
+                       AND EXTRACT(DOY  FROM interval_reset) = EXTRACT(DOY  FROM NOW())
+                       AND EXTRACT(YEAR FROM interval_reset) = EXTRACT(YEAR FROM NOW())
                   ORDER BY "xp_diff?: BigInt" DESC,
                            "user_id: BigInt" DESC
                      LIMIT $4
@@ -245,8 +256,8 @@ async fn guild_timeframe_users(
                       FROM app_public.user_levels
                      WHERE guild_id = $1
                        AND ((msg_week, user_id) < ($2, $3) OR $2 IS NULL OR $3 IS NULL)
-                       AND EXTRACT(WEEK FROM last_msg) = EXTRACT(WEEK FROM NOW())
-                       AND EXTRACT(YEAR FROM last_msg) = EXTRACT(YEAR FROM NOW())
+                       AND EXTRACT(WEEK FROM interval_reset) = EXTRACT(WEEK FROM NOW())
+                       AND EXTRACT(YEAR FROM interval_reset) = EXTRACT(YEAR FROM NOW())
                   ORDER BY "xp_diff?: BigInt" DESC,
                            "user_id: BigInt" DESC
                      LIMIT $4
@@ -270,8 +281,8 @@ async fn guild_timeframe_users(
                       FROM app_public.user_levels
                      WHERE guild_id = $1
                        AND ((msg_month, user_id) < ($2, $3) OR $2 IS NULL OR $3 IS NULL)
-                       AND EXTRACT(MONTH FROM last_msg) = EXTRACT(MONTH FROM NOW())
-                       AND EXTRACT(YEAR  FROM last_msg) = EXTRACT(YEAR  FROM NOW())
+                       AND EXTRACT(MONTH FROM interval_reset) = EXTRACT(MONTH FROM NOW())
+                       AND EXTRACT(YEAR  FROM interval_reset) = EXTRACT(YEAR  FROM NOW())
                   ORDER BY "xp_diff?: BigInt" DESC,
                            "user_id: BigInt" DESC
                      LIMIT $4
@@ -304,6 +315,60 @@ async fn guild_top_query(
     Ok((total?, users?))
 }
 
+/// Get guild all time ranks of only text or voice XP
+#[cfg(feature = "graphql")]
+async fn guild_source_top_query(
+    pool: &sqlx::PgPool,
+    guild_id: i64,
+    source: XpSource,
+    first: i64,
+    after: Option<(i64, i64)>,
+) -> Result<(BigInt, Vec<UserXP>)> {
+    let voice = matches!(source, XpSource::Voice);
+
+    // Text XP is the part of all time XP not earned in voice
+    let total = sqlx::query!(
+        r#"
+            SELECT COUNT(*) as "total!: BigInt"
+              FROM app_public.user_levels
+             WHERE guild_id = $1
+               AND CASE WHEN $2 THEN voice_xp ELSE msg_all_time - voice_xp END > 0
+        "#,
+        guild_id,
+        voice,
+    )
+    .fetch_one(pool)
+    .await?
+    .total;
+
+    let users = sqlx::query_as!(
+        UserXP,
+        r#"
+            SELECT user_id as "user_id: BigInt",
+                   guild_id as "guild_id?: BigInt",
+                   CASE WHEN $2 THEN voice_xp ELSE msg_all_time - voice_xp END as "xp!: BigInt",
+                   NULL as "xp_diff?: BigInt"
+              FROM app_public.user_levels
+             WHERE guild_id = $1
+               AND CASE WHEN $2 THEN voice_xp ELSE msg_all_time - voice_xp END > 0
+               AND ((CASE WHEN $2 THEN voice_xp ELSE msg_all_time - voice_xp END, user_id) < ($3, $4)
+                    OR $3 IS NULL OR $4 IS NULL)
+          ORDER BY "xp!: BigInt" DESC,
+                   "user_id: BigInt" DESC
+             LIMIT $5
+        "#,
+        guild_id,
+        voice,
+        after.map(|a| a.0), // xp
+        after.map(|a| a.1), // user id
+        first,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok((total, users))
+}
+
 #[cfg(feature = "graphql")]
 async fn global_timeframe_user_count(pool: &sqlx::PgPool, timeframe: TimeFrame) -> Result<BigInt> {
     // Timeframes also match year, so that old inactive users aren't considered
@@ -322,8 +387,8 @@ async fn global_timeframe_user_count(pool: &sqlx::PgPool, timeframe: TimeFrame)
             r#"
                   SELECT COUNT(DISTINCT user_id) as "total!: BigInt"
                     FROM app_public.user_levels
-                   WHERE EXTRACT(DOY  FROM last_msg) = EXTRACT(DOY  FROM NOW())
-                     AND EXTRACT(YEAR FROM last_msg) = EXTRACT(YEAR FROM NOW())
+                   WHERE EXTRACT(DOY  FROM interval_reset) = EXTRACT(DOY  FROM NOW())
+                     AND EXTRACT(YEAR FROM interval_reset) = EXTRACT(YEAR FROM NOW())
                 "#,
         )
         .fetch_one(pool)
@@ -333,8 +398,8 @@ async fn global_timeframe_user_count(pool: &sqlx::PgPool, timeframe: TimeFrame)
             r#"
                   SELECT COUNT(DISTINCT user_id) as "total!: BigInt"
                     FROM app_public.user_levels
-                   WHERE EXTRACT(WEEK FROM last_msg) = EXTRACT(WEEK FROM NOW())
-                     AND EXTRACT(YEAR FROM last_msg) = EXTRACT(YEAR FROM NOW())
+                   WHERE EXTRACT(WEEK FROM interval_reset) = EXTRACT(WEEK FROM NOW())
+                     AND EXTRACT(YEAR FROM interval_reset) = EXTRACT(YEAR FROM NOW())
                 "#,
         )
         .fetch_one(pool)
@@ -344,8 +409,8 @@ async fn global_timeframe_user_count(pool: &sqlx::PgPool, timeframe: TimeFrame)
             r#"
                   SELECT COUNT(DISTINCT user_id) as "total!: BigInt"
                     FROM app_public.user_levels
-                   WHERE EXTRACT(MONTH FROM last_msg) = EXTRACT(MONTH FROM NOW())
-                     AND EXTRACT(YEAR  FROM last_msg) = EXTRACT(YEAR  FROM NOW())
+                   WHERE EXTRACT(MONTH FROM interval_reset) = EXTRACT(MONTH FROM NOW())
+                     AND EXTRACT(YEAR  FROM interval_reset) = EXTRACT(YEAR  FROM NOW())
                 "#,
         )
         .fetch_one(pool)
@@ -395,8 +460,8 @@ async fn global_timeframe_users(
                            CAST(SUM(msg_all_time) AS BIGINT) AS "xp!: BigInt",
                            CAST(SUM(msg_day) AS BIGINT) AS "xp_diff?: BigInt"
                       FROM app_public.user_levels
-                     WHERE EXTRACT(DOY  FROM last_msg) = EXTRACT(DOY  FROM NOW())
-                       AND EXTRACT(YEAR FROM last_msg) = EXTRACT(YEAR FROM NOW())
+                     WHERE EXTRACT(DOY  FROM interval_reset) = EXTRACT(DOY  FROM NOW())
+                       AND EXTRACT(YEAR FROM interval_reset) = EXTRACT(YEAR FROM NOW())
                   GROUP BY user_id
                         -- after
                     HAVING ((SUM(msg_day), user_id) < ($1, $2) OR $1 IS NULL OR $2 IS NULL)
@@ -420,8 +485,8 @@ async fn global_timeframe_users(
                            CAST(SUM(msg_all_time) AS BIGINT) AS "xp!: BigInt",
                            CAST(SUM(msg_week) AS BIGINT) AS "xp_diff?: BigInt"
                       FROM app_public.user_levels
-                     WHERE EXTRACT(WEEK FROM last_msg) = EXTRACT(WEEK FROM NOW())
-                       AND EXTRACT(YEAR FROM last_msg) = EXTRACT(YEAR FROM NOW())
+                     WHERE EXTRACT(WEEK FROM interval_reset) = EXTRACT(WEEK FROM NOW())
+                       AND EXTRACT(YEAR FROM interval_reset) = EXTRACT(YEAR FROM NOW())
                   GROUP BY user_id
                         -- after
                     HAVING ((SUM(msg_week), user_id) < ($1, $2) OR $1 IS NULL OR $2 IS NULL)
@@ -445,8 +510,8 @@ async fn global_timeframe_users(
                            CAST(SUM(msg_all_time) AS BIGINT) AS "xp!: BigInt",
                            CAST(SUM(msg_month) AS BIGINT) AS "xp_diff?: BigInt"
                       FROM app_public.user_levels
-                     WHERE EXTRACT(MONTH FROM last_msg) = EXTRACT(MONTH FROM NOW())
-                       AND EXTRACT(YEAR  FROM last_msg) = EXTRACT(YEAR  FROM NOW())
+                     WHERE EXTRACT(MONTH FROM interval_reset) = EXTRACT(MONTH FROM NOW())
+                       AND EXTRACT(YEAR  FROM interval_reset) = EXTRACT(YEAR  FROM NOW())
                   GROUP BY user_id
                         -- after
                     HAVING ((SUM(msg_month), user_id) < ($1, $2) OR $1 IS NULL OR $2 IS NULL)
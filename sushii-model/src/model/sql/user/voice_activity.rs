@@ -0,0 +1,202 @@
+use chrono::{naive::NaiveDateTime, Duration};
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::keys::DbPool;
+
+/// Total time a member has spent in voice channels in a guild
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct VoiceActivity {
+    pub guild_id: i64,
+    pub user_id: i64,
+    /// Total seconds of finished voice sessions
+    pub total_secs: i64,
+    /// Start of the current session, None if not in a voice channel
+    pub session_start: Option<NaiveDateTime>,
+    /// Last time the current session was confirmed to be ongoing
+    pub last_seen: Option<NaiveDateTime>,
+}
+
+impl VoiceActivity {
+    pub fn new(guild_id: GuildId, user_id: UserId) -> Self {
+        VoiceActivity {
+            guild_id: guild_id.0 as i64,
+            user_id: user_id.0 as i64,
+            total_secs: 0,
+            session_start: None,
+            last_seen: None,
+        }
+    }
+
+    /// Starts a session if one isn't already started
+    pub fn start_session(mut self, now: NaiveDateTime) -> Self {
+        if self.session_start.is_none() {
+            self.session_start.replace(now);
+            self.last_seen.replace(now);
+        }
+
+        self
+    }
+
+    /// Ends the current session and adds it to the total
+    pub fn end_session(mut self, now: NaiveDateTime) -> Self {
+        if let Some(start) = self.session_start.take() {
+            // Clock changes shouldn't decrease the total
+            self.total_secs += (now - start).num_seconds().max(0);
+        }
+
+        self.last_seen = None;
+
+        self
+    }
+
+    /// Total voice time including the current session
+    pub fn total_duration(&self, now: NaiveDateTime) -> Duration {
+        let current = self
+            .session_start
+            .map_or_else(Duration::zero, |start| now - start)
+            .max(Duration::zero());
+
+        Duration::seconds(self.total_secs) + current
+    }
+
+    pub async fn from_id(
+        ctx: &Context,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<Self>> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            VoiceActivity,
+            r#"
+                SELECT *
+                  FROM app_public.voice_activity
+                 WHERE guild_id = $1
+                   AND user_id = $2
+            "#,
+            guild_id.0 as i64,
+            user_id.0 as i64,
+        )
+        .fetch_optional(&pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Marks ongoing sessions of members still in voice as seen
+    pub async fn heartbeat(
+        ctx: &Context,
+        guild_id: GuildId,
+        user_ids: &[UserId],
+        now: NaiveDateTime,
+    ) -> Result<()> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        let user_ids: Vec<i64> = user_ids.iter().map(|id| id.0 as i64).collect();
+
+        sqlx::query!(
+            r#"
+                UPDATE app_public.voice_activity
+                   SET last_seen = $3
+                 WHERE guild_id = $1
+                   AND user_id = ANY($2)
+                   AND session_start IS NOT NULL
+            "#,
+            guild_id.0 as i64,
+            &user_ids,
+            now,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Ends sessions of members that aren't in voice anymore, e.g. if they
+    /// left while sushii was offline. Sessions only count up to when they were
+    /// last seen
+    pub async fn end_stale_sessions(
+        ctx: &Context,
+        guild_id: GuildId,
+        in_voice: &[UserId],
+    ) -> Result<u64> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        let in_voice: Vec<i64> = in_voice.iter().map(|id| id.0 as i64).collect();
+
+        sqlx::query!(
+            r#"
+                UPDATE app_public.voice_activity
+                   SET total_secs = total_secs + GREATEST(
+                           EXTRACT(EPOCH FROM COALESCE(last_seen, session_start) - session_start),
+                           0
+                       )::BIGINT,
+                       session_start = NULL,
+                       last_seen = NULL
+                 WHERE guild_id = $1
+                   AND session_start IS NOT NULL
+                   AND NOT (user_id = ANY($2))
+            "#,
+            guild_id.0 as i64,
+            &in_voice,
+        )
+        .execute(&pool)
+        .await
+        .map(|res| res.rows_affected())
+        .map_err(Into::into)
+    }
+
+    pub async fn save(&self, ctx: &Context) -> Result<Self> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+
+        sqlx::query_as!(
+            VoiceActivity,
+            r#"
+                INSERT INTO app_public.voice_activity
+                     VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (guild_id, user_id)
+                  DO UPDATE
+                        SET total_secs = $3,
+                            session_start = $4,
+                            last_seen = $5
+                  RETURNING *
+            "#,
+            self.guild_id,
+            self.user_id,
+            self.total_secs,
+            self.session_start,
+            self.last_seen,
+        )
+        .fetch_one(&pool)
+        .await
+        .map_err(Into::into)
+    }
+}
+
+#[test]
+fn tracks_voice_sessions() {
+    use chrono::NaiveDate;
+
+    let start = NaiveDate::from_ymd(2021, 1, 1).and_hms(0, 0, 0);
+
+    let activity = VoiceActivity::new(GuildId(1), UserId(2))
+        .start_session(start)
+        // Moving channels doesn't restart the session
+        .start_session(start + Duration::minutes(10));
+
+    assert_eq!(
+        activity.total_duration(start + Duration::minutes(30)),
+        Duration::minutes(30)
+    );
+
+    let activity = activity
+        .end_session(start + Duration::hours(1))
+        .start_session(start + Duration::hours(2))
+        .end_session(start + Duration::hours(3));
+
+    assert_eq!(activity.total_secs, 2 * 60 * 60);
+    assert!(activity.session_start.is_none());
+    assert!(activity.last_seen.is_none());
+}
@@ -0,0 +1,78 @@
+use chrono::{naive::NaiveDateTime, offset::Utc};
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::error::Result;
+use crate::keys::DbPool;
+
+/// A change to a user's XP made by a moderator
+#[derive(Deserialize, Serialize, sqlx::FromRow, Clone, Debug)]
+pub struct XpAdjustment {
+    pub id: i64,
+    pub guild_id: i64,
+    /// User whose XP was changed, None if all users in the guild were reset,
+    /// imported or changed by a season ending
+    pub user_id: Option<i64>,
+    pub moderator_id: i64,
+    /// add, remove, set, reset, import or season
+    pub action: String,
+    pub old_xp: Option<i64>,
+    pub new_xp: Option<i64>,
+    pub created_at: NaiveDateTime,
+}
+
+impl XpAdjustment {
+    pub fn new(guild_id: GuildId, moderator_id: UserId, action: &str) -> Self {
+        XpAdjustment {
+            // Set by the database when saved
+            id: 0,
+            guild_id: guild_id.0 as i64,
+            user_id: None,
+            moderator_id: moderator_id.0 as i64,
+            action: action.into(),
+            old_xp: None,
+            new_xp: None,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    /// Sets the user and their XP before and after the change
+    pub fn user(mut self, user_id: UserId, old_xp: i64, new_xp: i64) -> Self {
+        self.user_id = Some(user_id.0 as i64);
+        self.old_xp = Some(old_xp);
+        self.new_xp = Some(new_xp);
+
+        self
+    }
+
+    pub async fn save(&self, ctx: &Context) -> Result<Self> {
+        let pool = ctx.data.read().await.get::<DbPool>().cloned().unwrap();
+        let mut conn = pool.acquire().await?;
+
+        self.save_with(&mut conn).await
+    }
+
+    /// Saves with an existing connection, e.g. a transaction the adjustment
+    /// is part of
+    pub async fn save_with(&self, conn: &mut sqlx::PgConnection) -> Result<Self> {
+        sqlx::query_as!(
+            XpAdjustment,
+            r#"
+                INSERT INTO app_public.xp_adjustments (guild_id, user_id, moderator_id, action, old_xp, new_xp, created_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7)
+                  RETURNING *
+            "#,
+            self.guild_id,
+            self.user_id,
+            self.moderator_id,
+            self.action,
+            self.old_xp,
+            self.new_xp,
+            self.created_at,
+        )
+        .fetch_one(conn)
+        .await
+        .map_err(Into::into)
+    }
+}
@@ -1,4 +1,9 @@
-use serenity::model::id::GuildId;
+use chrono::naive::NaiveDateTime;
+use serenity::model::{
+    guild::Emoji,
+    id::{EmojiId, GuildId, UserId},
+};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use super::sql::GuildConfig;
@@ -6,4 +11,9 @@ use super::sql::GuildConfig;
 #[derive(Default, Clone)]
 pub struct SushiiCache {
     pub guilds: Arc<dashmap::DashMap<GuildId, GuildConfig>>,
+    /// Guild emojis before the latest update, since the serenity cache is
+    /// already updated when the event handler runs
+    pub guild_emojis: Arc<dashmap::DashMap<GuildId, HashMap<EmojiId, Emoji>>>,
+    /// Members earning voice XP and when they were last credited
+    pub voice_xp: Arc<dashmap::DashMap<(GuildId, UserId), NaiveDateTime>>,
 }
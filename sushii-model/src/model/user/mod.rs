@@ -1,9 +1,13 @@
 pub mod user_level;
 
+#[cfg(feature = "graphql")]
+pub mod xp_source;
 #[cfg(feature = "graphql")]
 pub mod xp_timeframe;
 
 pub use self::user_level::UserLevelProgress;
 
+#[cfg(feature = "graphql")]
+pub use self::xp_source::XpSource;
 #[cfg(feature = "graphql")]
 pub use self::xp_timeframe::TimeFrame;
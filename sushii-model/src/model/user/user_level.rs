@@ -45,15 +45,44 @@ impl UserLevelProgress {
     }
 }
 
+/// Total XP required for a level, None if it overflows
+pub fn checked_next_level(level: i64) -> Option<i64> {
+    level
+        .checked_mul(level)?
+        .checked_sub(level)?
+        .checked_mul(50)
+}
+
+/// Total XP required for a level, saturating at i64::MAX
 pub fn next_level(level: i64) -> i64 {
-    50 * (level.pow(2)) - (50 * level)
+    checked_next_level(level).unwrap_or(i64::MAX)
 }
 
 pub fn get_level(xp: i64) -> i64 {
     let mut level = 0;
-    while next_level(level + 1) <= xp {
+    while checked_next_level(level + 1).map_or(false, |next| next <= xp) {
         level += 1;
     }
 
     level
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_level_overflow() {
+        assert_eq!(checked_next_level(0), Some(0));
+        assert_eq!(checked_next_level(2), Some(100));
+        assert_eq!(checked_next_level(25), Some(30000));
+        assert_eq!(checked_next_level(i64::MAX), None);
+
+        assert_eq!(next_level(3), 300);
+        assert_eq!(next_level(i64::MAX), i64::MAX);
+
+        assert_eq!(get_level(0), 1);
+        assert_eq!(get_level(299), 2);
+        assert_eq!(get_level(300), 3);
+    }
+}
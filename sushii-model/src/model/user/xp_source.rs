@@ -0,0 +1,7 @@
+#[derive(juniper::GraphQLEnum, Debug, Copy, Clone)]
+#[graphql(description = "Where XP was earned, text and voice XP are only counted all time")]
+pub enum XpSource {
+    Combined,
+    Text,
+    Voice,
+}